@@ -1,4 +1,25 @@
+use super::approvals::ApprovalRegistry;
+use super::discovery::{CodexLaunch, discover_codex_launch};
+use super::error::CodexiaError;
+use super::exec_progress::ExecProgressRegistry;
+use super::framing::{BATCH_FLUSH_INTERVAL, FlushPolicy, StdinOptions};
+use super::launch_args::LaunchArgs;
+use super::line_reader::BoundedLines;
+use super::notification::{Decoded, UNKNOWN_NOTIFICATION_EVENT, decode as decode_notification};
+use super::patches::PatchRegistry;
+use super::message_queue::MessageQueue;
+use super::questions::QuestionRegistry;
+use super::recent::RecentEvents;
+use super::redact::redact_url;
+use super::retry::RetryRegistry;
 use super::server_request::handle_server_request;
+use super::session_log::SessionLogs;
+use super::session_usage::UsageRegistry;
+use super::spawn::app_server_command;
+use super::stderr_log::{LogLevel, error_event, parse_stderr_line};
+use super::settings::{CodexConfig, load_codex_config};
+use super::tee::TeeRegistry;
+use super::threads::{PendingStarts, ThreadRegistry};
 use codexia_shared::event_sink::EventSink;
 use codexia_db::automation_runs::sync_automation_run_status;
 use codex_app_server_protocol::{
@@ -6,55 +27,285 @@ use codex_app_server_protocol::{
     JSONRPCMessage, JSONRPCResponse, RequestId, ServerNotification, ServerRequest,
     ItemStartedNotification, ItemCompletedNotification, ThreadItem
 };
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{ChildStdin, Command};
-use tokio::sync::{Mutex, oneshot};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncWriteExt, BufReader, BufWriter};
+use tokio::process::{Child, ChildStdin};
+use tokio::sync::{Mutex, MutexGuard, Notify, Semaphore, oneshot};
 
-use codex_finder::discover_codex_command;
+/// How long shutdown waits for turn interrupts and for the process to exit
+/// before it is killed.
+pub(crate) const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
 
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+/// How long the app-server gets to exit after SIGTERM before it is killed.
+#[cfg(unix)]
+const TERMINATE_GRACE: Duration = Duration::from_secs(1);
+
+/// How [`CodexAppServer::shutdown`] ended the app-server process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShutdownOutcome {
+    /// It exited on its own once stdin closed, with rollouts flushed.
+    Graceful,
+    /// It only exited after SIGTERM.
+    Terminated,
+    /// It had to be killed, so the last rollout lines may be missing.
+    Killed,
+    /// No process was left to stop.
+    NotRunning,
+}
+
+impl ShutdownOutcome {
+    pub fn is_forced(self) -> bool {
+        matches!(self, Self::Terminated | Self::Killed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ShutdownReport {
+    /// Threads that were open.
+    pub sessions: usize,
+    pub outcome: ShutdownOutcome,
+}
 
 pub struct CodexAppServer {
-    stdin: Mutex<ChildStdin>,
+    stdin: Mutex<Option<BufWriter<ChildStdin>>>,
+    stdin_options: StdinOptions,
+    /// Priority writes waiting for stdin. While non-zero, normal writes step
+    /// aside so an interrupt is never queued behind a backlog of requests.
+    priority_writes: AtomicUsize,
+    priority_done: Notify,
+    /// One permit per normal write allowed to wait for stdin; see
+    /// [`StdinOptions::max_queued_writes`].
+    write_slots: Semaphore,
+    child: Mutex<Option<Child>>,
     pending: Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>,
     next_id: AtomicU64,
+    /// Set once [`CodexAppServer::shutdown`] starts, so the stdout reader can
+    /// tell a requested exit from a crash.
+    shutdown_requested: AtomicBool,
+    /// Set once the app-server's stdout closes, so later sends fail at once
+    /// instead of writing to a dead pipe.
+    exited: AtomicBool,
+    config: std::sync::RwLock<CodexConfig>,
+    launch: CodexLaunch,
+    launch_args: LaunchArgs,
+    /// OS process id of the spawned app-server.
+    pid: Option<u32>,
+    spawned_at: Instant,
+    pub threads: ThreadRegistry,
+    pub starts: PendingStarts,
+    pub approvals: ApprovalRegistry,
+    pub tees: TeeRegistry,
+    pub patches: PatchRegistry,
+    pub recent: RecentEvents,
+    pub retries: RetryRegistry,
+    pub session_logs: SessionLogs,
+    pub exec_progress: ExecProgressRegistry,
+    pub questions: QuestionRegistry,
+    pub usage: UsageRegistry,
+    pub messages: MessageQueue,
+}
+
+const EXITED: &str = "codex app-server has exited; reconnect to continue the session";
+
+pub fn session_exited_event(thread_id: &str) -> String {
+    super::utils::event_name("session-exited", thread_id)
+}
+
+/// The JSON-RPC response answering server request `id`, as written to stdin.
+pub(crate) fn response_message(id: RequestId, result: Value) -> Result<Value, String> {
+    serde_json::to_value(JSONRPCResponse { id, result }).map_err(|e| e.to_string())
+}
+
+/// Whether `child` exits within `timeout`.
+async fn wait_for_exit(child: &mut Child, timeout: Duration) -> bool {
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) => {
+            log::info!("codex app-server exited: {}", status);
+            true
+        }
+        Ok(Err(err)) => {
+            log::warn!("codex app-server wait failed: {}", err);
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(unix)]
+fn send_sigterm(child: &Child) -> bool {
+    let Some(pid) = child.id().and_then(|pid| libc::pid_t::try_from(pid).ok()) else {
+        return false;
+    };
+    // SAFETY: kill(2) takes no pointers; `pid` is our own, not yet reaped, child.
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        return true;
+    }
+    log::warn!(
+        "Failed to send SIGTERM to codex app-server: {}",
+        std::io::Error::last_os_error()
+    );
+    false
+}
+
+/// Gives `child`, whose stdin is already closed, `timeout` to exit, then
+/// terminates it on Unix and kills it as a last resort.
+async fn stop_child(child: &mut Child, timeout: Duration) -> ShutdownOutcome {
+    if wait_for_exit(child, timeout).await {
+        return ShutdownOutcome::Graceful;
+    }
+    #[cfg(unix)]
+    {
+        log::warn!("codex app-server did not exit in {:?}, sending SIGTERM", timeout);
+        if send_sigterm(child) && wait_for_exit(child, TERMINATE_GRACE).await {
+            return ShutdownOutcome::Terminated;
+        }
+    }
+    log::warn!("codex app-server is still running, killing it");
+    if let Err(err) = child.kill().await {
+        log::error!("Failed to kill codex app-server: {}", err);
+    }
+    ShutdownOutcome::Killed
+}
+
+/// Exit code and, on Unix, the terminating signal of a finished process.
+fn exit_details(status: &std::process::ExitStatus) -> (Option<i32>, Option<i32>) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        (status.code(), status.signal())
+    }
+    #[cfg(not(unix))]
+    {
+        (status.code(), None)
+    }
+}
+
+/// Keeps [`CodexAppServer::priority_writes`] accurate even when a priority
+/// write fails or is cancelled.
+struct PriorityWrite<'a>(&'a CodexAppServer);
+
+impl Drop for PriorityWrite<'_> {
+    fn drop(&mut self) {
+        self.0.priority_writes.fetch_sub(1, Ordering::SeqCst);
+        self.0.priority_done.notify_waiters();
+    }
 }
 
 impl CodexAppServer {
+    /// Locks stdin for a normal write, yielding to pending priority writes.
+    async fn lock_stdin(&self) -> MutexGuard<'_, Option<BufWriter<ChildStdin>>> {
+        loop {
+            // Registered before the check so a priority write finishing in
+            // between is not missed.
+            let priority_done = self.priority_done.notified();
+            if self.priority_writes.load(Ordering::SeqCst) == 0 {
+                let guard = self.stdin.lock().await;
+                if self.priority_writes.load(Ordering::SeqCst) == 0 {
+                    return guard;
+                }
+            }
+            priority_done.await;
+        }
+    }
+
+    /// Fails right away when [`StdinOptions::max_queued_writes`] writes are
+    /// already waiting, so a flood of sends cannot pile up without bound.
     async fn write_message(&self, value: Value) -> Result<(), String> {
-        let mut stdin = self.stdin.lock().await;
-        let mut line = serde_json::to_string(&value).map_err(|e| e.to_string())?;
-        line.push('\n');
+        let _slot = self.write_slots.try_acquire().map_err(|_| {
+            format!(
+                "codex is not keeping up: {} messages are already waiting to be sent",
+                self.stdin_options.max_queued_writes
+            )
+        })?;
+        let guard = self.lock_stdin().await;
+        self.write_locked(guard, value, false).await
+    }
+
+    /// Writes ahead of every queued normal write; only the write already in
+    /// progress is waited for. Always flushed, whatever the flush policy.
+    async fn write_priority_message(&self, value: Value) -> Result<(), String> {
+        self.priority_writes.fetch_add(1, Ordering::SeqCst);
+        let _pending = PriorityWrite(self);
+        let guard = self.stdin.lock().await;
+        self.write_locked(guard, value, true).await
+    }
+
+    /// The whole message is written while `guard` is held, so concurrent
+    /// sends never interleave on the pipe.
+    async fn write_locked(
+        &self,
+        mut guard: MutexGuard<'_, Option<BufWriter<ChildStdin>>>,
+        value: Value,
+        flush: bool,
+    ) -> Result<(), String> {
+        if self.exited.load(Ordering::SeqCst) {
+            return Err(EXITED.to_string());
+        }
+        let stdin = guard
+            .as_mut()
+            .ok_or_else(|| "codex app-server has been shut down".to_string())?;
+        let message = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+        self.session_logs.outgoing(&value);
         stdin
-            .write_all(line.as_bytes())
+            .write_all(&self.stdin_options.framing.encode(&message))
             .await
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?;
+        if flush || self.stdin_options.flush_policy == FlushPolicy::PerMessage {
+            stdin.flush().await.map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Flushes writes buffered under [`FlushPolicy::Batched`].
+    async fn flush_stdin(&self) -> Result<(), String> {
+        match self.stdin.lock().await.as_mut() {
+            Some(stdin) => stdin.flush().await.map_err(|e| e.to_string()),
+            None => Err("codex app-server has been shut down".to_string()),
+        }
     }
 
     pub async fn send_request(&self, method: &str, params: Value) -> Result<Value, String> {
+        self.request(method, params, false).await
+    }
+
+    /// Like [`CodexAppServer::send_request`], but written to stdin before any
+    /// queued request. Used for `turn/interrupt`, so a session that is
+    /// flooding the app-server with requests can still be stopped.
+    pub async fn send_priority_request(&self, method: &str, params: Value) -> Result<Value, String> {
+        self.request(method, params, true).await
+    }
+
+    async fn request(&self, method: &str, params: Value, priority: bool) -> Result<Value, String> {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
         let (tx, rx) = oneshot::channel();
         self.pending.lock().await.insert(id, tx);
 
-        self.write_message(serde_json::json!({
+        let message = serde_json::json!({
             "id": id,
             "method": method,
             "params": params
-        }))
-        .await?;
+        });
+        let written = if priority {
+            self.write_priority_message(message).await
+        } else {
+            self.write_message(message).await
+        };
+        if let Err(err) = written {
+            self.pending.lock().await.remove(&id);
+            return Err(err);
+        }
 
         rx.await.map_err(|_| "request canceled".to_string())?
     }
 
     pub async fn send_response(&self, id: RequestId, result: Value) -> Result<(), String> {
-        let message = JSONRPCResponse { id, result };
-        let value = serde_json::to_value(message).map_err(|e| e.to_string())?;
-        self.write_message(value).await
+        self.write_message(response_message(id, result)?).await
     }
 
     pub async fn send_notification(
@@ -69,6 +320,90 @@ impl CodexAppServer {
         };
         self.write_message(value).await
     }
+
+    /// How the app-server was launched; paths sent to it go through
+    /// [`CodexLaunch::translate_path`].
+    pub fn launch(&self) -> &CodexLaunch {
+        &self.launch
+    }
+
+    /// The command line the app-server was spawned with, secrets redacted.
+    pub fn launch_args(&self) -> &LaunchArgs {
+        &self.launch_args
+    }
+
+    /// Process id of the app-server; with a spawn wrapper, the wrapper's.
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// How long ago the app-server was spawned.
+    pub fn uptime(&self) -> Duration {
+        self.spawned_at.elapsed()
+    }
+
+    pub fn config(&self) -> CodexConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Replaces the runtime settings; persisting them is up to the caller.
+    pub fn set_config(&self, config: CodexConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// Whether the app-server child process is still running.
+    pub async fn is_running(&self) -> bool {
+        match self.child.lock().await.as_mut() {
+            Some(child) => matches!(child.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+
+    /// Interrupts every in-flight turn, closes stdin so the app-server can
+    /// flush its rollouts and exit, and stops it if it does not exit within
+    /// [`CodexConfig::shutdown_timeout`]: SIGTERM first on Unix, then kill.
+    pub async fn shutdown(&self) -> ShutdownReport {
+        self.shutdown_requested.store(true, Ordering::SeqCst);
+        let sessions = self.threads.list().len();
+        let active_turns = self.threads.active_turns();
+
+        let interrupts = active_turns.iter().map(|(thread_id, turn_id)| {
+            self.send_priority_request(
+                "turn/interrupt",
+                serde_json::json!({ "threadId": thread_id, "turnId": turn_id }),
+            )
+        });
+        match tokio::time::timeout(SHUTDOWN_TIMEOUT, futures::future::join_all(interrupts)).await {
+            Ok(results) => {
+                for ((thread_id, _), result) in active_turns.iter().zip(results) {
+                    if let Err(err) = result {
+                        log::warn!("codex shutdown: failed to interrupt {}: {}", thread_id, err);
+                    }
+                }
+            }
+            Err(_) => log::warn!("codex shutdown: timed out interrupting active turns"),
+        }
+
+        self.priority_writes.fetch_add(1, Ordering::SeqCst);
+        let closing = PriorityWrite(self);
+        if let Some(mut stdin) = self.stdin.lock().await.take() {
+            let _ = stdin.flush().await;
+        }
+        drop(closing);
+
+        let outcome = match self.child.lock().await.take() {
+            Some(mut child) => stop_child(&mut child, self.config().shutdown_timeout()).await,
+            None => ShutdownOutcome::NotRunning,
+        };
+
+        log::info!(
+            "codex shutdown: closed {} sessions ({} with an active turn), {:?}",
+            sessions,
+            active_turns.len(),
+            outcome
+        );
+        ShutdownReport { sessions, outcome }
+    }
 }
 
 #[derive(Clone)]
@@ -92,41 +427,182 @@ impl CodexInitializationState {
     }
 }
 
-pub async fn connect_codex(event_sink: Arc<dyn EventSink>) -> Result<Arc<CodexAppServer>, String> {
-    log::info!("Connecting to codex app-server");
-    let codex_bin =
-        discover_codex_command().ok_or_else(|| "Unable to locate codex binary".to_string())?;
+/// The arguments `codex app-server` is started with under `config`. Invalid
+/// settings are logged and left out instead of failing the launch.
+pub fn build_args(config: &CodexConfig) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(provider) = &config.oss_provider {
+        match provider.config_args() {
+            Ok(provider_args) => {
+                log::info!(
+                    "codex app-server OSS provider {} at {}{}",
+                    provider.provider_id,
+                    redact_url(&provider.base_url),
+                    if provider.api_key.is_some() { " (API key set)" } else { "" }
+                );
+                args.extend(provider_args);
+            }
+            Err(err) => log::warn!("Ignoring OSS provider: {}", err),
+        }
+    }
+    match config.reasoning_args() {
+        Ok(reasoning_args) => args.extend(reasoning_args),
+        Err(err) => log::warn!("Ignoring reasoning settings: {}", err),
+    }
+    match config.provider_args() {
+        Ok(provider_args) => args.extend(provider_args),
+        Err(err) => log::warn!("Ignoring model provider: {}", err),
+    }
+    match config.custom_args() {
+        Ok(custom_args) => args.extend(custom_args),
+        Err(err) => log::warn!("Ignoring custom_args: {}", err),
+    }
+    args
+}
 
-    let mut command = {
-        let mut cmd = Command::new(codex_bin);
-        cmd.arg("app-server");
-        cmd
-    };
+/// The app-server command for `config`, with its environment but without
+/// stdio, so it can be inspected without being spawned.
+fn launch_command(launch: &CodexLaunch, config: &CodexConfig) -> Result<tokio::process::Command, CodexiaError> {
+    let args = build_args(config);
+    let mut command = app_server_command(launch, config.spawn_strategy.unwrap_or_default(), &args)?;
+    if let Some(proxy) = config.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+        log::info!("codex app-server proxy: {}", redact_url(proxy));
+    }
+    let launch_env = config.launch_env();
+    if !launch_env.is_empty() {
+        if launch.is_wsl() {
+            // wsl.exe only forwards variables listed in WSLENV.
+            let names: Vec<&str> = launch_env.iter().map(|(key, _)| key.as_str()).collect();
+            let wslenv = match std::env::var("WSLENV") {
+                Ok(existing) if !existing.is_empty() => format!("{}:{}", existing, names.join(":")),
+                _ => names.join(":"),
+            };
+            command.env("WSLENV", wslenv);
+        }
+        command.envs(launch_env);
+    }
+    Ok(command)
+}
+
+/// What [`connect_codex`] would run under `config`, with secrets redacted.
+/// Nothing is spawned besides the discovery probes.
+pub async fn build_codex_command(config: &CodexConfig) -> Result<LaunchArgs, CodexiaError> {
+    let launch = discover_codex_launch().await.ok_or(CodexiaError::NotInstalled)?;
+    let command = launch_command(&launch, config)?;
+    Ok(LaunchArgs::from_command(&command))
+}
 
+pub async fn connect_codex(
+    event_sink: Arc<dyn EventSink>,
+) -> Result<Arc<CodexAppServer>, CodexiaError> {
+    log::info!("Connecting to codex app-server");
+    let launch = discover_codex_launch().await.ok_or(CodexiaError::NotInstalled)?;
+
+    let config = load_codex_config();
+    let mut command = launch_command(&launch, &config)?;
     command.stdin(std::process::Stdio::piped());
     command.stdout(std::process::Stdio::piped());
     command.stderr(std::process::Stdio::piped());
-    #[cfg(target_os = "windows")]
-    command.creation_flags(CREATE_NO_WINDOW);
+    let launch_args = LaunchArgs::from_command(&command);
+    log::debug!("codex app-server command: {}", launch_args.command_line);
 
-    let mut child = command.spawn().map_err(|e| e.to_string())?;
-    let stdin = child.stdin.take().ok_or("missing stdin")?;
-    let stdout = child.stdout.take().ok_or("missing stdout")?;
-    let stderr = child.stderr.take().ok_or("missing stderr")?;
+    let mut child = command
+        .spawn()
+        .map_err(|e| CodexiaError::SpawnFailed(e.to_string()))?;
+    let spawned_at = Instant::now();
+    let pid = child.id();
+    let missing = |stream: &str| CodexiaError::SpawnFailed(format!("missing {}", stream));
+    let stdin = child.stdin.take().ok_or_else(|| missing("stdin"))?;
+    let stdout = child.stdout.take().ok_or_else(|| missing("stdout"))?;
+    let stderr = child.stderr.take().ok_or_else(|| missing("stderr"))?;
+
+    let max_line_bytes = config.max_stdout_line_bytes();
+    let stdin_options = StdinOptions::from_env();
+    if stdin_options != StdinOptions::default() {
+        log::info!("codex app-server stdin options: {:?}", stdin_options);
+    }
 
     let client = Arc::new(CodexAppServer {
-        stdin: Mutex::new(stdin),
+        stdin: Mutex::new(Some(BufWriter::new(stdin))),
+        stdin_options,
+        priority_writes: AtomicUsize::new(0),
+        priority_done: Notify::new(),
+        write_slots: Semaphore::new(stdin_options.max_queued_writes),
+        child: Mutex::new(Some(child)),
         pending: Mutex::new(HashMap::new()),
         next_id: AtomicU64::new(1),
+        shutdown_requested: AtomicBool::new(false),
+        exited: AtomicBool::new(false),
+        config: std::sync::RwLock::new(config),
+        launch,
+        launch_args,
+        pid,
+        spawned_at,
+        threads: ThreadRegistry::default(),
+        starts: PendingStarts::default(),
+        approvals: ApprovalRegistry::default(),
+        tees: TeeRegistry::default(),
+        patches: PatchRegistry::default(),
+        recent: RecentEvents::default(),
+        retries: RetryRegistry::default(),
+        session_logs: SessionLogs::from_env(),
+        exec_progress: ExecProgressRegistry::default(),
+        questions: QuestionRegistry::default(),
+        usage: UsageRegistry::default(),
+        messages: MessageQueue::default(),
     });
     log::info!("Connected to codex app-server");
 
+    if stdin_options.flush_policy == FlushPolicy::Batched {
+        let client_clone = Arc::clone(&client);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(BATCH_FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                if client_clone.flush_stdin().await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    super::heartbeat::spawn(&client, &event_sink);
+
     // Spawn stdout reader task
     let client_clone = Arc::clone(&client);
     let event_sink_clone = Arc::clone(&event_sink);
     tokio::spawn(async move {
-        let mut lines = BufReader::new(stdout).lines();
+        let mut lines = BoundedLines::new(BufReader::new(stdout), max_line_bytes);
         while let Ok(Some(line)) = lines.next_line().await {
+            if line.dropped > 0 {
+                let bytes = line.text.len() + line.dropped;
+                log::warn!(
+                    "codex app-server sent a {} byte line, over the {} byte limit; dropping it",
+                    bytes,
+                    max_line_bytes
+                );
+                // A dropped response would leave its caller waiting forever.
+                let waiter = match super::line_reader::truncated_response_id(&line.text) {
+                    Some(id) => client_clone.pending.lock().await.remove(&id),
+                    None => None,
+                };
+                if let Some(tx) = waiter {
+                    let _ = tx.send(Err(format!(
+                        "codex response was {} bytes, over the {} byte limit; raise max_stdout_line_bytes",
+                        bytes, max_line_bytes
+                    )));
+                }
+                event_sink_clone.emit(
+                    "codex:lineTruncated",
+                    serde_json::json!({
+                        "bytes": bytes,
+                        "limit": max_line_bytes,
+                        "threadId": super::session_log::raw_thread_id(&line.text),
+                    }),
+                );
+                continue;
+            }
+            let line = line.text;
             if line.trim().is_empty() {
                 continue;
             }
@@ -135,6 +611,7 @@ pub async fn connect_codex(event_sink: Arc<dyn EventSink>) -> Result<Arc<CodexAp
                 Ok(v) => v,
                 Err(err) => {
                     log::warn!("codex:parseError: {:?}", err);
+                    client_clone.session_logs.parse_failure(&line, &err.to_string());
                     event_sink_clone.emit(
                         "codex:parseError",
                         serde_json::json!({ "error": err.to_string(), "raw": line }),
@@ -143,6 +620,8 @@ pub async fn connect_codex(event_sink: Arc<dyn EventSink>) -> Result<Arc<CodexAp
                 }
             };
 
+            client_clone.session_logs.incoming(&value);
+
             // Classify message type
             if let Ok(message) = serde_json::from_value::<JSONRPCMessage>(value.clone()) {
                 match message {
@@ -176,71 +655,173 @@ pub async fn connect_codex(event_sink: Arc<dyn EventSink>) -> Result<Arc<CodexAp
                     JSONRPCMessage::Request(request) => {
                         // Handle server requests
                         if let Ok(server_request) = ServerRequest::try_from(request) {
-                            handle_server_request(&event_sink_clone, server_request).await;
+                            handle_server_request(&client_clone, &event_sink_clone, server_request).await;
                         }
                     }
                     JSONRPCMessage::Notification(notification) => {
                         let method = notification.method.clone();
-                        if let Ok(server_notification) = ServerNotification::try_from(notification) {
-                            
-                            match &server_notification {
-                                ServerNotification::ReasoningTextDelta(_) | 
-                                ServerNotification::ReasoningSummaryPartAdded(_) | 
-                                ServerNotification::ReasoningSummaryTextDelta(_) => continue,
-                                
-                                ServerNotification::ItemStarted(ItemStartedNotification { item: ThreadItem::Reasoning { .. }, .. }) |
-                                ServerNotification::ItemCompleted(ItemCompletedNotification { item: ThreadItem::Reasoning { .. }, .. }) => continue,
-                                
-                                _ => {}
+                        let server_notification = match decode_notification(notification) {
+                            Decoded::Typed(server_notification) => server_notification,
+                            Decoded::Drifted { payload, error } => {
+                                log::warn!("codex:notification {} did not match the protocol, forwarding as received: {}", method, error);
+                                forward_notification(&client_clone, &event_sink_clone, payload);
+                                continue;
                             }
+                            Decoded::Unknown { payload } => {
+                                log::info!("codex:notification with unknown method {}", method);
+                                client_clone.recent.observe(&payload);
+                                client_clone.tees.observe(&payload);
+                                event_sink_clone.emit(UNKNOWN_NOTIFICATION_EVENT, payload);
+                                continue;
+                            }
+                        };
 
-                            match &server_notification {
-                                ServerNotification::RawResponseItemCompleted(_) |
-                                ServerNotification::AgentMessageDelta(_) |
-                                ServerNotification::ThreadTokenUsageUpdated(_) |
-                                ServerNotification::AccountRateLimitsUpdated(_) |
-                                ServerNotification::PlanDelta(_) => {}
-                                
-                                _ => {
-                                    log::info!("codex:notification: {:?}", method);
-                                }
+                        match &server_notification {
+                            ServerNotification::ReasoningTextDelta(_) | 
+                            ServerNotification::ReasoningSummaryPartAdded(_) | 
+                            ServerNotification::ReasoningSummaryTextDelta(_) => continue,
+                            
+                            ServerNotification::ItemStarted(ItemStartedNotification { item: ThreadItem::Reasoning { .. }, .. }) |
+                            ServerNotification::ItemCompleted(ItemCompletedNotification { item: ThreadItem::Reasoning { .. }, .. }) => continue,
+                            
+                            _ => {}
+                        }
+
+                        match &server_notification {
+                            ServerNotification::RawResponseItemCompleted(_) |
+                            ServerNotification::AgentMessageDelta(_) |
+                            ServerNotification::ThreadTokenUsageUpdated(_) |
+                            ServerNotification::AccountRateLimitsUpdated(_) |
+                            ServerNotification::PlanDelta(_) => {}
+                            
+                            _ => {
+                                log::info!("codex:notification: {:?}", method);
                             }
+                        }
 
-                            match serde_json::to_value(&server_notification) {
-                                Ok(payload) => {
-                                    sync_automation_run_status(&payload);
-                                    event_sink_clone.emit("codex:notification", payload);
-                                }
-                                Err(err) => {
-                                    log::warn!("codex:notification (serializeError): {:?}", err);
-                                }
+                        match serde_json::to_value(&server_notification) {
+                            Ok(payload) => forward_notification(&client_clone, &event_sink_clone, payload),
+                            Err(err) => {
+                                log::warn!("codex:notification (serializeError): {:?}", err);
                             }
                         }
                     }
                 }
             }
         }
+
+        report_stdout_closed(&client_clone, &event_sink_clone).await;
     });
 
     // Spawn stderr reader task
+    let client_clone = Arc::clone(&client);
     let event_sink_clone = Arc::clone(&event_sink);
     tokio::spawn(async move {
-        let mut lines = BufReader::new(stderr).lines();
+        // Stderr is only shown, so an oversized line is kept cut short.
+        let mut lines = BoundedLines::new(BufReader::new(stderr), max_line_bytes);
         while let Ok(Some(line)) = lines.next_line().await {
+            let line = line.text;
             if line.trim().is_empty() {
                 continue;
             }
-            log::warn!("codex:stderr: {}", line);
-            event_sink_clone.emit(
-                "codex:stderr",
-                serde_json::json!({ "message": line }),
-            );
+            let entry = parse_stderr_line(&line);
+            match entry.level {
+                LogLevel::Error => log::error!("codex:stderr: {}", entry.message),
+                LogLevel::Warn => log::warn!("codex:stderr: {}", entry.message),
+                _ => log::info!("codex:stderr: {}", entry.message),
+            }
+            client_clone.tees.observe_stderr(&line);
+            let payload = match serde_json::to_value(&entry) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    log::warn!("codex:stderr (serializeError): {:?}", err);
+                    continue;
+                }
+            };
+            let thread_error = entry.session_id.as_deref().filter(|_| entry.level == LogLevel::Error);
+            if let Some(thread_id) = thread_error {
+                event_sink_clone.emit(&error_event(thread_id), payload.clone());
+            }
+            event_sink_clone.emit("codex:stderr", payload);
         }
     });
 
     Ok(client)
 }
 
+/// Updates the registries from a notification and emits it to the frontend.
+fn forward_notification(client: &Arc<CodexAppServer>, event_sink: &Arc<dyn EventSink>, payload: Value) {
+    client.threads.observe(&payload);
+    super::thread::observe_started(client, event_sink.as_ref(), &payload);
+    client.recent.observe(&payload);
+    client.tees.observe(&payload);
+    client.patches.observe(&payload, &client.threads);
+    client.questions.observe(&payload);
+    sync_automation_run_status(&payload);
+    super::retry::observe(client, event_sink, &payload);
+    super::message_queue::observe(client, event_sink, &payload);
+    super::exec_progress::observe(client, event_sink.as_ref(), &payload);
+    super::session_usage::observe(client, event_sink.as_ref(), &payload);
+    event_sink.emit("codex:notification", payload);
+}
+
+/// Logs and emits `codex:closed` once the app-server's stdout ends, with the
+/// exit status when it can be collected, plus `codex-session-exited-<id>`
+/// for every thread it was running. Requests still waiting for a response
+/// fail, as do later sends.
+async fn report_stdout_closed(client: &CodexAppServer, event_sink: &Arc<dyn EventSink>) {
+    let expected = client.shutdown_requested.load(Ordering::SeqCst);
+    client.exited.store(true, Ordering::SeqCst);
+    for (_, tx) in client.pending.lock().await.drain() {
+        let _ = tx.send(Err(EXITED.to_string()));
+    }
+
+    // During shutdown the child has already been taken and waited on there.
+    let status = match client.child.lock().await.as_mut() {
+        Some(child) => match tokio::time::timeout(SHUTDOWN_TIMEOUT, child.wait()).await {
+            Ok(Ok(status)) => Some(status),
+            Ok(Err(err)) => {
+                log::warn!("codex app-server wait failed: {}", err);
+                None
+            }
+            Err(_) => None,
+        },
+        None => None,
+    };
+    let (code, signal) = status.as_ref().map(exit_details).unwrap_or((None, None));
+
+    let detail = match (code, signal) {
+        (Some(code), _) => format!("code {}", code),
+        (None, Some(signal)) => format!("signal {}", signal),
+        (None, None) => "status unknown".to_string(),
+    };
+    if expected || code == Some(0) {
+        log::info!("codex app-server stdout closed: clean exit ({})", detail);
+    } else {
+        log::error!("codex app-server stdout closed: unexpected termination ({})", detail);
+    }
+
+    for session in client.threads.list() {
+        event_sink.emit(
+            &session_exited_event(&session.thread_id),
+            serde_json::json!({
+                "threadId": session.thread_id,
+                "expected": expected,
+                "exitCode": code,
+                "signal": signal,
+            }),
+        );
+    }
+    event_sink.emit(
+        "codex:closed",
+        serde_json::json!({
+            "expected": expected,
+            "exitCode": code,
+            "signal": signal,
+        }),
+    );
+}
+
 pub async fn initialize_codex(
     codex: &CodexAppServer,
     event_sink: Arc<dyn EventSink>,
@@ -275,3 +856,49 @@ pub async fn initialize_codex(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_args_from_settings_skipping_invalid_ones() {
+        let config = CodexConfig {
+            reasoning_effort: Some("high".to_string()),
+            model_provider: Some("ollama".to_string()),
+            custom_args: vec!["--enable".to_string(), "rmcp_client".to_string()],
+            ..CodexConfig::default()
+        };
+        assert_eq!(
+            build_args(&config),
+            ["-c", "model_reasoning_effort=\"high\"", "-c", "model_provider=\"ollama\"", "--enable", "rmcp_client"]
+        );
+
+        let config = CodexConfig {
+            reasoning_summary: Some("verbose".to_string()),
+            custom_args: vec!["exec".to_string()],
+            ..config
+        };
+        assert_eq!(build_args(&config), ["-c", "model_provider=\"ollama\""]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stops_children_gracefully_or_by_force() {
+        let mut quick = tokio::process::Command::new("sh").args(["-c", "exit 0"]).spawn().unwrap();
+        assert_eq!(stop_child(&mut quick, Duration::from_secs(5)).await, ShutdownOutcome::Graceful);
+
+        let mut stuck = tokio::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let outcome = stop_child(&mut stuck, Duration::from_millis(50)).await;
+        assert_eq!(outcome, ShutdownOutcome::Terminated);
+        assert!(outcome.is_forced());
+
+        let mut stubborn = tokio::process::Command::new("sh")
+            .args(["-c", "trap '' TERM; sleep 30"])
+            .spawn()
+            .unwrap();
+        // Let the shell install its trap before it is signalled.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(stop_child(&mut stubborn, Duration::from_millis(50)).await, ShutdownOutcome::Killed);
+    }
+}
@@ -0,0 +1,550 @@
+//! Tracks approval requests the app-server is waiting on.
+//!
+//! Every command-execution and file-change approval is recorded when it
+//! arrives and removed once a decision is sent, so unanswered requests can be
+//! auto-declined after [`CodexConfig::approval_auto_deny_secs`]. Each
+//! decision is kept in a per-thread history, and also written to the
+//! session's sidecar with [`CodexConfig::persist_approval_history`].
+//!
+//! Alongside the raw `codex/approval-request`, each request is announced as
+//! `codex-approval-<thread_id>` in a form the UI can show as is.
+//!
+//! [`CodexConfig::approval_auto_deny_secs`]: crate::settings::CodexConfig
+//! [`CodexConfig::persist_approval_history`]: crate::settings::CodexConfig
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use codex_app_server_protocol::{FileChangeApprovalDecision, FileChangeRequestApprovalResponse, RequestId};
+use codexia_shared::event_sink::EventSink;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::app_server::CodexAppServer;
+
+/// Decisions kept in memory per thread; the oldest are dropped first.
+const MAX_HISTORY: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalKind {
+    CommandExecution,
+    FileChange,
+}
+
+/// A decision that applies to command and file-change approvals alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalDecision {
+    Allow,
+    Deny,
+    /// Allow, and stop asking for similar requests for the rest of the session.
+    AllowForSession,
+}
+
+impl ApprovalDecision {
+    /// The decision string both approval responses use on the wire.
+    pub fn as_protocol(self) -> &'static str {
+        match self {
+            Self::Allow => "accept",
+            Self::Deny => "decline",
+            Self::AllowForSession => "acceptForSession",
+        }
+    }
+}
+
+impl From<bool> for ApprovalDecision {
+    fn from(approved: bool) -> Self {
+        if approved { Self::Allow } else { Self::Deny }
+    }
+}
+
+pub fn approval_event(thread_id: &str) -> String {
+    crate::utils::event_name("approval", thread_id)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptKind {
+    Exec,
+    Patch,
+}
+
+/// What an approval dialog shows, independent of codex's request schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApprovalPrompt {
+    pub approval_id: RequestId,
+    pub thread_id: String,
+    pub kind: PromptKind,
+    /// The command to run; `None` for patches.
+    pub command: Option<String>,
+    /// Where the command runs, or the thread's working directory for patches.
+    pub cwd: Option<String>,
+    pub reason: Option<String>,
+    /// Files the patch adds, deletes or updates; empty for commands.
+    pub paths: Vec<String>,
+}
+
+/// The prompt for the approval request `request_id` with `params`, where a
+/// patch approval carries its preview as `patch`. `thread_cwd` stands in for
+/// a missing `cwd`.
+pub(crate) fn approval_prompt(
+    request_id: &RequestId,
+    kind: ApprovalKind,
+    params: &Value,
+    thread_cwd: Option<String>,
+) -> Option<ApprovalPrompt> {
+    let str_param = |key: &str| params.get(key).and_then(Value::as_str).map(str::to_string);
+    let paths = params
+        .pointer("/patch/files")
+        .and_then(Value::as_array)
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|f| f.get("path")?.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(ApprovalPrompt {
+        approval_id: request_id.clone(),
+        thread_id: str_param("threadId")?,
+        kind: match kind {
+            ApprovalKind::CommandExecution => PromptKind::Exec,
+            ApprovalKind::FileChange => PromptKind::Patch,
+        },
+        command: str_param("command"),
+        cwd: str_param("cwd").or(thread_cwd),
+        reason: str_param("reason"),
+        paths,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingApproval {
+    pub request_id: RequestId,
+    pub kind: ApprovalKind,
+    pub thread_id: Option<String>,
+    /// The item the approval is for, e.g. the `fileChange` holding the patch.
+    pub item_id: Option<String>,
+    /// The command to run, or codex's reason for a file change.
+    pub summary: Option<String>,
+    pub received_at: Instant,
+    /// Arrival order, since several requests can share an `Instant`.
+    seq: u64,
+}
+
+/// Who or what made an approval decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DecisionSource {
+    /// Answered individually in the UI.
+    User,
+    /// Part of an approve/decline-all.
+    Bulk,
+    /// Declined after [`CodexConfig::approval_auto_deny_secs`].
+    ///
+    /// [`CodexConfig::approval_auto_deny_secs`]: crate::settings::CodexConfig
+    AutoDeny,
+    /// Accepted with the rest of a preview-mode batch.
+    PreviewBatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    pub request_id: RequestId,
+    pub kind: ApprovalKind,
+    pub thread_id: Option<String>,
+    pub summary: Option<String>,
+    /// The decision sent to codex, e.g. `accept` or `decline`.
+    pub decision: String,
+    pub source: DecisionSource,
+    /// Seconds since the epoch.
+    pub decided_at: i64,
+}
+
+#[derive(Default)]
+pub struct ApprovalRegistry {
+    pending: Mutex<HashMap<RequestId, PendingApproval>>,
+    next_seq: AtomicU64,
+    history: Mutex<HashMap<String, Vec<ApprovalRecord>>>,
+}
+
+impl ApprovalRegistry {
+    pub fn insert(&self, request_id: RequestId, kind: ApprovalKind, params: &Value) {
+        let str_param = |key: &str| params.get(key).and_then(Value::as_str).map(str::to_string);
+        self.pending.lock().unwrap().insert(
+            request_id.clone(),
+            PendingApproval {
+                request_id,
+                kind,
+                thread_id: str_param("threadId"),
+                item_id: str_param("itemId"),
+                summary: str_param("command").or_else(|| str_param("reason")),
+                received_at: Instant::now(),
+                seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            },
+        );
+    }
+
+    /// Puts back a request whose decision could not be sent.
+    fn restore(&self, pending: PendingApproval) {
+        self.pending.lock().unwrap().insert(pending.request_id.clone(), pending);
+    }
+
+    /// Removes and returns the request, or `None` if it was already answered.
+    pub fn take(&self, request_id: &RequestId) -> Option<PendingApproval> {
+        self.pending.lock().unwrap().remove(request_id)
+    }
+
+    pub fn get(&self, request_id: &RequestId) -> Option<PendingApproval> {
+        self.pending.lock().unwrap().get(request_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<PendingApproval> {
+        self.pending.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Removes and returns every pending request of `thread_id`, oldest first.
+    pub fn take_for_thread(&self, thread_id: &str) -> Vec<PendingApproval> {
+        let mut pending = self.pending.lock().unwrap();
+        let ids: Vec<RequestId> = pending
+            .values()
+            .filter(|p| p.thread_id.as_deref() == Some(thread_id))
+            .map(|p| p.request_id.clone())
+            .collect();
+        let mut taken: Vec<PendingApproval> =
+            ids.iter().filter_map(|id| pending.remove(id)).collect();
+        taken.sort_by_key(|p| p.seq);
+        taken
+    }
+
+    fn record(&self, pending: &PendingApproval, result: &Value, source: DecisionSource) -> Option<ApprovalRecord> {
+        let thread_id = pending.thread_id.clone()?;
+        let decision = match result.get("decision") {
+            Some(Value::String(decision)) => decision.clone(),
+            Some(other) => other.to_string(),
+            None => result.to_string(),
+        };
+        let record = ApprovalRecord {
+            request_id: pending.request_id.clone(),
+            kind: pending.kind,
+            thread_id: Some(thread_id.clone()),
+            summary: pending.summary.clone(),
+            decision,
+            source,
+            decided_at: chrono::Utc::now().timestamp(),
+        };
+        let mut history = self.history.lock().unwrap();
+        let entries = history.entry(thread_id).or_default();
+        entries.push(record.clone());
+        if entries.len() > MAX_HISTORY {
+            entries.remove(0);
+        }
+        Some(record)
+    }
+
+    /// Decisions made in `thread_id`, oldest first.
+    pub fn history(&self, thread_id: &str) -> Vec<ApprovalRecord> {
+        self.history.lock().unwrap().get(thread_id).cloned().unwrap_or_default()
+    }
+
+    pub(crate) fn forget_history(&self, thread_id: &str) {
+        self.history.lock().unwrap().remove(thread_id);
+    }
+}
+
+/// Adds a sent decision to the thread's history, and to its sidecar when
+/// that is enabled.
+fn record_decision(codex: &CodexAppServer, pending: &PendingApproval, result: &Value, source: DecisionSource) {
+    let Some(record) = codex.approvals.record(pending, result, source) else {
+        return;
+    };
+    if !codex.config().persist_approval_history {
+        return;
+    }
+    let rollout = record
+        .thread_id
+        .as_deref()
+        .and_then(|thread_id| codex.threads.get(thread_id))
+        .and_then(|session| session.rollout_path);
+    if let Some(rollout) = rollout {
+        crate::session_meta::append_approval_record(&rollout, record);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalResult {
+    pub request_id: RequestId,
+    pub kind: ApprovalKind,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Sends an approval decision and stops tracking the request. Fails without
+/// sending anything when the request is no longer pending, e.g. after it was
+/// auto-declined, since codex would ignore the late answer.
+pub async fn respond_to_approval(
+    codex: &CodexAppServer,
+    request_id: RequestId,
+    result: Value,
+) -> Result<(), String> {
+    respond_with_source(codex, request_id, result, DecisionSource::User).await
+}
+
+pub(crate) async fn respond_with_source(
+    codex: &CodexAppServer,
+    request_id: RequestId,
+    result: Value,
+    source: DecisionSource,
+) -> Result<(), String> {
+    let pending = codex.approvals.take(&request_id);
+    if pending.is_none() && source == DecisionSource::User {
+        return Err(format!(
+            "No pending approval {:?}; it was already answered or has expired",
+            request_id
+        ));
+    }
+    if let Err(err) = codex.send_response(request_id, result.clone()).await {
+        // Still unanswered as far as codex knows, so keep it answerable.
+        if let Some(pending) = pending {
+            codex.approvals.restore(pending);
+        }
+        return Err(err);
+    }
+    if let Some(pending) = pending {
+        record_decision(codex, &pending, &result, source);
+    }
+    Ok(())
+}
+
+/// The `item/fileChange/requestApproval` result carrying `decision`.
+fn file_change_response(decision: FileChangeApprovalDecision) -> Result<Value, String> {
+    serde_json::to_value(FileChangeRequestApprovalResponse { decision }).map_err(|e| e.to_string())
+}
+
+/// Sends the decision on a patch approval. Fails instead of writing a
+/// response codex would discard when the request is no longer pending, e.g.
+/// after it was auto-declined or its turn was interrupted.
+pub async fn respond_to_file_change_approval(
+    codex: &CodexAppServer,
+    request_id: RequestId,
+    decision: FileChangeApprovalDecision,
+) -> Result<(), String> {
+    match codex.approvals.get(&request_id).map(|p| p.kind) {
+        Some(ApprovalKind::FileChange) => {}
+        Some(ApprovalKind::CommandExecution) => {
+            return Err(format!("Approval {:?} is for a command, not a file change", request_id));
+        }
+        None => {
+            return Err(format!(
+                "No pending file-change approval {:?}; it was already answered or has expired",
+                request_id
+            ));
+        }
+    }
+    let result = file_change_response(decision)?;
+    log::info!("codex:response: {}", result);
+    respond_to_approval(codex, request_id, result).await
+}
+
+/// Decisions made in `thread_id`, oldest first.
+pub fn get_approval_history(
+    codex: &CodexAppServer,
+    thread_id: &str,
+) -> Result<Vec<ApprovalRecord>, crate::error::CodexiaError> {
+    crate::thread::require_thread(codex, thread_id)?;
+    Ok(codex.approvals.history(thread_id))
+}
+
+/// Answers every approval `thread_id` is waiting on with the same decision.
+/// The pending set is snapshotted first, so requests arriving meanwhile are
+/// left for the user. A request whose decision could not be sent stays
+/// pending and can still be answered; its result carries the error.
+pub async fn respond_to_all_pending(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    decision: ApprovalDecision,
+) -> Vec<ApprovalResult> {
+    let decision = json!({ "decision": decision.as_protocol() });
+    let pending = codex.approvals.take_for_thread(thread_id);
+
+    let sends = pending
+        .iter()
+        .map(|p| codex.send_response(p.request_id.clone(), decision.clone()));
+    let results = futures::future::join_all(sends).await;
+
+    pending
+        .into_iter()
+        .zip(results)
+        .map(|(p, result)| {
+            match &result {
+                Ok(()) => record_decision(codex, &p, &decision, DecisionSource::Bulk),
+                // Still unanswered as far as codex knows, so keep it answerable.
+                Err(_) => codex.approvals.restore(p.clone()),
+            }
+            (p, result)
+        })
+        .map(|(p, result)| ApprovalResult {
+            request_id: p.request_id,
+            kind: p.kind,
+            ok: result.is_ok(),
+            error: result.err(),
+        })
+        .collect()
+}
+
+/// Declines `request_id` after `timeout` unless a decision arrived first, and
+/// emits `codex/approval-timeout` when it does. If the decline cannot be
+/// sent, the request stays pending for the user.
+pub(crate) fn schedule_auto_deny(
+    codex: Arc<CodexAppServer>,
+    event_sink: Arc<dyn EventSink>,
+    request_id: RequestId,
+    timeout: Duration,
+) {
+    tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let Some(pending) = codex.approvals.take(&request_id) else {
+            return;
+        };
+        let decline = json!({ "decision": "decline" });
+
+        log::warn!(
+            "approval {:?} unanswered after {:?}, declining",
+            request_id,
+            timeout
+        );
+        if let Err(err) = codex.send_response(request_id.clone(), decline.clone()).await {
+            log::error!("Failed to auto-decline approval {:?}: {}", request_id, err);
+            codex.approvals.restore(pending);
+            return;
+        }
+        record_decision(&codex, &pending, &decline, DecisionSource::AutoDeny);
+
+        event_sink.emit(
+            "codex/approval-timeout",
+            json!({
+                "requestId": request_id,
+                "threadId": pending.thread_id,
+                "type": pending.kind,
+                "timeoutSecs": timeout.as_secs(),
+            }),
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_for_thread_only_drains_that_thread() {
+        let registry = ApprovalRegistry::default();
+        registry.insert(RequestId::Integer(1), ApprovalKind::CommandExecution, &json!({ "threadId": "t1" }));
+        registry.insert(RequestId::Integer(2), ApprovalKind::FileChange, &json!({ "threadId": "t2" }));
+        registry.insert(RequestId::Integer(3), ApprovalKind::FileChange, &json!({ "threadId": "t1" }));
+
+        let taken = registry.take_for_thread("t1");
+        let ids: Vec<RequestId> = taken.into_iter().map(|p| p.request_id).collect();
+        assert_eq!(ids, vec![RequestId::Integer(1), RequestId::Integer(3)]);
+        assert_eq!(registry.list().len(), 1);
+        assert!(registry.take(&RequestId::Integer(1)).is_none());
+    }
+
+    #[test]
+    fn records_decisions_per_thread() {
+        let registry = ApprovalRegistry::default();
+        registry.insert(
+            RequestId::Integer(1),
+            ApprovalKind::CommandExecution,
+            &json!({ "threadId": "t1", "command": "cargo test" }),
+        );
+        let pending = registry.take(&RequestId::Integer(1)).unwrap();
+        registry.record(&pending, &json!({ "decision": "accept" }), DecisionSource::User);
+        registry.record(&pending, &json!({ "decision": { "acceptWithExecpolicyAmendment": {} } }), DecisionSource::Bulk);
+
+        let history = registry.history("t1");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].summary.as_deref(), Some("cargo test"));
+        assert_eq!((history[0].decision.as_str(), history[0].source), ("accept", DecisionSource::User));
+        assert!(history[1].decision.starts_with('{'));
+        assert!(registry.history("t2").is_empty());
+    }
+
+    #[test]
+    fn builds_prompts_for_commands_and_patches() {
+        let exec = approval_prompt(
+            &RequestId::Integer(4),
+            ApprovalKind::CommandExecution,
+            &json!({ "threadId": "t1", "command": "rm -rf target", "cwd": "/repo/sub" }),
+            Some("/repo".to_string()),
+        )
+        .unwrap();
+        assert_eq!(exec.kind, PromptKind::Exec);
+        assert_eq!((exec.command.as_deref(), exec.cwd.as_deref()), (Some("rm -rf target"), Some("/repo/sub")));
+        assert!(exec.paths.is_empty());
+
+        let patch = approval_prompt(
+            &RequestId::Integer(5),
+            ApprovalKind::FileChange,
+            &json!({
+                "threadId": "t1",
+                "reason": "write outside the workspace",
+                "patch": { "files": [{ "path": "src/lib.rs" }, { "path": "README.md" }] },
+            }),
+            Some("/repo".to_string()),
+        )
+        .unwrap();
+        let value = serde_json::to_value(&patch).unwrap();
+        assert_eq!(value["kind"], "patch");
+        assert_eq!(value["approval_id"], 5);
+        assert_eq!(value["cwd"], "/repo");
+        assert_eq!(value["paths"], json!(["src/lib.rs", "README.md"]));
+        assert_eq!(approval_event("t1"), "codex-approval-t1");
+
+        assert!(approval_prompt(&RequestId::Integer(6), ApprovalKind::FileChange, &json!({}), None).is_none());
+    }
+
+    #[test]
+    fn decisions_map_to_protocol_strings() {
+        let decode = |s: &str| serde_json::from_value::<ApprovalDecision>(json!(s)).unwrap();
+        assert_eq!(decode("allowForSession").as_protocol(), "acceptForSession");
+        assert_eq!(decode("allow").as_protocol(), "accept");
+        assert_eq!(ApprovalDecision::from(false).as_protocol(), "decline");
+        assert!(serde_json::from_value::<ApprovalDecision>(json!("maybe")).is_err());
+    }
+
+    #[test]
+    fn patch_decision_is_written_as_approval_response() {
+        let mut stdin: Vec<u8> = Vec::new();
+        for (id, decision) in [
+            (7, FileChangeApprovalDecision::AcceptForSession),
+            (8, FileChangeApprovalDecision::Decline),
+        ] {
+            let result = file_change_response(decision).unwrap();
+            let message = crate::app_server::response_message(RequestId::Integer(id), result).unwrap();
+            stdin.extend(crate::framing::StdinFraming::Newline.encode(&message.to_string()));
+        }
+
+        let written: Vec<Value> = String::from_utf8(stdin)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            written,
+            [
+                json!({ "id": 7, "result": { "decision": "acceptForSession" } }),
+                json!({ "id": 8, "result": { "decision": "decline" } }),
+            ]
+        );
+
+        let registry = ApprovalRegistry::default();
+        registry.insert(RequestId::Integer(7), ApprovalKind::FileChange, &json!({ "threadId": "t1" }));
+        let pending = registry.take(&RequestId::Integer(7)).unwrap();
+        registry.restore(pending);
+        assert_eq!(registry.get(&RequestId::Integer(7)).map(|p| p.kind), Some(ApprovalKind::FileChange));
+    }
+}
@@ -0,0 +1,292 @@
+//! Explicitly attached files, sent as input items of a new turn.
+//!
+//! Text files are inlined as fenced text items and images become
+//! `localImage` items. Only files inside the thread's working directory are
+//! read, and oversized or binary files are skipped with a reason instead of
+//! failing the whole attachment.
+//!
+//! [`send_input_items`] is the strict variant for composed messages: the
+//! caller picks each item, so anything unusable fails the send.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::app_server::CodexAppServer;
+use crate::error::CodexiaError;
+use crate::turn::start_turn;
+
+const MAX_TEXT_BYTES: u64 = 256 * 1024;
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+/// Bytes inspected for NUL when deciding whether a file is binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachedFile {
+    pub path: String,
+    /// `text` or `image`.
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachResult {
+    pub included: Vec<AttachedFile>,
+    pub skipped: Vec<SkippedFile>,
+    /// The `turn/start` response; `None` when there was nothing to send.
+    pub turn: Option<Value>,
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Resolves `path` against `cwd` and checks it stays inside it, following
+/// symlinks.
+fn resolve_inside(cwd: &Path, path: &str) -> Result<PathBuf, String> {
+    let candidate = Path::new(path);
+    let candidate = if candidate.is_relative() {
+        cwd.join(candidate)
+    } else {
+        candidate.to_path_buf()
+    };
+    let resolved = candidate
+        .canonicalize()
+        .map_err(|e| format!("cannot read: {}", e))?;
+    let root = cwd.canonicalize().map_err(|e| format!("working directory unavailable: {}", e))?;
+    if !resolved.starts_with(&root) {
+        return Err("outside the session's working directory".to_string());
+    }
+    if !resolved.is_file() {
+        return Err("not a file".to_string());
+    }
+    Ok(resolved)
+}
+
+/// One input item for `path`, or the reason it was skipped.
+fn attachment_item(cwd: &Path, path: &str) -> Result<(AttachedFile, Value), String> {
+    let resolved = resolve_inside(cwd, path)?;
+    let size = std::fs::metadata(&resolved).map_err(|e| e.to_string())?.len();
+    let display = resolved
+        .strip_prefix(cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf()))
+        .unwrap_or(&resolved)
+        .to_string_lossy()
+        .to_string();
+
+    if is_image(&resolved) {
+        if size > MAX_IMAGE_BYTES {
+            return Err(format!("image larger than {} MB", MAX_IMAGE_BYTES / (1024 * 1024)));
+        }
+        let item = json!({ "type": "localImage", "path": resolved.to_string_lossy() });
+        return Ok((AttachedFile { path: display, kind: "image".to_string() }, item));
+    }
+
+    if size > MAX_TEXT_BYTES {
+        return Err(format!("larger than {} KB", MAX_TEXT_BYTES / 1024));
+    }
+    let bytes = std::fs::read(&resolved).map_err(|e| e.to_string())?;
+    if bytes.iter().take(BINARY_SNIFF_BYTES).any(|b| *b == 0) {
+        return Err("binary file".to_string());
+    }
+    let content = String::from_utf8(bytes).map_err(|_| "binary file".to_string())?;
+    let text = format!("{}:\n```\n{}\n```", display, content.trim_end());
+    let item = json!({ "type": "text", "text": text, "text_elements": [] });
+    Ok((AttachedFile { path: display, kind: "text".to_string() }, item))
+}
+
+/// Input items for `paths`, with what was included and what was skipped.
+fn build_attachments(cwd: &Path, paths: &[String]) -> (Vec<Value>, Vec<AttachedFile>, Vec<SkippedFile>) {
+    let mut items = Vec::new();
+    let mut included = Vec::new();
+    let mut skipped = Vec::new();
+    for path in paths {
+        match attachment_item(cwd, path) {
+            Ok((file, item)) => {
+                included.push(file);
+                items.push(item);
+            }
+            Err(reason) => skipped.push(SkippedFile {
+                path: path.clone(),
+                reason,
+            }),
+        }
+    }
+    (items, included, skipped)
+}
+
+/// Sends the readable files among `paths`, after the optional `message`, as
+/// one new turn of `thread_id`.
+pub async fn attach_files(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    paths: Vec<String>,
+    message: Option<String>,
+) -> Result<AttachResult, CodexiaError> {
+    crate::thread::require_thread(codex, thread_id)?;
+    let cwd = codex
+        .threads
+        .cwd(thread_id)
+        .ok_or("The session has no working directory to attach files from")?;
+
+    let (mut items, included, skipped) = build_attachments(&cwd, &paths);
+    for item in items.iter_mut() {
+        if let Some(Value::String(path)) = item.get_mut("path") {
+            *path = codex.launch().translate_path(path);
+        }
+    }
+    let message = message.filter(|m| !m.trim().is_empty());
+    if items.is_empty() && message.is_none() {
+        return Ok(AttachResult {
+            included,
+            skipped,
+            turn: None,
+        });
+    }
+    if let Some(message) = message {
+        items.insert(0, json!({ "type": "text", "text": message, "text_elements": [] }));
+    }
+
+    let turn = start_turn(codex, json!({ "threadId": thread_id, "input": items })).await?;
+    Ok(AttachResult {
+        included,
+        skipped,
+        turn: Some(turn),
+    })
+}
+
+/// One item of a message composed by the UI.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InputItemPayload {
+    Text { text: String },
+    /// An image file on this machine, sent as a `localImage` item.
+    LocalImage { path: String },
+}
+
+/// Checks `path` is an image codex can read and returns it canonicalized.
+fn check_image(path: &str) -> Result<PathBuf, String> {
+    let resolved = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("Cannot read image {}: {}", path, e))?;
+    if !resolved.is_file() {
+        return Err(format!("Image {} is not a file", path));
+    }
+    if !is_image(&resolved) {
+        return Err(format!(
+            "Unsupported image format for {}; expected one of: {}",
+            path,
+            IMAGE_EXTENSIONS.join(", ")
+        ));
+    }
+    let size = std::fs::metadata(&resolved).map_err(|e| e.to_string())?.len();
+    if size > MAX_IMAGE_BYTES {
+        return Err(format!("Image {} is larger than {} MB", path, MAX_IMAGE_BYTES / (1024 * 1024)));
+    }
+    Ok(resolved)
+}
+
+/// `turn/start` input items for `items`, failing on the first unusable one.
+fn input_items(items: Vec<InputItemPayload>) -> Result<Vec<Value>, String> {
+    let mut input = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            InputItemPayload::Text { text } if text.trim().is_empty() => {}
+            InputItemPayload::Text { text } => {
+                input.push(json!({ "type": "text", "text": text, "text_elements": [] }));
+            }
+            InputItemPayload::LocalImage { path } => {
+                let resolved = check_image(&path)?;
+                input.push(json!({ "type": "localImage", "path": resolved.to_string_lossy() }));
+            }
+        }
+    }
+    if input.is_empty() {
+        return Err("Message has no text or images".to_string());
+    }
+    Ok(input)
+}
+
+/// Starts a turn of `thread_id` with text and image `items`, in order.
+pub async fn send_input_items(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    items: Vec<InputItemPayload>,
+) -> Result<Value, CodexiaError> {
+    crate::thread::require_thread(codex, thread_id)?;
+    let mut input = input_items(items)?;
+    for item in input.iter_mut() {
+        if let Some(Value::String(path)) = item.get_mut("path") {
+            *path = codex.launch().translate_path(path);
+        }
+    }
+    Ok(start_turn(codex, json!({ "threadId": thread_id, "input": input })).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn includes_text_and_images_and_skips_the_rest() {
+        let root = std::env::temp_dir().join(format!("codexia-attach-{}", uuid::Uuid::new_v4()));
+        let cwd = root.join("repo");
+        std::fs::create_dir_all(&cwd).unwrap();
+        std::fs::write(cwd.join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(cwd.join("logo.PNG"), [0x89, b'P', b'N', b'G', 0]).unwrap();
+        std::fs::write(cwd.join("blob.bin"), [1, 0, 2]).unwrap();
+        std::fs::write(root.join("secret.txt"), "nope").unwrap();
+
+        let paths = ["main.rs", "logo.PNG", "blob.bin", "../secret.txt", "missing.rs"]
+            .map(str::to_string);
+        let (items, included, skipped) = build_attachments(&cwd, &paths);
+
+        assert_eq!(included.iter().map(|f| f.kind.as_str()).collect::<Vec<_>>(), ["text", "image"]);
+        assert!(items[0]["text"].as_str().unwrap().starts_with("main.rs:\n```\nfn main() {}"));
+        assert_eq!(items[1]["type"], "localImage");
+        let reasons: Vec<&str> = skipped.iter().map(|s| s.reason.as_str()).collect();
+        assert_eq!(reasons[0], "binary file");
+        assert_eq!(reasons[1], "outside the session's working directory");
+        assert!(reasons[2].starts_with("cannot read"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn maps_text_and_images_and_rejects_bad_images() {
+        let dir = std::env::temp_dir().join(format!("codexia-items-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = dir.join("shot.jpg");
+        std::fs::write(&image, [0xff, 0xd8, 0xff]).unwrap();
+        let notes = dir.join("notes.txt");
+        std::fs::write(&notes, "hi").unwrap();
+        let path = |p: &Path| p.to_string_lossy().to_string();
+
+        let input = input_items(vec![
+            InputItemPayload::Text { text: "what is this?".to_string() },
+            InputItemPayload::LocalImage { path: path(&image) },
+            InputItemPayload::Text { text: "  ".to_string() },
+        ])
+        .unwrap();
+        assert_eq!(input.len(), 2);
+        assert_eq!(input[0]["type"], "text");
+        assert_eq!(input[1], json!({ "type": "localImage", "path": path(&image.canonicalize().unwrap()) }));
+
+        let err = input_items(vec![InputItemPayload::LocalImage { path: path(&notes) }]).unwrap_err();
+        assert!(err.starts_with("Unsupported image format"), "{}", err);
+        let err = input_items(vec![InputItemPayload::LocalImage { path: path(&dir.join("gone.png")) }]).unwrap_err();
+        assert!(err.starts_with("Cannot read image"), "{}", err);
+        assert!(input_items(Vec::new()).is_err());
+
+        let payload: InputItemPayload = serde_json::from_value(json!({ "type": "localImage", "path": "/a.png" })).unwrap();
+        assert!(matches!(payload, InputItemPayload::LocalImage { .. }));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 pub mod mcp;
+pub mod models;
 pub mod provider;
 pub mod toml_helpers;
 
@@ -50,6 +51,5 @@ pub struct CodexConfig {
 }
 
 pub fn get_config_path() -> Result<PathBuf, String> {
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-    Ok(home_dir.join(".codex").join("config.toml"))
+    Ok(crate::utils::try_codex_home()?.join("config.toml"))
 }
@@ -0,0 +1,149 @@
+//! Models the user configured in codex's `config.toml`, for the model picker.
+
+use serde::Serialize;
+
+use super::get_config_path;
+use crate::providers::RootConfig;
+
+const DEFAULT_PROVIDER: &str = "openai";
+
+/// Offered when `config.toml` is missing or names no model.
+const DEFAULT_MODELS: &[&str] = &["gpt-5-codex", "gpt-5", "gpt-5-mini"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModelInfo {
+    pub provider_id: String,
+    pub model_id: String,
+    /// `model` for OpenAI models, `model (provider)` otherwise.
+    pub label: String,
+}
+
+impl ModelInfo {
+    fn new(provider_id: &str, model_id: &str) -> Self {
+        let label = if provider_id == DEFAULT_PROVIDER {
+            model_id.to_string()
+        } else {
+            format!("{} ({})", model_id, provider_id)
+        };
+        Self {
+            provider_id: provider_id.to_string(),
+            model_id: model_id.to_string(),
+            label,
+        }
+    }
+}
+
+fn push_unique(models: &mut Vec<ModelInfo>, provider_id: &str, model_id: &str) {
+    let model_id = model_id.trim();
+    if model_id.is_empty() || models.iter().any(|m| m.provider_id == provider_id && m.model_id == model_id) {
+        return;
+    }
+    models.push(ModelInfo::new(provider_id, model_id));
+}
+
+/// The `model` of a table (the root or a profile) under its `model_provider`.
+fn table_model(table: &toml::Table, models: &mut Vec<ModelInfo>) {
+    let provider = table
+        .get("model_provider")
+        .and_then(toml::Value::as_str)
+        .unwrap_or(DEFAULT_PROVIDER);
+    if let Some(model) = table.get("model").and_then(toml::Value::as_str) {
+        push_unique(models, provider, model);
+    }
+}
+
+/// The root and profile models first, then for each `model_providers` entry
+/// its `models` list, or the bundled models of a known provider.
+fn models_from_config(config: &toml::Table, bundled: &RootConfig) -> Vec<ModelInfo> {
+    let mut models = Vec::new();
+    table_model(config, &mut models);
+    if let Some(profiles) = config.get("profiles").and_then(toml::Value::as_table) {
+        for profile in profiles.values().filter_map(toml::Value::as_table) {
+            table_model(profile, &mut models);
+        }
+    }
+    let providers = config.get("model_providers").and_then(toml::Value::as_table);
+    for (provider_id, provider) in providers.into_iter().flatten() {
+        let listed = provider.get("models").and_then(toml::Value::as_array);
+        match listed {
+            Some(listed) => {
+                for model in listed.iter().filter_map(toml::Value::as_str) {
+                    push_unique(&mut models, provider_id, model);
+                }
+            }
+            None => {
+                let known = bundled.data.iter().find(|p| &p.model_provider == provider_id);
+                for model in known.and_then(|p| p.models.as_ref()).into_iter().flatten() {
+                    push_unique(&mut models, provider_id, &model.id);
+                }
+            }
+        }
+    }
+    models
+}
+
+fn default_models() -> Vec<ModelInfo> {
+    DEFAULT_MODELS
+        .iter()
+        .map(|model| ModelInfo::new(DEFAULT_PROVIDER, model))
+        .collect()
+}
+
+/// Models configured in `config.toml`, or a built-in list when there is
+/// no config file or it names no model. A malformed file is an error.
+pub fn list_available_models() -> Result<Vec<ModelInfo>, String> {
+    let path = get_config_path()?;
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(default_models()),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    let bundled: RootConfig =
+        serde_json::from_str(include_str!("../llms.json")).map_err(|e| format!("Failed to parse llms.json: {}", e))?;
+    let models = models_from_config(&config, &bundled);
+    Ok(if models.is_empty() { default_models() } else { models })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_models_of_configured_providers() {
+        let config: toml::Table = r#"
+            model = "gpt-5-codex"
+
+            [profiles.local]
+            model = "qwen3-coder"
+            model_provider = "ollama"
+
+            [profiles.fast]
+            model = "gpt-5-codex"
+
+            [model_providers.lan]
+            name = "LAN"
+            models = ["llama-70b", ""]
+
+            [model_providers.nvidia]
+            name = "NVIDIA"
+        "#
+        .parse()
+        .unwrap();
+        let bundled: RootConfig = serde_json::from_str(include_str!("../llms.json")).unwrap();
+
+        let models = models_from_config(&config, &bundled);
+        let ids: Vec<(&str, &str)> = models
+            .iter()
+            .map(|m| (m.provider_id.as_str(), m.model_id.as_str()))
+            .collect();
+        assert_eq!(&ids[..3], &[("openai", "gpt-5-codex"), ("ollama", "qwen3-coder"), ("lan", "llama-70b")]);
+        assert!(ids.contains(&("nvidia", "openai/gpt-oss-120b")));
+        assert_eq!(models[0].label, "gpt-5-codex");
+        assert_eq!(models[1].label, "qwen3-coder (ollama)");
+
+        assert!(models_from_config(&toml::Table::new(), &bundled).is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+//! Lightweight reachability probes for model providers.
+//!
+//! Used before starting a thread so a missing network connection (or a local
+//! model server that is not running) shows up as a warning up front instead
+//! of a confusing mid-turn failure.
+
+use std::time::Duration;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+
+use crate::config::get_config_path;
+use crate::providers::RootConfig;
+use crate::settings::CodexConfig;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Provider codex falls back to when none is configured anywhere.
+const DEFAULT_PROVIDER: &str = "openai";
+
+const OPENAI_ENDPOINT: &str = "https://api.openai.com/v1";
+
+/// Providers that always run on the local machine.
+pub(crate) const LOCAL_PROVIDER_IDS: &[&str] = &["oss", "ollama", "lmstudio"];
+
+/// Default endpoint of the `oss` provider (Ollama), overridable with
+/// `CODEX_OSS_BASE_URL` the same way codex resolves it.
+const DEFAULT_OSS_ENDPOINT: &str = "http://localhost:11434/v1";
+
+const LMSTUDIO_ENDPOINT: &str = "http://localhost:1234/v1";
+
+fn provider_base_url(provider: &str) -> Option<String> {
+    match provider {
+        "openai" => return Some(OPENAI_ENDPOINT.to_string()),
+        "oss" => {
+            return Some(
+                std::env::var("CODEX_OSS_BASE_URL")
+                    .unwrap_or_else(|_| DEFAULT_OSS_ENDPOINT.to_string()),
+            );
+        }
+        "lmstudio" => return Some(LMSTUDIO_ENDPOINT.to_string()),
+        _ => {}
+    }
+    let config: RootConfig = serde_json::from_str(include_str!("./llms.json")).ok()?;
+    config
+        .data
+        .into_iter()
+        .find(|p| p.model_provider == provider)
+        .map(|p| p.base_url)
+}
+
+fn is_local_host(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "::1" | "[::1]" | "0.0.0.0")
+}
+
+/// Codex's `config.toml`, for its `model_provider` and `model_providers`.
+async fn read_codex_toml() -> Option<toml::Table> {
+    let content = tokio::fs::read_to_string(get_config_path().ok()?).await.ok()?;
+    content.parse().ok()
+}
+
+/// The provider a thread runs on: its own `modelProvider`, else
+/// [`CodexConfig::model_provider`], else `model_provider` in codex's
+/// `config.toml`, else OpenAI.
+fn effective_provider(provider: Option<&str>, config: &CodexConfig, codex_toml: Option<&toml::Table>) -> String {
+    let non_empty = |p: &&str| !p.trim().is_empty();
+    provider
+        .filter(non_empty)
+        .or_else(|| config.model_provider.as_deref().filter(non_empty))
+        .or_else(|| codex_toml?.get("model_provider")?.as_str().filter(non_empty))
+        .unwrap_or(DEFAULT_PROVIDER)
+        .trim()
+        .to_string()
+}
+
+/// Endpoint of `provider`: its `model_providers` entry in codex's
+/// `config.toml`, then Codexia's OSS provider, then the built-in ones.
+fn resolve_base_url(provider: &str, config: &CodexConfig, codex_toml: Option<&toml::Table>) -> Option<String> {
+    let from_toml = codex_toml
+        .and_then(|table| table.get("model_providers")?.get(provider)?.get("base_url")?.as_str())
+        .map(str::to_string);
+    let from_oss = config
+        .oss_provider
+        .as_ref()
+        .filter(|oss| oss.provider_id.trim() == provider)
+        .map(|oss| oss.base_url.trim().to_string());
+    from_toml.or(from_oss).or_else(|| provider_base_url(provider))
+}
+
+/// Returns `(host, port)` to probe, or `None` when the endpoint is on this
+/// machine or not a URL.
+fn probe_target(base_url: &str) -> Option<(String, u16)> {
+    let url = Url::parse(base_url).ok()?;
+    let host = url.host_str()?.to_string();
+    if is_local_host(&host) {
+        return None;
+    }
+    let port = url.port_or_known_default()?;
+    Some((host, port))
+}
+
+/// Whether `base_url` points at this machine.
+fn is_local_endpoint(base_url: &str) -> bool {
+    Url::parse(base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(is_local_host))
+        .unwrap_or(false)
+}
+
+/// Probes the provider codex will use for a thread asking for `provider`.
+/// Local endpoints get an OSS server health check, remote ones a TCP probe.
+/// Returns a human readable warning when the endpoint cannot be reached;
+/// providers without a known endpoint are skipped.
+pub async fn check_provider_connectivity(provider: Option<&str>, config: &CodexConfig) -> Option<String> {
+    let codex_toml = read_codex_toml().await;
+    let label = effective_provider(provider, config, codex_toml.as_ref());
+    let Some(base_url) = resolve_base_url(&label, config, codex_toml.as_ref()) else {
+        log::debug!("No known endpoint for provider {}, skipping the connectivity check", label);
+        return None;
+    };
+    if is_local_endpoint(&base_url) {
+        let status = check_oss_provider(Some(base_url)).await;
+        return status.error.map(|err| {
+            format!("Local model server not reachable at {}: {}", status.endpoint, err)
+        });
+    }
+
+    let (host, port) = probe_target(&base_url)?;
+
+    match tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect((host.as_str(), port))).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(err)) => {
+            log::warn!("connectivity check to {}:{} failed: {}", host, port, err);
+            Some(format!("No network connectivity to {} ({}): {}", label, host, err))
+        }
+        Err(_) => {
+            log::warn!("connectivity check to {}:{} timed out", host, port);
+            Some(format!(
+                "No network connectivity to {} ({}): timed out after {:?}",
+                label, host, PROBE_TIMEOUT
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OssProviderStatus {
+    pub endpoint: String,
+    pub reachable: bool,
+    pub models: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Minimal `/v1/models` shape; local servers omit some OpenAI fields.
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelId>,
+}
+
+#[derive(Deserialize)]
+struct ModelId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaTags {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+/// Probes a local model server (Ollama, LM Studio or any OpenAI-compatible
+/// server) and lists the models it serves. `endpoint` defaults to the `oss`
+/// provider endpoint; both `http://host:port` and `http://host:port/v1` work.
+pub async fn check_oss_provider(endpoint: Option<String>) -> OssProviderStatus {
+    let endpoint = endpoint
+        .filter(|e| !e.trim().is_empty())
+        .or_else(|| provider_base_url("oss"))
+        .unwrap_or_else(|| DEFAULT_OSS_ENDPOINT.to_string());
+    let base = endpoint.trim_end_matches('/');
+    let root = base.strip_suffix("/v1").unwrap_or(base);
+
+    let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            return OssProviderStatus {
+                endpoint,
+                reachable: false,
+                models: Vec::new(),
+                error: Some(err.to_string()),
+            };
+        }
+    };
+
+    // OpenAI-compatible listing first, then Ollama's native endpoint.
+    let openai_error = match client.get(format!("{}/v1/models", root)).send().await {
+        Ok(resp) if resp.status().is_success() => match resp.json::<ModelList>().await {
+            Ok(list) => {
+                return OssProviderStatus {
+                    endpoint,
+                    reachable: true,
+                    models: list.data.into_iter().map(|m| m.id).collect(),
+                    error: None,
+                };
+            }
+            Err(err) => err.to_string(),
+        },
+        Ok(resp) => format!("HTTP {}", resp.status()),
+        Err(err) => err.to_string(),
+    };
+
+    match client.get(format!("{}/api/tags", root)).send().await {
+        Ok(resp) if resp.status().is_success() => {
+            let models = resp
+                .json::<OllamaTags>()
+                .await
+                .map(|tags| tags.models.into_iter().map(|m| m.name).collect())
+                .unwrap_or_default();
+            OssProviderStatus {
+                endpoint,
+                reachable: true,
+                models,
+                error: None,
+            }
+        }
+        _ => OssProviderStatus {
+            endpoint,
+            reachable: false,
+            models: Vec::new(),
+            error: Some(openai_error),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_endpoints_are_not_probed() {
+        let config = CodexConfig::default();
+        let ollama = resolve_base_url("ollama", &config, None).unwrap();
+        assert_eq!(ollama, "http://localhost:11434/v1");
+        assert!(is_local_endpoint(&ollama));
+        assert_eq!(probe_target(&ollama), None);
+        assert_eq!(resolve_base_url("custom", &config, None), None);
+    }
+
+    #[test]
+    fn default_provider_probes_openai() {
+        let config = CodexConfig::default();
+        let provider = effective_provider(None, &config, None);
+        assert_eq!(provider, "openai");
+        let url = resolve_base_url(&provider, &config, None).unwrap();
+        assert_eq!(probe_target(&url), Some(("api.openai.com".to_string(), 443)));
+        let url = resolve_base_url("openrouter", &config, None).unwrap();
+        assert_eq!(probe_target(&url), Some(("openrouter.ai".to_string(), 443)));
+    }
+
+    #[test]
+    fn resolves_the_configured_provider() {
+        let codex_toml: toml::Table = r#"
+            model_provider = "azure"
+            [model_providers.azure]
+            base_url = "https://corp.openai.azure.com/openai"
+            [model_providers.lan]
+            base_url = "http://10.0.0.5:8000/v1"
+        "#
+        .parse()
+        .unwrap();
+        let mut config = CodexConfig::default();
+        assert_eq!(effective_provider(None, &config, Some(&codex_toml)), "azure");
+        assert_eq!(
+            resolve_base_url("azure", &config, Some(&codex_toml)).as_deref(),
+            Some("https://corp.openai.azure.com/openai")
+        );
+
+        config.model_provider = Some("lan".to_string());
+        assert_eq!(effective_provider(None, &config, Some(&codex_toml)), "lan");
+        assert_eq!(effective_provider(Some("openai"), &config, Some(&codex_toml)), "openai");
+        let lan = resolve_base_url("lan", &config, Some(&codex_toml)).unwrap();
+        assert_eq!(probe_target(&lan), Some(("10.0.0.5".to_string(), 8000)));
+    }
+}
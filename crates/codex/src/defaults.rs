@@ -0,0 +1,268 @@
+//! Last-used thread settings, stored in `~/.codexia/thread-defaults.json` and
+//! applied to `thread/start` for any field the caller leaves unset.
+
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+
+/// `thread/start` fields that may be stored as defaults. Per-workspace fields
+/// such as `cwd` are deliberately excluded.
+const DEFAULT_KEYS: &[&str] = &[
+    "model",
+    "modelProvider",
+    "approvalPolicy",
+    "sandbox",
+    "serviceTier",
+    "personality",
+    "baseInstructions",
+    "developerInstructions",
+    "config",
+];
+
+/// `config` keys that would override the sandbox or approval policy behind
+/// the UI's back. Those are only set through the `sandbox` and
+/// `approvalPolicy` fields, which the access-mode picker controls.
+const SAFETY_CRITICAL_KEYS: &[&str] = &["sandbox_mode", "approval_policy"];
+
+/// The entry of [`SAFETY_CRITICAL_KEYS`] `key` starts with, including dotted
+/// sub-keys such as `approval_policy.granular`.
+fn critical_prefix(key: &str) -> Option<&'static str> {
+    SAFETY_CRITICAL_KEYS
+        .iter()
+        .find(|critical| key == **critical || key.strip_prefix(**critical).is_some_and(|rest| rest.starts_with('.')))
+        .copied()
+}
+
+/// What `key` overrides when it can change the sandbox or approval policy:
+/// an entry of [`SAFETY_CRITICAL_KEYS`], `profile`, which selects a profile
+/// carrying its own policies, or `profiles` for a profile's policy or a
+/// whole profile table.
+pub(crate) fn safety_critical_key(key: &str) -> Option<&'static str> {
+    // Quotes and spaces around the dots do not change the TOML key.
+    let key: String = key
+        .chars()
+        .filter(|c| !matches!(c, '"' | '\'') && !c.is_whitespace())
+        .collect();
+    if let Some(critical) = critical_prefix(&key) {
+        return Some(critical);
+    }
+    if key == "profile" {
+        return Some("profile");
+    }
+    let profile = key.strip_prefix("profiles")?;
+    if profile.is_empty() {
+        return Some("profiles");
+    }
+    let profile = profile.strip_prefix('.')?;
+    if !profile.contains('.') {
+        return Some("profiles");
+    }
+    // Every suffix, so a profile name containing dots cannot hide the key.
+    profile
+        .match_indices('.')
+        .find_map(|(at, _)| critical_prefix(&profile[at + 1..]))
+        .map(|_| "profiles")
+}
+
+/// Rejects `config` overrides of [`SAFETY_CRITICAL_KEYS`].
+pub(crate) fn reject_safety_overrides(params: &Value) -> Result<(), String> {
+    let Some(Value::Object(config)) = params.get("config") else {
+        return Ok(());
+    };
+    for key in config.keys() {
+        if let Some(critical) = safety_critical_key(key) {
+            log::warn!("Rejected config override of {} ({})", critical, key);
+            return Err(format!(
+                "config override '{}' is not allowed; set the sandbox or approval policy through the access mode instead",
+                key
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `approvalPolicy` values codex accepts, besides a `{ "granular": ... }`
+/// object.
+const APPROVAL_POLICIES: &[&str] = &["untrusted", "on-failure", "on-request", "never"];
+
+pub fn list_approval_policies() -> Vec<&'static str> {
+    APPROVAL_POLICIES.to_vec()
+}
+
+/// Checks `params.approvalPolicy` against [`APPROVAL_POLICIES`], accepting
+/// case and `_` variants such as `ON_REQUEST` and rewriting them to the
+/// canonical spelling.
+pub(crate) fn normalize_approval_policy(params: &mut Value) -> Result<(), String> {
+    let Some(policy) = params.get_mut("approvalPolicy") else {
+        return Ok(());
+    };
+    let raw = match policy {
+        Value::Null => return Ok(()),
+        Value::Object(map) if map.contains_key("granular") => return Ok(()),
+        Value::String(raw) => raw.clone(),
+        other => other.to_string(),
+    };
+    let normalized = raw.trim().to_ascii_lowercase().replace('_', "-");
+    match APPROVAL_POLICIES.iter().find(|known| **known == normalized) {
+        Some(known) => {
+            *policy = Value::String(known.to_string());
+            Ok(())
+        }
+        None => Err(format!(
+            "Unknown approval policy '{}'; expected one of: {}",
+            raw,
+            APPROVAL_POLICIES.join(", ")
+        )),
+    }
+}
+
+pub fn thread_defaults_path() -> PathBuf {
+    crate::utils::codexia_dir().join("thread-defaults.json")
+}
+
+/// Keeps only known keys with non-null values.
+fn sanitize(defaults: Map<String, Value>) -> Map<String, Value> {
+    defaults
+        .into_iter()
+        .filter(|(key, value)| DEFAULT_KEYS.contains(&key.as_str()) && !value.is_null())
+        .collect()
+}
+
+pub fn get_default_config() -> Map<String, Value> {
+    let path = thread_defaults_path();
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Map::new();
+    };
+    match serde_json::from_str::<Value>(&content) {
+        Ok(Value::Object(map)) => sanitize(map),
+        _ => {
+            log::warn!("Ignoring malformed {}", path.display());
+            Map::new()
+        }
+    }
+}
+
+/// `stored` with the fields of `update` applied; a `null` field clears the
+/// stored value.
+fn merge_defaults(mut stored: Map<String, Value>, update: Map<String, Value>) -> Map<String, Value> {
+    for (key, value) in update {
+        if value.is_null() {
+            stored.remove(&key);
+        } else {
+            stored.insert(key, value);
+        }
+    }
+    sanitize(stored)
+}
+
+/// Updates the stored defaults with the fields of `config`, keeping the
+/// ones it leaves out, so saving the model does not drop a stored sandbox.
+pub fn set_default_config(mut config: Value) -> Result<Map<String, Value>, String> {
+    normalize_approval_policy(&mut config)?;
+    let Value::Object(map) = config else {
+        return Err("Default config must be a JSON object".to_string());
+    };
+    let defaults = merge_defaults(get_default_config(), map);
+    reject_safety_overrides(&Value::Object(defaults.clone()))?;
+    let path = thread_defaults_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&defaults).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(defaults)
+}
+
+fn is_unset(map: &Map<String, Value>, key: &str) -> bool {
+    map.get(key).is_none_or(Value::is_null)
+}
+
+/// Fills unset fields of `params` from `defaults`. `config` is merged key by
+/// key so an explicit override still wins over a stored one.
+pub fn apply_defaults(params: &mut Value, defaults: &Map<String, Value>) {
+    let Value::Object(params) = params else {
+        return;
+    };
+    for (key, value) in defaults {
+        if let (Some(Value::Object(current)), Value::Object(stored)) = (params.get_mut(key), value) {
+            for (config_key, config_value) in stored {
+                if is_unset(current, config_key) {
+                    current.insert(config_key.clone(), config_value.clone());
+                }
+            }
+            continue;
+        }
+        if is_unset(params, key) {
+            params.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn fills_only_unset_fields() {
+        let defaults = sanitize(
+            json!({
+                "model": "gpt-5",
+                "sandbox": "workspace-write",
+                "cwd": "/tmp",
+                "config": { "model_reasoning_effort": "high", "web_search_request": true },
+            })
+            .as_object()
+            .unwrap()
+            .clone(),
+        );
+        let mut params = json!({
+            "model": "o3",
+            "sandbox": null,
+            "cwd": "/work",
+            "config": { "web_search_request": false },
+        });
+        apply_defaults(&mut params, &defaults);
+
+        assert_eq!(params["model"], "o3");
+        assert_eq!(params["sandbox"], "workspace-write");
+        assert_eq!(params["cwd"], "/work");
+        assert_eq!(params["config"]["web_search_request"], false);
+        assert_eq!(params["config"]["model_reasoning_effort"], "high");
+    }
+
+    #[test]
+    fn merges_updates_into_stored_defaults() {
+        let stored = sanitize(json!({ "model": "gpt-5", "sandbox": "read-only", "approvalPolicy": "untrusted" }).as_object().unwrap().clone());
+        let update = json!({ "model": "o3", "approvalPolicy": null, "cwd": "/tmp" }).as_object().unwrap().clone();
+        let merged = merge_defaults(stored, update);
+        assert_eq!(Value::Object(merged), json!({ "model": "o3", "sandbox": "read-only" }));
+    }
+
+    #[test]
+    fn normalizes_approval_policies() {
+        let mut params = json!({ "approvalPolicy": "ON_REQUEST" });
+        normalize_approval_policy(&mut params).unwrap();
+        assert_eq!(params["approvalPolicy"], "on-request");
+
+        let mut params = json!({ "approvalPolicy": { "granular": { "rules": true } } });
+        assert!(normalize_approval_policy(&mut params).is_ok());
+
+        let err = normalize_approval_policy(&mut json!({ "approvalPolicy": "full_auto" })).unwrap_err();
+        assert!(err.contains("full_auto") && err.contains("on-failure"));
+    }
+
+    #[test]
+    fn rejects_sandbox_and_approval_overrides() {
+        assert!(reject_safety_overrides(&json!({ "config": { "model_reasoning_effort": "high" } })).is_ok());
+        assert!(reject_safety_overrides(&json!({ "sandbox": "danger-full-access" })).is_ok());
+        assert!(reject_safety_overrides(&json!({ "config": { "sandbox_mode": "danger-full-access" } })).is_err());
+        assert!(reject_safety_overrides(&json!({ "config": { "approval_policy.granular": {} } })).is_err());
+        assert!(reject_safety_overrides(&json!({ "config": { "approval_policy_hint": 1 } })).is_ok());
+        assert!(reject_safety_overrides(&json!({ "config": { "profile": "yolo" } })).is_err());
+        assert!(reject_safety_overrides(&json!({ "config": { "profiles": { "yolo": {} } } })).is_err());
+        assert!(reject_safety_overrides(&json!({ "config": { "profiles.yolo.sandbox_mode": "danger-full-access" } })).is_err());
+        assert!(reject_safety_overrides(&json!({ "config": { "profiles.\"a.b\".approval_policy": "never" } })).is_err());
+        assert!(reject_safety_overrides(&json!({ "config": { "profiles.yolo.model": "o3" } })).is_ok());
+    }
+}
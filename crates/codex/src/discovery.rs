@@ -0,0 +1,539 @@
+//! Locates the codex executable and builds the command that launches it.
+//!
+//! Native candidates are tried in [`search_candidates`] order and the first
+//! one answering `codex -V` wins. On Windows, when none does, codex installed
+//! inside WSL is used through `wsl.exe`, with Windows paths translated to
+//! their `/mnt/<drive>` form.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use codex_finder::discover_codex_command;
+use regex::Regex;
+use serde::Serialize;
+use tokio::process::Command;
+
+use crate::redact::redact_url;
+use crate::settings::load_codex_config;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// How long a probe such as `codex -V` may take before the candidate is
+/// treated as not working.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The `major.minor.patch` part of `codex -V` output such as
+/// `codex-cli 0.50.0`.
+static VERSION_NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)\.(\d+)\.(\d+)").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodexLaunch {
+    Native(PathBuf),
+    /// codex inside the default WSL distribution, as a Linux path.
+    Wsl(String),
+}
+
+impl CodexLaunch {
+    /// Builds the command running codex with `args`. Stdio is left to the
+    /// caller.
+    pub fn command<I, S>(&self, args: I) -> Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let mut command = match self {
+            CodexLaunch::Native(path) => Command::new(path),
+            CodexLaunch::Wsl(codex_path) => {
+                // A login shell puts nvm/npm shims on PATH, which the
+                // `#!/usr/bin/env node` wrapper of npm installs needs.
+                let mut cmd = Command::new("wsl.exe");
+                cmd.args(["--", "bash", "-lc", "exec \"$0\" \"$@\"", codex_path]);
+                cmd
+            }
+        };
+        command.args(args);
+        #[cfg(target_os = "windows")]
+        command.creation_flags(CREATE_NO_WINDOW);
+        command
+    }
+
+    pub fn is_wsl(&self) -> bool {
+        matches!(self, CodexLaunch::Wsl(_))
+    }
+
+    /// Translates a local path into one the codex process can open.
+    pub fn translate_path(&self, path: &str) -> String {
+        match self {
+            CodexLaunch::Native(_) => path.to_string(),
+            CodexLaunch::Wsl(_) => to_wsl_path(path),
+        }
+    }
+}
+
+/// `C:\Users\me\repo` → `/mnt/c/Users/me/repo`. Paths without a drive letter
+/// are only normalized to forward slashes.
+pub fn to_wsl_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let drive = (bytes[0] as char).to_ascii_lowercase();
+        let rest = path[2..].replace('\\', "/");
+        let rest = rest.trim_start_matches('/');
+        if rest.is_empty() {
+            return format!("/mnt/{}", drive);
+        }
+        return format!("/mnt/{}/{}", drive, rest);
+    }
+    path.replace('\\', "/")
+}
+
+#[cfg(target_os = "windows")]
+const EXECUTABLE_NAMES: &[&str] = &["codex.exe", "codex.cmd"];
+#[cfg(not(target_os = "windows"))]
+const EXECUTABLE_NAMES: &[&str] = &["codex"];
+
+/// A place codex was looked for, and what was found there.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveryCandidate {
+    pub path: String,
+    /// Where the candidate came from, e.g. `config`, `PATH` or `homebrew`.
+    pub source: String,
+    /// An executable file exists at `path`.
+    pub exists: bool,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveryReport {
+    pub candidates: Vec<DiscoveryCandidate>,
+    /// The candidate that would be launched; `wsl:<path>` for WSL.
+    pub selected: Option<String>,
+    /// Configured proxy, with credentials redacted.
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    /// Set when the working candidates report different versions, naming
+    /// the one in use and where the others are.
+    pub version_conflict: Option<String>,
+}
+
+/// Stdout of `command` when it exits successfully within [`PROBE_TIMEOUT`].
+/// Runs on the async runtime without blocking a worker thread.
+async fn probe_output(mut command: Command) -> Option<String> {
+    command.stdin(Stdio::null()).kill_on_drop(true);
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+    let output = tokio::time::timeout(PROBE_TIMEOUT, command.output())
+        .await
+        .ok()?
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `npm prefix -g`; global binaries live in its `bin` directory, or in the
+/// prefix itself on Windows.
+async fn npm_global_bin() -> Option<PathBuf> {
+    let mut command = Command::new(if cfg!(windows) { "npm.cmd" } else { "npm" });
+    command.args(["prefix", "-g"]);
+    let prefix = PathBuf::from(probe_output(command).await?);
+    if prefix.as_os_str().is_empty() {
+        return None;
+    }
+    Some(if cfg!(windows) { prefix } else { prefix.join("bin") })
+}
+
+/// Whether `path` is a file the current user may execute.
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+/// Per-user install directories under `home`, for installs whose bin
+/// directory is not on the PATH a GUI app inherits.
+fn home_locations(home: &Path) -> Vec<(&'static str, PathBuf)> {
+    vec![
+        ("local-bin", home.join(".local").join("bin")),
+        ("npm-global", home.join(".npm-global").join("bin")),
+        ("bun", home.join(".bun").join("bin")),
+        ("cargo", home.join(".cargo").join("bin")),
+        ("codex-home", home.join(".codex").join("bin")),
+    ]
+}
+
+/// Every native location checked, in order, without duplicates:
+/// [`CodexConfig::extra_discovery_paths`] first, then the built-in ones.
+///
+/// [`CodexConfig::extra_discovery_paths`]: crate::settings::CodexConfig
+pub async fn search_candidates() -> Vec<(String, PathBuf)> {
+    let mut candidates: Vec<(String, PathBuf)> = Vec::new();
+    for extra in load_codex_config().extra_discovery_paths {
+        let path = PathBuf::from(extra.trim());
+        if path.is_dir() {
+            for name in EXECUTABLE_NAMES {
+                candidates.push(("config".to_string(), path.join(name)));
+            }
+        } else if !path.as_os_str().is_empty() {
+            candidates.push(("config".to_string(), path));
+        }
+    }
+
+    let mut locations: Vec<(&str, PathBuf)> = Vec::new();
+    if let Some(path) = std::env::var_os("PATH") {
+        locations.extend(std::env::split_paths(&path).map(|dir| ("PATH", dir)));
+    }
+    if let Some(dir) = npm_global_bin().await {
+        locations.push(("npm-global", dir));
+    }
+    for prefix in ["/opt/homebrew", "/usr/local"] {
+        let prefix = Path::new(prefix);
+        locations.push(("homebrew", prefix.join("opt").join("codex").join("bin")));
+        locations.push(("homebrew", prefix.join("bin")));
+    }
+    if let Some(home) = crate::utils::home_dir() {
+        locations.extend(home_locations(&home));
+    }
+
+    if let Some(path) = discover_codex_command() {
+        candidates.push(("codex-finder".to_string(), Path::new(&path).to_path_buf()));
+    }
+    for (source, dir) in locations {
+        for name in EXECUTABLE_NAMES {
+            candidates.push((source.to_string(), dir.join(name)));
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|(_, path)| seen.insert(path.clone()));
+    candidates
+}
+
+/// Output of `codex -V`, or `None` when it does not run successfully.
+async fn codex_version(path: &Path) -> Option<String> {
+    let mut command = Command::new(path);
+    command.arg("-V");
+    probe_output(command).await
+}
+
+/// The first native candidate that runs, with its `codex -V` output.
+async fn first_working_native() -> Option<(PathBuf, String)> {
+    for (_, path) in search_candidates().await {
+        if !is_executable(&path) {
+            continue;
+        }
+        if let Some(version) = codex_version(&path).await {
+            return Some((path, version));
+        }
+    }
+    None
+}
+
+/// Asks the default WSL distribution where codex is.
+#[cfg(target_os = "windows")]
+async fn probe_wsl_codex() -> Option<String> {
+    let mut command = Command::new("wsl.exe");
+    command.args(["--", "bash", "-lc", "which codex"]);
+    let path = probe_output(command).await?;
+    (!path.is_empty()).then_some(path)
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn probe_wsl_codex() -> Option<String> {
+    None
+}
+
+/// Finds codex natively first, then inside WSL.
+pub async fn discover_codex_launch() -> Option<CodexLaunch> {
+    if let Some((path, _)) = first_working_native().await {
+        log::info!("Using codex at {}", path.display());
+        return Some(CodexLaunch::Native(path));
+    }
+    let wsl_path = probe_wsl_codex().await?;
+    log::info!("Using codex from WSL: {}", wsl_path);
+    Some(CodexLaunch::Wsl(wsl_path))
+}
+
+/// Probes every candidate, so users can see where codex was looked for and
+/// which proxy it is launched with.
+pub async fn codex_discovery_diagnostics() -> DiscoveryReport {
+    let mut candidates: Vec<DiscoveryCandidate> = Vec::new();
+    for (source, path) in search_candidates().await {
+        let exists = is_executable(&path);
+        let version = if exists { codex_version(&path).await } else { None };
+        candidates.push(DiscoveryCandidate {
+            path: path.to_string_lossy().to_string(),
+            source,
+            exists,
+            version,
+        });
+    }
+    let selected = match candidates.iter().find(|c| c.version.is_some()) {
+        Some(candidate) => Some(candidate.path.clone()),
+        None => probe_wsl_codex().await.map(|path| format!("wsl:{}", path)),
+    };
+    let version_conflict = version_conflict(&candidates, selected.as_deref());
+    if let Some(warning) = &version_conflict {
+        log::warn!("{}", warning);
+    }
+    let config = load_codex_config();
+    DiscoveryReport {
+        candidates,
+        selected,
+        version_conflict,
+        proxy: config.proxy.as_deref().map(redact_url),
+        no_proxy: config.no_proxy,
+    }
+}
+
+/// A warning when more than one distinct codex version is installed, which
+/// explains codex behaving differently here than in the user's terminal.
+fn version_conflict(candidates: &[DiscoveryCandidate], selected: Option<&str>) -> Option<String> {
+    let working: Vec<(&str, &str)> = candidates
+        .iter()
+        .filter_map(|c| Some((c.path.as_str(), c.version.as_deref()?)))
+        .collect();
+    let mut versions: Vec<&str> = working.iter().map(|(_, version)| *version).collect();
+    versions.sort_unstable();
+    versions.dedup();
+    if versions.len() < 2 {
+        return None;
+    }
+
+    let (used_path, used_version) = working
+        .iter()
+        .find(|(path, _)| Some(*path) == selected)
+        .copied()
+        .unwrap_or(working[0]);
+    let others: Vec<String> = working
+        .iter()
+        .filter(|(_, version)| *version != used_version)
+        .map(|(path, version)| format!("{} at {}", version, path))
+        .collect();
+    Some(format!(
+        "Found {} different codex versions. Using {} at {}; also installed: {}",
+        versions.len(),
+        used_version,
+        used_path,
+        others.join(", ")
+    ))
+}
+
+/// What the frontend needs to decide whether to show onboarding.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BootstrapStatus {
+    pub installed: bool,
+    /// The codex that would be launched; `wsl:<path>` for WSL.
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub logged_in: bool,
+    /// Whether the codex home directory exists.
+    pub home_exists: bool,
+}
+
+/// Whether `auth.json` in `home` holds an API key or ChatGPT tokens.
+fn has_credentials(home: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(home.join("auth.json")) else {
+        return false;
+    };
+    let Ok(auth) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return false;
+    };
+    let present = |key: &str| auth.get(key).is_some_and(|value| match value {
+        serde_json::Value::String(s) => !s.trim().is_empty(),
+        value => !value.is_null(),
+    });
+    present("OPENAI_API_KEY") || present("tokens")
+}
+
+/// Never fails: anything that cannot be determined is reported as missing.
+pub async fn bootstrap_check() -> BootstrapStatus {
+    let home = crate::utils::codex_home();
+    let mut status = BootstrapStatus {
+        logged_in: has_credentials(&home),
+        home_exists: home.is_dir(),
+        ..BootstrapStatus::default()
+    };
+    if let Some((path, version)) = first_working_native().await {
+        status.installed = true;
+        status.path = Some(path.to_string_lossy().to_string());
+        status.version = Some(version);
+    } else if let Some(path) = probe_wsl_codex().await {
+        status.installed = true;
+        status.path = Some(format!("wsl:{}", path));
+    }
+    status
+}
+
+/// Output of `codex [subcommand] --help` for the codex that would be
+/// launched, to check which subcommands and flags it supports.
+pub async fn get_codex_help(subcommand: Option<String>) -> Result<String, String> {
+    let subcommand = subcommand.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(sub) = &subcommand {
+        if !sub.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("Invalid codex subcommand '{}'", sub));
+        }
+    }
+    let launch = discover_codex_launch()
+        .await
+        .ok_or_else(|| "Unable to locate codex binary".to_string())?;
+    let args: Vec<String> = subcommand.into_iter().chain(["--help".to_string()]).collect();
+    let mut command = launch.command(&args);
+    command.stdin(Stdio::null());
+    let output = tokio::time::timeout(std::time::Duration::from_secs(10), command.output())
+        .await
+        .map_err(|_| "codex --help timed out".to_string())?
+        .map_err(|e| format!("Failed to run codex: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.status.success() {
+        return Err(format!("codex {} failed: {}", args.join(" "), stderr.trim()));
+    }
+    Ok(if stdout.trim().is_empty() { stderr } else { stdout })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodexVersion {
+    /// `codex -V` output as printed, e.g. `codex-cli 0.50.0`.
+    pub raw: String,
+    /// `None` along with `minor` and `patch` when `raw` has no version number.
+    pub major: Option<u64>,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+}
+
+impl CodexVersion {
+    pub fn parse(raw: &str) -> Self {
+        let numbers = VERSION_NUMBER
+            .captures(raw)
+            .and_then(|caps| Some((caps[1].parse().ok()?, caps[2].parse().ok()?, caps[3].parse().ok()?)));
+        CodexVersion {
+            raw: raw.trim().to_string(),
+            major: numbers.map(|(major, _, _)| major),
+            minor: numbers.map(|(_, minor, _)| minor),
+            patch: numbers.map(|(_, _, patch)| patch),
+        }
+    }
+}
+
+/// `codex -V` of the codex that would be launched, with its version number
+/// parsed so the UI can gate features without parsing the output itself.
+pub async fn check_codex_version_info() -> Result<CodexVersion, String> {
+    let launch = discover_codex_launch()
+        .await
+        .ok_or_else(|| "Unable to locate codex binary".to_string())?;
+    let raw = probe_output(launch.command(["-V"]))
+        .await
+        .ok_or_else(|| "codex -V failed or timed out".to_string())?;
+    Ok(CodexVersion::parse(&raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn only_accepts_executable_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("codexia-discovery-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("codex");
+        std::fs::write(&file, "#!/bin/sh\n").unwrap();
+        assert!(!is_executable(&file));
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(is_executable(&file));
+        assert!(!is_executable(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn probes_report_only_successful_output() {
+        let mut ok = Command::new("sh");
+        ok.args(["-c", "echo ' codex-cli 0.50.0 '"]);
+        assert_eq!(probe_output(ok).await.as_deref(), Some("codex-cli 0.50.0"));
+        let mut failing = Command::new("sh");
+        failing.args(["-c", "echo nope; exit 1"]);
+        assert_eq!(probe_output(failing).await, None);
+        assert_eq!(probe_output(Command::new("/nonexistent/codex")).await, None);
+    }
+
+    #[test]
+    fn detects_credentials_in_auth_json() {
+        let home = std::env::temp_dir().join(format!("codexia-auth-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&home).unwrap();
+        assert!(!has_credentials(&home));
+        std::fs::write(home.join("auth.json"), r#"{"OPENAI_API_KEY":null,"tokens":null}"#).unwrap();
+        assert!(!has_credentials(&home));
+        std::fs::write(home.join("auth.json"), r#"{"OPENAI_API_KEY":"sk-1"}"#).unwrap();
+        assert!(has_credentials(&home));
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn warns_about_distinct_versions() {
+        let candidate = |path: &str, version: Option<&str>| DiscoveryCandidate {
+            path: path.to_string(),
+            source: "PATH".to_string(),
+            exists: version.is_some(),
+            version: version.map(str::to_string),
+        };
+        let mut candidates = vec![
+            candidate("/opt/homebrew/bin/codex", Some("codex-cli 0.50.0")),
+            candidate("/usr/local/bin/codex", Some("codex-cli 0.50.0")),
+            candidate("/missing/codex", None),
+        ];
+        assert_eq!(version_conflict(&candidates, Some("/opt/homebrew/bin/codex")), None);
+
+        candidates.push(candidate("/home/me/.npm-global/bin/codex", Some("codex-cli 0.20.0")));
+        let warning = version_conflict(&candidates, Some("/opt/homebrew/bin/codex")).unwrap();
+        assert!(warning.starts_with("Found 2 different codex versions. Using codex-cli 0.50.0 at /opt/homebrew/bin/codex"));
+        assert!(warning.ends_with("also installed: codex-cli 0.20.0 at /home/me/.npm-global/bin/codex"));
+    }
+
+    #[test]
+    fn checks_per_user_install_directories() {
+        let home = Path::new("/home/me");
+        let dirs: Vec<(&str, PathBuf)> = home_locations(home);
+        let sources: Vec<&str> = dirs.iter().map(|(source, _)| *source).collect();
+        assert_eq!(sources, ["local-bin", "npm-global", "bun", "cargo", "codex-home"]);
+        assert_eq!(dirs[1].1, home.join(".npm-global/bin"));
+        assert_eq!(dirs[3].1, home.join(".cargo/bin"));
+    }
+
+    #[test]
+    fn parses_version_numbers() {
+        let version = CodexVersion::parse("codex-cli 0.12.3\n");
+        assert_eq!(version.raw, "codex-cli 0.12.3");
+        assert_eq!((version.major, version.minor, version.patch), (Some(0), Some(12), Some(3)));
+        assert_eq!(CodexVersion::parse("codex-cli 1.2.0-alpha.4").minor, Some(2));
+
+        let unparsed = CodexVersion::parse("codex-cli dev");
+        assert_eq!(unparsed.raw, "codex-cli dev");
+        assert_eq!((unparsed.major, unparsed.minor, unparsed.patch), (None, None, None));
+    }
+
+    #[test]
+    fn translates_windows_paths_for_wsl() {
+        assert_eq!(to_wsl_path(r"C:\Users\me\repo"), "/mnt/c/Users/me/repo");
+        assert_eq!(to_wsl_path("D:/work"), "/mnt/d/work");
+        assert_eq!(to_wsl_path(r"E:\"), "/mnt/e");
+        assert_eq!(to_wsl_path("/home/me"), "/home/me");
+    }
+}
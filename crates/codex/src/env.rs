@@ -1,10 +1,10 @@
 #[cfg(target_os = "macos")]
-use std::process::Command;
+use tokio::process::Command;
 
 #[cfg(target_os = "windows")]
 use winreg::{enums::*, RegKey};
 
-pub fn get_env(key: String) -> Result<String, String> {
+pub async fn get_env(key: String) -> Result<String, String> {
     #[cfg(target_os = "windows")]
     {
         let root = RegKey::predef(HKEY_CURRENT_USER);
@@ -18,7 +18,7 @@ pub fn get_env(key: String) -> Result<String, String> {
     #[cfg(target_os = "macos")]
     {
         let cmd = format!("source ~/.zshrc && echo ${}", key);
-        if let Ok(output) = Command::new("zsh").args(["-c", &cmd]).output() {
+        if let Ok(output) = Command::new("zsh").args(["-c", &cmd]).output().await {
             if output.status.success() {
                 let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
                 if !value.is_empty() {
@@ -31,7 +31,7 @@ pub fn get_env(key: String) -> Result<String, String> {
     std::env::var(&key).map_err(|_| format!("Environment variable '{}' not found", key))
 }
 
-pub fn set_env(key: String, value: String) -> Result<(), String> {
+pub async fn set_env(key: String, value: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         let root = RegKey::predef(HKEY_CURRENT_USER);
@@ -66,6 +66,7 @@ pub fn set_env(key: String, value: String) -> Result<(), String> {
         let output = Command::new("zsh")
             .args(["-c", &cmd])
             .output()
+            .await
             .map_err(|e| e.to_string())?;
 
         if !output.status.success() {
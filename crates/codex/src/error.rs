@@ -0,0 +1,104 @@
+//! Typed errors for the codex commands.
+//!
+//! Serialized as `{ "code": ..., "message": ... }` plus any variant fields in
+//! camelCase, so the frontend can branch on `code` and still show `message`.
+//! Plain `String` errors convert into [`CodexiaError::Other`], which keeps `?`
+//! working across helpers that still return strings.
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CodexiaError {
+    #[error("Unable to locate codex binary")]
+    NotInstalled,
+    #[error("codex {version} is not supported: {reason}")]
+    IncompatibleVersion { version: String, reason: String },
+    #[error("Session not found: {session_id}")]
+    SessionNotFound { session_id: String },
+    #[error("Failed to start codex: {0}")]
+    SpawnFailed(String),
+    #[error("Working directory does not exist: {path}")]
+    WorkingDirMissing { path: String },
+    #[error("The working directory of session {session_id} is fixed while it runs; resume the session in {path} to continue there")]
+    WorkingDirFixed { session_id: String, path: String },
+    #[error("codex is not connected")]
+    NotConnected,
+    #[error("{0}")]
+    Other(String),
+}
+
+impl CodexiaError {
+    /// Stable identifier the frontend matches on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CodexiaError::NotInstalled => "notInstalled",
+            CodexiaError::IncompatibleVersion { .. } => "incompatibleVersion",
+            CodexiaError::SessionNotFound { .. } => "sessionNotFound",
+            CodexiaError::SpawnFailed(_) => "spawnFailed",
+            CodexiaError::WorkingDirMissing { .. } => "workingDirMissing",
+            CodexiaError::WorkingDirFixed { .. } => "workingDirFixed",
+            CodexiaError::NotConnected => "notConnected",
+            CodexiaError::Other(_) => "other",
+        }
+    }
+}
+
+impl Serialize for CodexiaError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            CodexiaError::IncompatibleVersion { version, .. } => {
+                map.serialize_entry("version", version)?;
+            }
+            CodexiaError::SessionNotFound { session_id } => {
+                map.serialize_entry("sessionId", session_id)?;
+            }
+            CodexiaError::WorkingDirMissing { path } => {
+                map.serialize_entry("path", path)?;
+            }
+            CodexiaError::WorkingDirFixed { session_id, path } => {
+                map.serialize_entry("sessionId", session_id)?;
+                map.serialize_entry("path", path)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+impl From<String> for CodexiaError {
+    fn from(message: String) -> Self {
+        CodexiaError::Other(message)
+    }
+}
+
+impl From<&str> for CodexiaError {
+    fn from(message: &str) -> Self {
+        CodexiaError::Other(message.to_string())
+    }
+}
+
+impl From<CodexiaError> for String {
+    fn from(err: CodexiaError) -> Self {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_code_message_and_fields() {
+        let err = CodexiaError::SessionNotFound { session_id: "t1".to_string() };
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({ "code": "sessionNotFound", "message": "Session not found: t1", "sessionId": "t1" })
+        );
+        let err: CodexiaError = "boom".to_string().into();
+        assert_eq!(serde_json::to_value(&err).unwrap(), json!({ "code": "other", "message": "boom" }));
+    }
+}
@@ -0,0 +1,148 @@
+//! Best-effort progress of running commands, read from their output.
+//!
+//! Test runners and build tools print counts like `[12/340]` or percentages
+//! like `45%`. With [`CodexConfig::exec_progress`] on, the stdout reader
+//! scans `item/commandExecution/outputDelta` text for them and emits a
+//! `codex-exec-progress-<thread_id>` event whenever the fraction changes.
+//! Output without a recognizable pattern emits nothing.
+//!
+//! [`CodexConfig::exec_progress`]: crate::settings::CodexConfig
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use codexia_shared::event_sink::EventSink;
+use serde_json::{Value, json};
+
+use crate::app_server::CodexAppServer;
+
+pub fn exec_progress_event(thread_id: &str) -> String {
+    crate::utils::event_name("exec-progress", thread_id)
+}
+
+/// The last fraction reported per command item.
+#[derive(Default)]
+pub struct ExecProgressRegistry {
+    last: Mutex<HashMap<String, f64>>,
+}
+
+impl ExecProgressRegistry {
+    /// Records `fraction` for `item_id`; `false` when it is unchanged.
+    fn update(&self, item_id: &str, fraction: f64) -> bool {
+        let mut last = self.last.lock().unwrap();
+        if last.get(item_id) == Some(&fraction) {
+            return false;
+        }
+        last.insert(item_id.to_string(), fraction);
+        true
+    }
+
+    fn forget(&self, item_id: &str) {
+        self.last.lock().unwrap().remove(item_id);
+    }
+}
+
+/// The number ending at byte `end` of `text`, e.g. `12` or `45.5`.
+fn number_before(text: &str, end: usize) -> Option<f64> {
+    let start = text[..end]
+        .rfind(|c: char| !c.is_ascii_digit() && c != '.')
+        .map_or(0, |i| i + 1);
+    text[start..end].trim_start_matches('.').parse().ok()
+}
+
+/// `45%` style progress anywhere in `line`, the last one winning.
+fn percent(line: &str) -> Option<f64> {
+    line.match_indices('%')
+        .filter_map(|(i, _)| number_before(line, i))
+        .filter(|value| (0.0..=100.0).contains(value))
+        .last()
+        .map(|value| value / 100.0)
+}
+
+/// `12/340` style progress in a whitespace separated token, optionally in
+/// brackets or parentheses.
+fn count(line: &str) -> Option<f64> {
+    line.split_whitespace()
+        .filter_map(|token| {
+            let token = token.trim_matches(|c: char| "[]():,".contains(c));
+            let (done, total) = token.split_once('/')?;
+            let done: u64 = done.parse().ok()?;
+            let total: u64 = total.parse().ok()?;
+            (total > 0 && done <= total).then(|| done as f64 / total as f64)
+        })
+        .last()
+}
+
+/// The progress shown by the newest line of `output` that has any.
+pub fn parse_progress(output: &str) -> Option<f64> {
+    output
+        .lines()
+        .rev()
+        .find_map(|line| percent(line).or_else(|| count(line)))
+}
+
+/// Handles a serialized `ServerNotification`.
+pub fn observe(codex: &CodexAppServer, event_sink: &dyn EventSink, notification: &Value) {
+    let Some(method) = notification.get("method").and_then(Value::as_str) else {
+        return;
+    };
+    let Some(params) = notification.get("params") else {
+        return;
+    };
+    match method {
+        "item/commandExecution/outputDelta" => {
+            if !codex.config().exec_progress {
+                return;
+            }
+            let field = |key: &str| params.get(key).and_then(Value::as_str);
+            let (Some(thread_id), Some(item_id), Some(delta)) =
+                (field("threadId"), field("itemId"), field("delta"))
+            else {
+                return;
+            };
+            let Some(fraction) = parse_progress(delta) else {
+                return;
+            };
+            if codex.exec_progress.update(item_id, fraction) {
+                event_sink.emit(
+                    &exec_progress_event(thread_id),
+                    json!({
+                        "threadId": thread_id,
+                        "turnId": field("turnId"),
+                        "itemId": item_id,
+                        "fraction": fraction,
+                    }),
+                );
+            }
+        }
+        "item/completed" => {
+            if let Some(item_id) = params.get("item").and_then(|item| item.get("id")).and_then(Value::as_str) {
+                codex.exec_progress.forget(item_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_counts_and_percentages() {
+        assert_eq!(parse_progress("test result: running\n[12/48] tests::login"), Some(0.25));
+        assert_eq!(parse_progress("Compiling foo (3/4)\n"), Some(0.75));
+        assert_eq!(parse_progress("Downloading 45.5%\nDownloading 50%"), Some(0.5));
+        assert_eq!(parse_progress("50%\nno progress here\n"), Some(0.5));
+        assert_eq!(parse_progress("see src/lib.rs and 2025/09/01"), None);
+        assert_eq!(parse_progress("ratio 5/3, done 150%"), None);
+        assert_eq!(parse_progress(""), None);
+
+        let registry = ExecProgressRegistry::default();
+        assert!(registry.update("i1", 0.5));
+        assert!(!registry.update("i1", 0.5));
+        registry.forget("i1");
+        assert!(registry.update("i1", 0.5));
+        assert_eq!(exec_progress_event("t1"), "codex-exec-progress-t1");
+    }
+}
@@ -0,0 +1,144 @@
+//! How messages are framed and flushed on the app-server's stdin.
+//!
+//! The default (one JSON message per line, flushed after every message) is
+//! what `codex app-server` expects. The alternatives exist so protocol issues
+//! can be diagnosed without recompiling:
+//!
+//! - `CODEXIA_STDIN_FRAMING=newline|length-prefixed`
+//! - `CODEXIA_STDIN_FLUSH=per-message|batched`
+//! - `CODEXIA_STDIN_QUEUE=<n>`: how many messages may wait for stdin before
+//!   further sends fail instead of piling up behind them
+
+use std::time::Duration;
+
+/// Interval at which buffered writes are flushed under [`FlushPolicy::Batched`].
+pub const BATCH_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Default of [`StdinOptions::max_queued_writes`].
+pub const DEFAULT_MAX_QUEUED_WRITES: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StdinFraming {
+    /// `<json>\n`
+    #[default]
+    Newline,
+    /// `Content-Length: <n>\r\n\r\n<json>`
+    LengthPrefixed,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Flush after every message.
+    #[default]
+    PerMessage,
+    /// Let writes accumulate and flush every [`BATCH_FLUSH_INTERVAL`].
+    Batched,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StdinOptions {
+    pub framing: StdinFraming,
+    pub flush_policy: FlushPolicy,
+    /// Normal writes allowed to wait for stdin at once, including the one
+    /// being written. Priority writes are never refused.
+    pub max_queued_writes: usize,
+}
+
+impl Default for StdinOptions {
+    fn default() -> Self {
+        Self {
+            framing: StdinFraming::default(),
+            flush_policy: FlushPolicy::default(),
+            max_queued_writes: DEFAULT_MAX_QUEUED_WRITES,
+        }
+    }
+}
+
+fn parse_queue_size(value: &str) -> Option<usize> {
+    value.trim().parse().ok().filter(|size| *size > 0)
+}
+
+impl StdinFraming {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "newline" | "line" => Some(Self::Newline),
+            "length-prefixed" | "length_prefixed" | "content-length" => Some(Self::LengthPrefixed),
+            _ => None,
+        }
+    }
+
+    /// Encodes one serialized JSON message.
+    pub fn encode(self, message: &str) -> Vec<u8> {
+        match self {
+            Self::Newline => {
+                let mut bytes = Vec::with_capacity(message.len() + 1);
+                bytes.extend_from_slice(message.as_bytes());
+                bytes.push(b'\n');
+                bytes
+            }
+            Self::LengthPrefixed => {
+                format!("Content-Length: {}\r\n\r\n{}", message.len(), message).into_bytes()
+            }
+        }
+    }
+}
+
+impl FlushPolicy {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "per-message" | "per_message" | "message" => Some(Self::PerMessage),
+            "batched" | "batch" => Some(Self::Batched),
+            _ => None,
+        }
+    }
+}
+
+fn from_env_var<T: Default>(key: &str, parse: fn(&str) -> Option<T>) -> T {
+    match std::env::var(key) {
+        Ok(value) => parse(&value).unwrap_or_else(|| {
+            log::warn!("Ignoring unknown {} value '{}'", key, value);
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+impl StdinOptions {
+    pub fn from_env() -> Self {
+        Self {
+            framing: from_env_var("CODEXIA_STDIN_FRAMING", StdinFraming::parse),
+            flush_policy: from_env_var("CODEXIA_STDIN_FLUSH", FlushPolicy::parse),
+            max_queued_writes: match std::env::var("CODEXIA_STDIN_QUEUE") {
+                Ok(value) => parse_queue_size(&value).unwrap_or_else(|| {
+                    log::warn!("Ignoring invalid CODEXIA_STDIN_QUEUE value '{}'", value);
+                    DEFAULT_MAX_QUEUED_WRITES
+                }),
+                Err(_) => DEFAULT_MAX_QUEUED_WRITES,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_both_framings() {
+        assert_eq!(StdinFraming::Newline.encode("{}"), b"{}\n".to_vec());
+        assert_eq!(
+            StdinFraming::LengthPrefixed.encode("{\"a\":1}"),
+            b"Content-Length: 7\r\n\r\n{\"a\":1}".to_vec()
+        );
+    }
+
+    #[test]
+    fn parses_option_names() {
+        assert_eq!(StdinFraming::parse("Length-Prefixed"), Some(StdinFraming::LengthPrefixed));
+        assert_eq!(FlushPolicy::parse("batched"), Some(FlushPolicy::Batched));
+        assert_eq!(FlushPolicy::parse("sometimes"), None);
+        assert_eq!(parse_queue_size(" 8 "), Some(8));
+        assert_eq!(parse_queue_size("0"), None);
+        assert_eq!(parse_queue_size("lots"), None);
+    }
+}
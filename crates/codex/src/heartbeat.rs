@@ -0,0 +1,112 @@
+//! Optional heartbeat for turns that go quiet.
+//!
+//! While a model thinks it may send nothing for a minute, which the UI
+//! cannot tell apart from a hung session. With
+//! [`CodexConfig::heartbeat_secs`] set, every busy thread that has been
+//! silent for that long gets a `codex-heartbeat-<thread_id>` event carrying
+//! how long its turn has been running.
+//!
+//! [`CodexConfig::heartbeat_secs`]: crate::settings::CodexConfig
+
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use codexia_shared::event_sink::EventSink;
+use serde_json::json;
+
+use crate::app_server::CodexAppServer;
+use crate::threads::ThreadSession;
+
+/// How often the setting is re-checked while heartbeats are off.
+const IDLE_POLL: Duration = Duration::from_secs(5);
+
+pub fn heartbeat_event(thread_id: &str) -> String {
+    crate::utils::event_name("heartbeat", thread_id)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Heartbeat {
+    thread_id: String,
+    turn_id: String,
+    elapsed: Duration,
+}
+
+/// Threads with a turn in flight and no notification for `interval`.
+fn due(sessions: &[ThreadSession], now: Instant, interval: Duration) -> Vec<Heartbeat> {
+    sessions
+        .iter()
+        .filter_map(|session| {
+            let turn_id = session.active_turn_id.clone()?;
+            let silent = !matches!(session.last_event_at, Some(at) if now.duration_since(at) < interval);
+            silent.then(|| Heartbeat {
+                thread_id: session.thread_id.clone(),
+                turn_id,
+                elapsed: session.turn_started_at.map(|at| now.duration_since(at)).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Runs until the app-server is dropped or its stdout closes.
+pub(crate) fn spawn(codex: &Arc<CodexAppServer>, event_sink: &Arc<dyn EventSink>) {
+    let codex: Weak<CodexAppServer> = Arc::downgrade(codex);
+    let event_sink = Arc::clone(event_sink);
+    tokio::spawn(async move {
+        loop {
+            let interval = match codex.upgrade() {
+                Some(codex) => codex.config().heartbeat_secs.filter(|secs| *secs > 0),
+                None => return,
+            };
+            let Some(interval) = interval.map(Duration::from_secs) else {
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            };
+            tokio::time::sleep(interval).await;
+
+            let Some(codex) = codex.upgrade() else {
+                return;
+            };
+            if !codex.is_running().await {
+                return;
+            }
+            for beat in due(&codex.threads.list(), Instant::now(), interval) {
+                event_sink.emit(
+                    &heartbeat_event(&beat.thread_id),
+                    json!({
+                        "threadId": beat.thread_id,
+                        "turnId": beat.turn_id,
+                        "elapsedSecs": beat.elapsed.as_secs(),
+                    }),
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beats_only_for_silent_busy_threads() {
+        let now = Instant::now();
+        let session = |id: &str, turn: Option<&str>, silent_for: u64| ThreadSession {
+            thread_id: id.to_string(),
+            active_turn_id: turn.map(str::to_string),
+            last_event_at: now.checked_sub(Duration::from_secs(silent_for)),
+            turn_started_at: now.checked_sub(Duration::from_secs(45)),
+            ..Default::default()
+        };
+        let sessions = [
+            session("quiet", Some("u1"), 20),
+            session("streaming", Some("u2"), 1),
+            session("idle", None, 60),
+        ];
+
+        let beats = due(&sessions, now, Duration::from_secs(15));
+        assert_eq!(beats.len(), 1);
+        assert_eq!(beats[0].thread_id, "quiet");
+        assert_eq!(beats[0].elapsed, Duration::from_secs(45));
+        assert_eq!(heartbeat_event("t1"), "codex-heartbeat-t1");
+    }
+}
@@ -0,0 +1,111 @@
+//! The exact command line the app-server was spawned with, for bug reports
+//! and for re-running codex by hand. Secrets are redacted when it is
+//! captured, so nothing sensitive is kept around.
+
+use serde::Serialize;
+use tokio::process::Command;
+
+use crate::redact::{REDACTED, is_secret_env, is_secret_key, redact_url};
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LaunchArgs {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Environment variables set on top of the inherited environment.
+    pub env: Vec<(String, String)>,
+    /// `env` and `args` as one shell line that can be pasted into a terminal.
+    pub command_line: String,
+}
+
+/// Redacts the value of a `key=value` argument (as passed to `-c`) when the
+/// key's last segment names a credential.
+fn redact_arg(arg: &str) -> String {
+    match arg.split_once('=') {
+        Some((key, _)) if is_secret_key(key.rsplit('.').next().unwrap_or(key)) => {
+            format!("{}={}", key, REDACTED)
+        }
+        _ => redact_url(arg),
+    }
+}
+
+fn redact_env(key: &str, value: &str) -> String {
+    if is_secret_env(key) {
+        REDACTED.to_string()
+    } else {
+        redact_url(value)
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+impl LaunchArgs {
+    pub(crate) fn from_command(command: &Command) -> Self {
+        let command = command.as_std();
+        let program = command.get_program().to_string_lossy().to_string();
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| redact_arg(&arg.to_string_lossy()))
+            .collect();
+        let env: Vec<(String, String)> = command
+            .get_envs()
+            .filter_map(|(key, value)| {
+                let key = key.to_string_lossy().to_string();
+                let value = redact_env(&key, &value?.to_string_lossy());
+                Some((key, value))
+            })
+            .collect();
+
+        let command_line = env
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, shell_quote(value)))
+            .chain(std::iter::once(shell_quote(&program)))
+            .chain(args.iter().map(|arg| shell_quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            program,
+            args,
+            env,
+            command_line,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_command_with_secrets_redacted() {
+        let mut command = Command::new("/usr/local/bin/codex");
+        command.args([
+            "app-server",
+            "-c",
+            "model_providers.local.base_url=\"http://user:pw@localhost:11434/v1\"",
+            "-c",
+            "model_providers.local.api_key=\"sk-123\"",
+        ]);
+        command.env("CODEXIA_OSS_API_KEY", "sk-123");
+        command.env("HTTPS_PROXY", "http://me:pw@proxy:8080");
+        command.env("GITHUB_TOKENS_FILE", "/home/me/tokens");
+
+        let launch = LaunchArgs::from_command(&command);
+        assert_eq!(launch.program, "/usr/local/bin/codex");
+        assert_eq!(launch.args[2], "model_providers.local.base_url=\"http://***@localhost:11434/v1\"");
+        assert_eq!(launch.args[4], "model_providers.local.api_key=***");
+        assert!(launch.env.contains(&("CODEXIA_OSS_API_KEY".to_string(), REDACTED.to_string())));
+        assert!(launch.env.contains(&("GITHUB_TOKENS_FILE".to_string(), REDACTED.to_string())));
+        assert!(!launch.command_line.contains("sk-123") && !launch.command_line.contains("pw@"));
+        assert!(launch.command_line.contains("/usr/local/bin/codex app-server -c"));
+    }
+}
@@ -1,9 +1,44 @@
 pub mod app_server;
+pub mod approvals;
+pub mod attachments;
 pub mod config;
+pub mod connectivity;
+pub mod defaults;
+pub mod discovery;
 pub mod env;
+pub mod error;
+pub mod exec_progress;
+pub mod framing;
+pub mod heartbeat;
+pub mod launch_args;
+pub mod line_reader;
+pub mod log_tail;
+pub mod message_queue;
+pub mod migrate;
+pub mod notification;
+pub mod patches;
 pub mod providers;
+pub mod questions;
+pub mod reasoning;
+pub mod recent;
+pub mod redact;
+pub mod retry;
 mod server_request;
+pub mod session_log;
+pub mod session_export;
+pub mod session_meta;
+pub mod session_usage;
+pub mod sessions;
+pub mod settings;
+pub mod spawn;
+pub mod stderr_log;
+pub mod tee;
+pub mod thread;
+pub mod threads;
+pub mod turn;
+pub mod usage;
 pub mod utils;
+pub mod validation;
 
 pub use app_server::*;
 pub use config::mcp::{add_mcp_server, delete_mcp_server, read_mcp_servers, set_mcp_server_enabled};
@@ -0,0 +1,154 @@
+//! Line reader for the app-server's stdout with a cap on line length.
+//!
+//! `BufReader::lines` buffers a whole line however long it is, so a single
+//! pathological event (a huge base64 blob, megabytes of exec output) could
+//! allocate gigabytes. [`BoundedLines`] keeps at most `max_len` bytes of a
+//! line and discards the rest, reporting how much was dropped.
+
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// Default for [`CodexConfig::max_stdout_line_bytes`].
+///
+/// [`CodexConfig::max_stdout_line_bytes`]: crate::settings::CodexConfig
+pub const DEFAULT_MAX_LINE_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// The line without its terminator, cut to the length limit. Invalid
+    /// UTF-8 is replaced rather than failing the read.
+    pub text: String,
+    /// Bytes discarded past the limit; zero for a complete line.
+    pub dropped: usize,
+}
+
+pub struct BoundedLines<R> {
+    reader: R,
+    max_len: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: AsyncBufRead + Unpin> BoundedLines<R> {
+    pub fn new(reader: R, max_len: usize) -> Self {
+        Self {
+            reader,
+            max_len: max_len.max(1),
+            buf: Vec::new(),
+        }
+    }
+
+    /// The next line, or `None` at end of input.
+    pub async fn next_line(&mut self) -> std::io::Result<Option<Line>> {
+        self.buf.clear();
+        let mut dropped = 0;
+        let mut read_any = false;
+        loop {
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                if !read_any {
+                    return Ok(None);
+                }
+                break;
+            }
+            read_any = true;
+            let newline = available.iter().position(|b| *b == b'\n');
+            let chunk = &available[..newline.unwrap_or(available.len())];
+            let keep = chunk.len().min(self.max_len - self.buf.len());
+            self.buf.extend_from_slice(&chunk[..keep]);
+            dropped += chunk.len() - keep;
+            let consumed = chunk.len() + usize::from(newline.is_some());
+            self.reader.consume(consumed);
+            if newline.is_some() {
+                break;
+            }
+        }
+        if dropped == 0 && self.buf.last() == Some(&b'\r') {
+            self.buf.pop();
+        }
+        Ok(Some(Line {
+            text: String::from_utf8_lossy(&self.buf).into_owned(),
+            dropped,
+        }))
+    }
+}
+
+/// The top-level `"id"` of the cut-off JSON-RPC response `prefix`, so the
+/// request waiting on it can be failed instead of hanging. `None` for
+/// requests and notifications (a `"method"` key) and when no id came before
+/// the `"result"` or `"error"`.
+pub fn truncated_response_id(prefix: &str) -> Option<u64> {
+    let bytes = prefix.as_bytes();
+    let mut depth = 0usize;
+    let mut id = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            b'"' => {
+                let start = i + 1;
+                let end = string_end(bytes, start)?;
+                i = end + 1;
+                let key_follows = bytes[i..].iter().find(|b| !b.is_ascii_whitespace()) == Some(&b':');
+                if depth != 1 || !key_follows {
+                    continue;
+                }
+                match &prefix[start..end] {
+                    "method" => return None,
+                    "result" | "error" => return id,
+                    "id" => {
+                        let value = prefix[i..].trim_start().strip_prefix(':')?.trim_start();
+                        let value = value.strip_prefix('"').unwrap_or(value);
+                        let digits = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+                        id = value[..digits].parse().ok();
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Index of the quote closing the string that starts at `start`.
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_id_of_a_cut_off_response() {
+        assert_eq!(truncated_response_id(r#"{"id":42,"result":{"thread":{"id":"t1","turns":[{"it"#), Some(42));
+        assert_eq!(truncated_response_id(r#"{ "jsonrpc": "2.0", "id": "7", "error": { "message": "x"#), Some(7));
+        assert_eq!(truncated_response_id(r#"{"result":{"id":3,"data":"AAAA"#), None);
+        assert_eq!(truncated_response_id(r#"{"id":9,"method":"item/tool/call","params":{"#), None);
+        assert_eq!(truncated_response_id(r#"{"method":"item/completed","params":{"id":1,"#), None);
+        assert_eq!(truncated_response_id(r#"{"note":"say \"id\": 1","id":5,"result":{"#), Some(5));
+    }
+
+    #[tokio::test]
+    async fn truncates_long_lines_and_keeps_reading() {
+        let input: &[u8] = b"{\"a\":1}\r\n0123456789abcdef\nshort\nlast";
+        let mut lines = BoundedLines::new(input, 8);
+
+        let line = |text: &str, dropped| Some(Line { text: text.to_string(), dropped });
+        assert_eq!(lines.next_line().await.unwrap(), line("{\"a\":1}", 0));
+        assert_eq!(lines.next_line().await.unwrap(), line("01234567", 8));
+        assert_eq!(lines.next_line().await.unwrap(), line("short", 0));
+        assert_eq!(lines.next_line().await.unwrap(), line("last", 0));
+        assert_eq!(lines.next_line().await.unwrap(), None);
+    }
+}
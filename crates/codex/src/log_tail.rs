@@ -0,0 +1,151 @@
+//! Following a log file from the UI, for bug reports.
+//!
+//! [`stream_log_file`] emits the last [`MAX_BACKLOG_LINES`] lines of a log
+//! as `codexia-log-line` events, then keeps emitting lines as they are
+//! appended until [`stop_log_stream`]. A file that shrinks was rotated or
+//! truncated and is read again from the start.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use codexia_shared::event_sink::EventSink;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::json;
+
+use crate::session_log::{LOG_PATH_ENV, SessionLogs, is_log_name};
+
+pub const LOG_LINE_EVENT: &str = "codexia-log-line";
+
+/// Lines of the existing content emitted when a stream starts.
+const MAX_BACKLOG_LINES: usize = 2000;
+
+static STREAMS: LazyLock<Mutex<HashMap<PathBuf, RecommendedWatcher>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The log of `thread_id` when given, which needs per-session logs, and
+/// otherwise the application log `app_log`, if the app writes one.
+pub fn resolve_log_path(
+    session_logs: &SessionLogs,
+    thread_id: Option<&str>,
+    app_log: Option<PathBuf>,
+) -> Result<PathBuf, String> {
+    match thread_id {
+        Some(thread_id) if !is_log_name(thread_id) => Err(format!("Invalid thread id '{}'", thread_id)),
+        Some(thread_id) => session_logs.thread_log_path(thread_id).ok_or_else(|| {
+            format!("Per-session logs are off; set {} to a directory to write them", LOG_PATH_ENV)
+        }),
+        None => app_log.ok_or_else(|| "This server only logs to stderr".to_string()),
+    }
+}
+
+/// Reads what was appended to a file since the last call, in whole lines.
+struct Tail {
+    path: PathBuf,
+    offset: u64,
+    /// Text after the last newline, completed by a later read.
+    partial: String,
+}
+
+impl Tail {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial: String::new(),
+        }
+    }
+
+    fn read_lines(&mut self) -> std::io::Result<Vec<String>> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        self.offset += bytes.len() as u64;
+
+        self.partial.push_str(&String::from_utf8_lossy(&bytes));
+        let Some(end) = self.partial.rfind('\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        Ok(complete.lines().map(str::to_string).collect())
+    }
+}
+
+/// Emits the end of the log at `path`, then every line appended to it.
+/// Starting a stream of a path already streamed restarts it.
+pub fn stream_log_file(event_sink: Arc<dyn EventSink>, path: &Path) -> Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("Log file not found: {}", path.display()));
+    }
+    let display = path.to_string_lossy().to_string();
+    let emit = move |sink: &dyn EventSink, line: String| {
+        sink.emit(LOG_LINE_EVENT, json!({ "path": display, "line": line }));
+    };
+
+    let mut tail = Tail::new(path.to_path_buf());
+    let backlog = tail.read_lines().map_err(|e| e.to_string())?;
+    let skip = backlog.len().saturating_sub(MAX_BACKLOG_LINES);
+    for line in backlog.into_iter().skip(skip) {
+        emit(event_sink.as_ref(), line);
+    }
+
+    let tail = Mutex::new(tail);
+    let watched = path.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if !event.paths.iter().any(|p| p == &watched) {
+            return;
+        }
+        match tail.lock().unwrap().read_lines() {
+            Ok(lines) => lines.into_iter().for_each(|line| emit(event_sink.as_ref(), line)),
+            // Gone for a moment while being rotated; the next event reads it.
+            Err(err) => log::debug!("Cannot read {}: {}", watched.display(), err),
+        }
+    })
+    .map_err(|e| format!("Failed to watch log file: {}", e))?;
+    // The directory is watched so a log recreated by rotation keeps streaming.
+    let dir = path.parent().unwrap_or(path);
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch log file: {}", e))?;
+    STREAMS.lock().unwrap().insert(path.to_path_buf(), watcher);
+    Ok(())
+}
+
+/// Stops the stream of `path`; `false` when it was not streamed.
+pub fn stop_log_stream(path: &Path) -> bool {
+    STREAMS.lock().unwrap().remove(path).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_appended_whole_lines() {
+        let path = std::env::temp_dir().join(format!("codexia-tail-{}.log", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "first\nsecond\npart").unwrap();
+        let mut tail = Tail::new(path.clone());
+        assert_eq!(tail.read_lines().unwrap(), ["first", "second"]);
+        assert!(tail.read_lines().unwrap().is_empty());
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, b"ial\nthird\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), ["partial", "third"]);
+
+        std::fs::write(&path, "rotated\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), ["rotated"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
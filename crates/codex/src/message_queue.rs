@@ -0,0 +1,240 @@
+//! Messages held back while a thread is busy.
+//!
+//! Sending a message mid-turn makes codex either reject it or mix it into
+//! the running turn. With [`CodexConfig::queue_while_busy`] on,
+//! [`send_message`] queues the text instead and the stdout reader submits
+//! the oldest queued message once the turn completes, announcing the new
+//! queue as `codex-queue-<thread_id>`.
+//!
+//! A thread counts as busy from the moment a message is sent, not only once
+//! `turn/started` arrives, so two quick sends cannot both go through.
+//!
+//! [`CodexConfig::queue_while_busy`]: crate::settings::CodexConfig
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use codexia_shared::event_sink::EventSink;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::app_server::CodexAppServer;
+use crate::error::CodexiaError;
+use crate::turn::start_turn;
+
+pub fn queue_event(thread_id: &str) -> String {
+    crate::utils::event_name("queue", thread_id)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueuedMessage {
+    pub id: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SendResult {
+    /// Set when the message was queued behind the running turn.
+    pub queued: Option<QueuedMessage>,
+    /// The `turn/start` response when the message was sent right away.
+    pub turn: Option<Value>,
+}
+
+#[derive(Default)]
+pub struct MessageQueue {
+    /// Threads with a turn running or about to start.
+    active: Mutex<HashSet<String>>,
+    queued: Mutex<HashMap<String, VecDeque<QueuedMessage>>>,
+}
+
+impl MessageQueue {
+    /// Marks `thread_id` busy; `false` when it already was.
+    fn begin_turn(&self, thread_id: &str) -> bool {
+        self.active.lock().unwrap().insert(thread_id.to_string())
+    }
+
+    fn end_turn(&self, thread_id: &str) {
+        self.active.lock().unwrap().remove(thread_id);
+    }
+
+    pub fn turn_active(&self, thread_id: &str) -> bool {
+        self.active.lock().unwrap().contains(thread_id)
+    }
+
+    fn push(&self, thread_id: &str, text: String) -> QueuedMessage {
+        let message = QueuedMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            text,
+        };
+        self.queued
+            .lock()
+            .unwrap()
+            .entry(thread_id.to_string())
+            .or_default()
+            .push_back(message.clone());
+        message
+    }
+
+    fn push_front(&self, thread_id: &str, message: QueuedMessage) {
+        self.queued
+            .lock()
+            .unwrap()
+            .entry(thread_id.to_string())
+            .or_default()
+            .push_front(message);
+    }
+
+    /// Takes the oldest queued message, leaving the thread busy when there is
+    /// one so nothing overtakes it, and idle otherwise.
+    fn next(&self, thread_id: &str) -> Option<QueuedMessage> {
+        let mut queued = self.queued.lock().unwrap();
+        let message = queued.get_mut(thread_id).and_then(VecDeque::pop_front);
+        if queued.get(thread_id).is_some_and(VecDeque::is_empty) {
+            queued.remove(thread_id);
+        }
+        if message.is_none() {
+            self.end_turn(thread_id);
+        }
+        message
+    }
+
+    pub fn queued(&self, thread_id: &str) -> Vec<QueuedMessage> {
+        self.queued
+            .lock()
+            .unwrap()
+            .get(thread_id)
+            .map(|queue| queue.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Drops the queued messages of `thread_id`, returning how many there
+    /// were. The running turn is left alone.
+    pub fn clear(&self, thread_id: &str) -> usize {
+        self.queued.lock().unwrap().remove(thread_id).map_or(0, |queue| queue.len())
+    }
+
+    fn forget_thread(&self, thread_id: &str) {
+        self.clear(thread_id);
+        self.end_turn(thread_id);
+    }
+}
+
+fn text_input(thread_id: &str, text: &str) -> Value {
+    json!({
+        "threadId": thread_id,
+        "input": [{ "type": "text", "text": text, "text_elements": [] }],
+    })
+}
+
+/// Sends `text` as a new turn of `thread_id`, or queues it while a turn is
+/// running and [`CodexConfig::queue_while_busy`] is on.
+///
+/// [`CodexConfig::queue_while_busy`]: crate::settings::CodexConfig
+pub async fn send_message(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    text: &str,
+) -> Result<SendResult, CodexiaError> {
+    crate::thread::require_thread(codex, thread_id)?;
+    if text.trim().is_empty() {
+        return Err("Message cannot be empty".into());
+    }
+    let idle = codex.messages.begin_turn(thread_id);
+    if !idle && codex.config().queue_while_busy {
+        let message = codex.messages.push(thread_id, text.to_string());
+        return Ok(SendResult {
+            queued: Some(message),
+            turn: None,
+        });
+    }
+    match start_turn(codex, text_input(thread_id, text)).await {
+        Ok(turn) => Ok(SendResult {
+            queued: None,
+            turn: Some(turn),
+        }),
+        Err(err) => {
+            if idle {
+                codex.messages.end_turn(thread_id);
+            }
+            Err(err.into())
+        }
+    }
+}
+
+fn emit_queue(codex: &CodexAppServer, event_sink: &dyn EventSink, thread_id: &str, error: Option<String>) {
+    event_sink.emit(
+        &queue_event(thread_id),
+        json!({
+            "threadId": thread_id,
+            "queued": codex.messages.queued(thread_id),
+            "error": error,
+        }),
+    );
+}
+
+/// Handles a serialized `ServerNotification`, submitting the next queued
+/// message when a turn completes.
+pub fn observe(codex: &Arc<CodexAppServer>, event_sink: &Arc<dyn EventSink>, notification: &Value) {
+    let Some(method) = notification.get("method").and_then(Value::as_str) else {
+        return;
+    };
+    let Some(thread_id) = notification
+        .get("params")
+        .and_then(|params| params.get("threadId"))
+        .and_then(Value::as_str)
+    else {
+        return;
+    };
+    match method {
+        // Turns started elsewhere (another client, a retry) count too.
+        "turn/started" => {
+            codex.messages.begin_turn(thread_id);
+        }
+        "thread/closed" => codex.messages.forget_thread(thread_id),
+        "turn/completed" => {
+            let Some(message) = codex.messages.next(thread_id) else {
+                return;
+            };
+            emit_queue(codex, event_sink.as_ref(), thread_id, None);
+            let codex = Arc::clone(codex);
+            let event_sink = Arc::clone(event_sink);
+            let thread_id = thread_id.to_string();
+            tokio::spawn(async move {
+                if let Err(err) = start_turn(&codex, text_input(&thread_id, &message.text)).await {
+                    log::warn!("Sending queued message of thread {} failed: {}", thread_id, err);
+                    // Kept for the user to retry or clear rather than lost.
+                    codex.messages.push_front(&thread_id, message);
+                    codex.messages.end_turn(&thread_id);
+                    emit_queue(&codex, event_sink.as_ref(), &thread_id, Some(err));
+                }
+            });
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_messages_until_the_turn_ends() {
+        let queue = MessageQueue::default();
+        assert!(queue.begin_turn("t1"));
+        assert!(!queue.begin_turn("t1"));
+        let first = queue.push("t1", "then run the tests".to_string());
+        let second = queue.push("t1", "and commit".to_string());
+        queue.push("t2", "other thread".to_string());
+        assert_eq!(queue.queued("t1"), [first.clone(), second.clone()]);
+
+        assert_eq!(queue.next("t1"), Some(first));
+        assert!(queue.turn_active("t1"));
+        assert_eq!(queue.next("t1"), Some(second));
+        assert_eq!(queue.next("t1"), None);
+        assert!(!queue.turn_active("t1"));
+
+        assert_eq!(queue.clear("t2"), 1);
+        assert!(queue.queued("t2").is_empty());
+        assert_eq!(queue_event("t1"), "codex-queue-t1");
+    }
+}
@@ -0,0 +1,235 @@
+//! Upgrades rollout files written by older codex versions.
+//!
+//! Early codex builds wrote a bare session header followed by bare response
+//! items (plus `record_type: "state"` markers). Current builds wrap every line
+//! in a `{ timestamp, type, payload }` envelope, which is what Codexia parses.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::sessions::{get_sessions_path, rollout_under};
+use crate::utils::parse_json_line;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RolloutFormat {
+    /// `{ timestamp, type, payload }` envelope on every line.
+    Current,
+    /// Bare `{ id, timestamp, instructions }` header and bare response items.
+    Legacy,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub file_path: String,
+    pub format: RolloutFormat,
+    pub dry_run: bool,
+    /// Whether the file needs (or received) an upgrade.
+    pub changed: bool,
+    pub lines_total: usize,
+    pub lines_converted: usize,
+    /// Legacy state markers and unparseable lines that are not carried over.
+    pub lines_dropped: usize,
+    pub backup_path: Option<String>,
+}
+
+fn is_envelope(value: &Value) -> bool {
+    value.get("type").and_then(Value::as_str).is_some() && value.get("payload").is_some()
+}
+
+/// Detects the rollout format from the first non-empty line.
+pub fn detect_format(first_line: &str) -> RolloutFormat {
+    let Some(value) = parse_json_line(first_line) else {
+        return RolloutFormat::Unknown;
+    };
+    if is_envelope(&value) {
+        RolloutFormat::Current
+    } else if value.get("id").and_then(Value::as_str).is_some()
+        && value.get("timestamp").and_then(Value::as_str).is_some()
+    {
+        RolloutFormat::Legacy
+    } else {
+        RolloutFormat::Unknown
+    }
+}
+
+/// Converts one legacy line. `None` means the line is dropped.
+fn convert_legacy_line(value: Value, timestamp: &str, is_header: bool) -> Option<Value> {
+    if is_header {
+        return Some(json!({ "timestamp": timestamp, "type": "session_meta", "payload": value }));
+    }
+    if value.get("record_type").is_some() || value.get("type").and_then(Value::as_str).is_none() {
+        return None;
+    }
+    if is_envelope(&value) {
+        return Some(value);
+    }
+    Some(json!({ "timestamp": timestamp, "type": "response_item", "payload": value }))
+}
+
+/// Returns the upgraded lines and the number of dropped input lines.
+fn migrate_legacy_lines(lines: &[&str]) -> (Vec<String>, usize) {
+    let mut output = Vec::with_capacity(lines.len());
+    let mut dropped = 0;
+    let mut timestamp = String::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let Some(value) = parse_json_line(line) else {
+            dropped += 1;
+            continue;
+        };
+        if index == 0 {
+            timestamp = value
+                .get("timestamp")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+        }
+        match convert_legacy_line(value, &timestamp, index == 0) {
+            Some(converted) => output.push(converted.to_string()),
+            None => dropped += 1,
+        }
+    }
+
+    (output, dropped)
+}
+
+/// Picks `<file>.bak`, or `<file>.bak.N` if earlier backups exist.
+fn backup_path_for(path: &Path) -> PathBuf {
+    let base = format!("{}.bak", path.display());
+    let mut candidate = PathBuf::from(&base);
+    let mut n = 1;
+    while candidate.exists() {
+        candidate = PathBuf::from(format!("{}.{}", base, n));
+        n += 1;
+    }
+    candidate
+}
+
+fn migrate_file(path: &Path, dry_run: bool) -> Result<MigrationReport, String> {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+        return Err(format!("Not a rollout file: {}", path.display()));
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    let format = lines
+        .first()
+        .map(|line| detect_format(line))
+        .unwrap_or(RolloutFormat::Unknown);
+
+    let mut report = MigrationReport {
+        file_path: path.to_string_lossy().to_string(),
+        format,
+        dry_run,
+        changed: false,
+        lines_total: lines.len(),
+        lines_converted: 0,
+        lines_dropped: 0,
+        backup_path: None,
+    };
+
+    match format {
+        RolloutFormat::Current => return Ok(report),
+        RolloutFormat::Unknown => {
+            return Err(format!("Unrecognized rollout format: {}", path.display()));
+        }
+        RolloutFormat::Legacy => {}
+    }
+
+    let (migrated, dropped) = migrate_legacy_lines(&lines);
+    report.changed = true;
+    report.lines_converted = migrated.len();
+    report.lines_dropped = dropped;
+    if dry_run {
+        return Ok(report);
+    }
+
+    let backup = backup_path_for(path);
+    fs::copy(path, &backup).map_err(|e| format!("Failed to write backup: {}", e))?;
+    report.backup_path = Some(backup.to_string_lossy().to_string());
+
+    let tmp = path.with_extension("jsonl.tmp");
+    let mut body = migrated.join("\n");
+    body.push('\n');
+    fs::write(&tmp, body).map_err(|e| format!("Failed to write migrated file: {}", e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))?;
+
+    log::info!(
+        "migrated rollout {} ({} lines, {} dropped, backup {})",
+        path.display(),
+        report.lines_converted,
+        report.lines_dropped,
+        backup.display()
+    );
+    Ok(report)
+}
+
+/// Upgrades a legacy rollout file in place, keeping a backup next to it.
+/// With `dry_run` only the report is produced and nothing is written. Files
+/// outside the sessions directory are refused.
+pub async fn migrate_session_file(file_path: String, dry_run: bool) -> Result<MigrationReport, String> {
+    let root = get_sessions_path()?;
+    tokio::task::spawn_blocking(move || migrate_file(&rollout_under(&root, Path::new(&file_path))?, dry_run))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            detect_format(r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"x"}}"#),
+            RolloutFormat::Current
+        );
+        assert_eq!(
+            detect_format(r#"{"id":"x","timestamp":"2025-05-01T10:00:00Z","instructions":null}"#),
+            RolloutFormat::Legacy
+        );
+        assert_eq!(detect_format("not json"), RolloutFormat::Unknown);
+    }
+
+    #[test]
+    fn refuses_files_outside_the_sessions_directory() {
+        let base = std::env::temp_dir().join(format!("codexia-migrate-{}", uuid::Uuid::new_v4()));
+        let root = base.join("sessions");
+        fs::create_dir_all(&root).unwrap();
+        let inside = root.join("rollout.jsonl");
+        let outside = base.join("notes.jsonl");
+        fs::write(&inside, "{}\n").unwrap();
+        fs::write(&outside, "{}\n").unwrap();
+
+        assert!(rollout_under(&root, &inside).is_ok());
+        assert!(rollout_under(&root, &outside).is_err());
+        assert!(rollout_under(&root, &root.join("..").join("notes.jsonl")).is_err());
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn wraps_legacy_lines_in_envelopes() {
+        let lines = [
+            r#"{"id":"x","timestamp":"2025-05-01T10:00:00Z","instructions":null}"#,
+            r#"{"record_type":"state"}"#,
+            r#"{"type":"message","role":"user","content":[{"type":"input_text","text":"hi"}]}"#,
+        ];
+        let (migrated, dropped) = migrate_legacy_lines(&lines);
+        assert_eq!(dropped, 1);
+        assert_eq!(migrated.len(), 2);
+
+        let header = parse_json_line(&migrated[0]).unwrap();
+        assert_eq!(header["type"], "session_meta");
+        assert_eq!(header["payload"]["id"], "x");
+
+        let item = parse_json_line(&migrated[1]).unwrap();
+        assert_eq!(item["type"], "response_item");
+        assert_eq!(item["timestamp"], "2025-05-01T10:00:00Z");
+        assert_eq!(item["payload"]["role"], "user");
+    }
+}
@@ -0,0 +1,163 @@
+//! Tolerant decoding of app-server notifications.
+//!
+//! The protocol crate is pinned to one codex release, so a newer codex can
+//! send a method it does not know, or a known method whose params no longer
+//! match (a renamed or newly required field). Unknown fields are already
+//! ignored by serde; these two cases used to drop the notification. Instead,
+//! a known method that fails to parse is forwarded as received so the UI
+//! still renders the fields it understands, and an unknown method is emitted
+//! as `codex:unknownNotification`.
+//!
+//! Which of the two it is depends only on the method: a known method whose
+//! params hold something new, such as an item type added in a later release,
+//! is drifted, not unknown, so the registries still see it.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use codex_app_server_protocol::{JSONRPCNotification, ServerNotification};
+use serde_json::{Value, json};
+
+pub const UNKNOWN_NOTIFICATION_EVENT: &str = "codex:unknownNotification";
+
+#[derive(Debug)]
+pub enum Decoded {
+    Typed(ServerNotification),
+    /// A known method whose params did not parse, as `{ method, params }`.
+    Drifted { payload: Value, error: String },
+    /// A method this build does not know, as `{ method, params }`.
+    Unknown { payload: Value },
+}
+
+fn raw_payload(notification: &JSONRPCNotification) -> Value {
+    json!({
+        "method": notification.method,
+        "params": notification.params.clone().unwrap_or(Value::Null),
+    })
+}
+
+/// The variants listed in a serde "unknown variant `x`, expected one of
+/// `a`, `b`" error.
+fn expected_variants(error: &str) -> HashSet<String> {
+    let Some((_, expected)) = error.split_once("expected one of ") else {
+        return HashSet::new();
+    };
+    expected
+        .split(", ")
+        .filter_map(|variant| variant.trim().strip_prefix('`')?.split('`').next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Every method of [`ServerNotification`], read from the error serde gives
+/// for a method it cannot know, so the set follows the protocol crate.
+static KNOWN_METHODS: LazyLock<HashSet<String>> = LazyLock::new(|| {
+    let probe = serde_json::from_value::<JSONRPCNotification>(json!({ "method": "codexia/probe", "params": {} }));
+    match probe.map(ServerNotification::try_from) {
+        Ok(Err(err)) => expected_variants(&err.to_string()),
+        _ => HashSet::new(),
+    }
+});
+
+/// Whether `method` is outside [`ServerNotification`]. Without the method
+/// list, falls back to serde naming that very method as the unknown variant.
+fn is_unknown_method(method: &str, error: &str) -> bool {
+    if KNOWN_METHODS.is_empty() {
+        return error.contains(&format!("unknown variant `{}`", method));
+    }
+    !KNOWN_METHODS.contains(method)
+}
+
+pub fn decode(notification: JSONRPCNotification) -> Decoded {
+    let payload = raw_payload(&notification);
+    let method = notification.method.clone();
+    match ServerNotification::try_from(notification) {
+        Ok(typed) => Decoded::Typed(typed),
+        Err(err) => {
+            let error = err.to_string();
+            if is_unknown_method(&method, &error) {
+                Decoded::Unknown { payload }
+            } else {
+                Decoded::Drifted { payload, error }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(value: Value) -> JSONRPCNotification {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn extra_fields_still_parse() {
+        let decoded = decode(notification(json!({
+            "method": "item/agentMessage/delta",
+            "params": {
+                "threadId": "t1",
+                "turnId": "u1",
+                "itemId": "m1",
+                "delta": "hi",
+                "addedInAFutureRelease": { "nested": [1, 2] },
+            },
+        })));
+        let Decoded::Typed(typed) = decoded else {
+            panic!("expected a typed notification, got {:?}", decoded);
+        };
+        let payload = serde_json::to_value(typed).unwrap();
+        assert_eq!(payload["params"]["delta"], "hi");
+    }
+
+    #[test]
+    fn changed_params_are_forwarded_as_received() {
+        let decoded = decode(notification(json!({
+            "method": "item/agentMessage/delta",
+            "params": { "threadId": "t1", "text": "renamed from delta" },
+        })));
+        let Decoded::Drifted { payload, .. } = decoded else {
+            panic!("expected a drifted notification, got {:?}", decoded);
+        };
+        assert_eq!(payload["method"], "item/agentMessage/delta");
+        assert_eq!(payload["params"]["threadId"], "t1");
+    }
+
+    #[test]
+    fn new_nested_variants_of_known_methods_are_drifted() {
+        assert!(KNOWN_METHODS.contains("item/started"));
+        assert!(KNOWN_METHODS.contains("turn/completed"));
+        let decoded = decode(notification(json!({
+            "method": "item/started",
+            "params": {
+                "threadId": "t1",
+                "turnId": "u1",
+                "item": { "type": "hologram", "id": "i1" },
+            },
+        })));
+        let Decoded::Drifted { payload, .. } = decoded else {
+            panic!("expected a drifted notification, got {:?}", decoded);
+        };
+        assert_eq!(payload["params"]["item"]["type"], "hologram");
+        assert_eq!(
+            expected_variants("unknown variant `x`, expected one of `turn/started`, `turn/completed`"),
+            HashSet::from(["turn/started".to_string(), "turn/completed".to_string()])
+        );
+    }
+
+    #[test]
+    fn unknown_methods_are_kept() {
+        let decoded = decode(notification(json!({
+            "method": "thread/hologram/updated",
+            "params": { "threadId": "t1", "frames": 3 },
+        })));
+        let Decoded::Unknown { payload } = decoded else {
+            panic!("expected an unknown notification, got {:?}", decoded);
+        };
+        assert_eq!(payload["params"]["frames"], 3);
+
+        let decoded = decode(notification(json!({ "method": "server/ping" })));
+        assert!(matches!(decoded, Decoded::Unknown { payload } if payload["params"].is_null()));
+    }
+}
@@ -0,0 +1,512 @@
+//! File changes codex proposes and applies.
+//!
+//! A `fileChange` item is kept from `item/started` until it completes, so a
+//! patch approval can be shown with its full diff. The affected files are
+//! snapshotted when the item starts and again once it is applied, which lets
+//! [`undo_last_patch`] restore them as long as nobody touched them since.
+//! The snapshots are read on a separate thread, in notification order, so
+//! large patches do not hold up the stdout reader.
+//!
+//! In preview mode, patch approvals are not shown one by one but collected
+//! per thread until [`apply_previewed_patches`] accepts the whole batch.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use codex_app_server_protocol::RequestId;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_server::CodexAppServer;
+use crate::threads::ThreadRegistry;
+
+/// Applied patches remembered per thread for undo.
+const MAX_UNDO_DEPTH: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PatchFile {
+    pub path: String,
+    /// `add`, `delete` or `update`.
+    pub kind: String,
+    pub move_path: Option<String>,
+    pub diff: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PatchPreview {
+    pub approval_id: RequestId,
+    pub thread_id: String,
+    pub item_id: String,
+    pub files: Vec<PatchFile>,
+}
+
+/// Added and removed line counts. New and deleted files carry their whole
+/// content as the diff, so every line counts.
+fn count_lines(kind: &str, diff: &str) -> (usize, usize) {
+    match kind {
+        "add" => (diff.lines().count(), 0),
+        "delete" => (0, diff.lines().count()),
+        _ => diff.lines().fold((0, 0), |(added, removed), line| {
+            if line.starts_with("+++") || line.starts_with("---") {
+                (added, removed)
+            } else if line.starts_with('+') {
+                (added + 1, removed)
+            } else if line.starts_with('-') {
+                (added, removed + 1)
+            } else {
+                (added, removed)
+            }
+        }),
+    }
+}
+
+/// Parses the `changes` of a `fileChange` item.
+pub fn patch_files(changes: &Value) -> Vec<PatchFile> {
+    let Some(changes) = changes.as_array() else {
+        return Vec::new();
+    };
+    changes
+        .iter()
+        .filter_map(|change| {
+            let path = change.get("path")?.as_str()?.to_string();
+            let kind_value = change.get("kind");
+            let kind = kind_value
+                .and_then(|k| k.get("type").or(Some(k)))
+                .and_then(Value::as_str)
+                .unwrap_or("update")
+                .to_string();
+            let move_path = kind_value
+                .and_then(|k| k.get("move_path"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let diff = change
+                .get("diff")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let (added, removed) = count_lines(&kind, &diff);
+            Some(PatchFile {
+                path,
+                kind,
+                move_path,
+                diff,
+                added,
+                removed,
+            })
+        })
+        .collect()
+}
+
+/// Contents of one affected file; `None` when it does not exist.
+struct FileSnapshot {
+    path: PathBuf,
+    before: Option<Vec<u8>>,
+    after: Option<Vec<u8>>,
+}
+
+struct AppliedPatch {
+    snapshots: Vec<FileSnapshot>,
+}
+
+type AppliedHistory = Arc<Mutex<HashMap<String, Vec<AppliedPatch>>>>;
+
+enum SnapshotJob {
+    Started { item_id: String, paths: Vec<PathBuf> },
+    /// `thread_id` is set when the patch was applied.
+    Completed { item_id: String, thread_id: Option<String> },
+    Forget { thread_id: String },
+    #[cfg(test)]
+    Flush(Sender<()>),
+}
+
+/// Reads the snapshots of each job in turn, so a patch's files are always
+/// read before it is applied and again after.
+fn run_snapshots(jobs: Receiver<SnapshotJob>, applied: AppliedHistory) {
+    let mut started: HashMap<String, Vec<FileSnapshot>> = HashMap::new();
+    for job in jobs {
+        match job {
+            SnapshotJob::Started { item_id, paths } => {
+                let snapshots = paths
+                    .into_iter()
+                    .map(|path| FileSnapshot {
+                        before: std::fs::read(&path).ok(),
+                        after: None,
+                        path,
+                    })
+                    .collect();
+                started.insert(item_id, snapshots);
+            }
+            SnapshotJob::Completed { item_id, thread_id } => {
+                let (Some(snapshots), Some(thread_id)) = (started.remove(&item_id), thread_id) else {
+                    continue;
+                };
+                let snapshots = snapshots
+                    .into_iter()
+                    .map(|snapshot| FileSnapshot {
+                        after: std::fs::read(&snapshot.path).ok(),
+                        ..snapshot
+                    })
+                    .collect();
+                let mut applied = applied.lock().unwrap();
+                let history = applied.entry(thread_id).or_default();
+                history.push(AppliedPatch { snapshots });
+                if history.len() > MAX_UNDO_DEPTH {
+                    history.remove(0);
+                }
+            }
+            SnapshotJob::Forget { thread_id } => {
+                applied.lock().unwrap().remove(&thread_id);
+            }
+            #[cfg(test)]
+            SnapshotJob::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PatchRegistry {
+    /// Files of in-flight `fileChange` items, by item id.
+    proposed: Mutex<HashMap<String, Vec<PatchFile>>>,
+    applied: AppliedHistory,
+    /// Patch approvals held back in preview mode, per thread, oldest first.
+    previewed: Mutex<HashMap<String, Vec<PatchPreview>>>,
+    /// Jobs for the snapshot thread, started with the first patch.
+    snapshots: Mutex<Option<Sender<SnapshotJob>>>,
+}
+
+/// Every path a change touches, resolved against the thread's `cwd`.
+fn affected_paths(files: &[PatchFile], cwd: Option<&Path>) -> Vec<PathBuf> {
+    let resolve = |path: &str| {
+        let path = Path::new(path);
+        match cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        }
+    };
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for file in files {
+        for path in std::iter::once(&file.path).chain(file.move_path.as_ref()) {
+            let path = resolve(path);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+impl PatchRegistry {
+    /// Files of the in-flight `fileChange` item `item_id`.
+    pub fn proposed(&self, item_id: &str) -> Option<Vec<PatchFile>> {
+        self.proposed.lock().unwrap().get(item_id).cloned()
+    }
+
+    fn snapshot(&self, job: SnapshotJob) {
+        let mut sender = self.snapshots.lock().unwrap();
+        let sender = sender.get_or_insert_with(|| {
+            let (sender, jobs) = std::sync::mpsc::channel();
+            let applied = Arc::clone(&self.applied);
+            if let Err(err) = std::thread::Builder::new()
+                .name("codexia-patch-snapshots".to_string())
+                .spawn(move || run_snapshots(jobs, applied))
+            {
+                log::warn!("Failed to start the patch snapshot thread: {}", err);
+            }
+            sender
+        });
+        if sender.send(job).is_err() {
+            log::warn!("Patch snapshot thread is gone; undo will miss this patch");
+        }
+    }
+
+    /// Waits until the snapshot thread has handled every job sent so far.
+    #[cfg(test)]
+    fn flush(&self) {
+        let (done, wait) = std::sync::mpsc::channel();
+        self.snapshot(SnapshotJob::Flush(done));
+        let _ = wait.recv();
+    }
+
+    /// Handles a serialized `ServerNotification`. `threads` supplies the
+    /// working directory relative change paths are resolved against.
+    pub fn observe(&self, notification: &Value, threads: &ThreadRegistry) {
+        let Some(method) = notification.get("method").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(params) = notification.get("params") else {
+            return;
+        };
+        let thread_id = params.get("threadId").and_then(Value::as_str);
+        if method == "thread/closed" {
+            if let Some(thread_id) = thread_id {
+                self.snapshot(SnapshotJob::Forget {
+                    thread_id: thread_id.to_string(),
+                });
+                self.previewed.lock().unwrap().remove(thread_id);
+            }
+            return;
+        }
+        let Some(item) = params.get("item") else {
+            return;
+        };
+        if item.get("type").and_then(Value::as_str) != Some("fileChange") {
+            return;
+        }
+        let Some(item_id) = item.get("id").and_then(Value::as_str) else {
+            return;
+        };
+
+        match method {
+            "item/started" => {
+                let files = item.get("changes").map(patch_files).unwrap_or_default();
+                let cwd = thread_id.and_then(|id| threads.cwd(id));
+                let paths = affected_paths(&files, cwd.as_deref());
+                self.proposed.lock().unwrap().insert(item_id.to_string(), files);
+                self.snapshot(SnapshotJob::Started {
+                    item_id: item_id.to_string(),
+                    paths,
+                });
+            }
+            "item/completed" => {
+                if self.proposed.lock().unwrap().remove(item_id).is_none() {
+                    return;
+                }
+                let applied = item.get("status").and_then(Value::as_str) == Some("completed");
+                self.snapshot(SnapshotJob::Completed {
+                    item_id: item_id.to_string(),
+                    thread_id: thread_id.filter(|_| applied).map(str::to_string),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Holds a patch approval for batch review and returns the thread's
+    /// whole batch so far.
+    ///
+    /// codex waits for each approval before it continues, so a batch gathers
+    /// the patches proposed before the turn blocks, e.g. parallel tool calls.
+    pub(crate) fn add_preview(&self, preview: PatchPreview) -> Vec<PatchPreview> {
+        let mut previewed = self.previewed.lock().unwrap();
+        let batch = previewed.entry(preview.thread_id.clone()).or_default();
+        batch.push(preview);
+        batch.clone()
+    }
+
+    /// Patches of `thread_id` waiting for batch review.
+    pub fn previewed(&self, thread_id: &str) -> Vec<PatchPreview> {
+        self.previewed
+            .lock()
+            .unwrap()
+            .get(thread_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn take_previewed(&self, thread_id: &str) -> Vec<PatchPreview> {
+        self.previewed
+            .lock()
+            .unwrap()
+            .remove(thread_id)
+            .unwrap_or_default()
+    }
+
+    /// Restores the files of the last patch applied in `thread_id` and
+    /// returns their paths. Fails without touching anything if any of them
+    /// changed since the patch was applied.
+    pub fn undo_last(&self, thread_id: &str) -> Result<Vec<String>, String> {
+        let mut applied = self.applied.lock().unwrap();
+        let patch = applied
+            .get(thread_id)
+            .and_then(|history| history.last())
+            .ok_or_else(|| format!("No applied patch to undo for thread {}", thread_id))?;
+
+        for snapshot in &patch.snapshots {
+            if std::fs::read(&snapshot.path).ok() != snapshot.after {
+                return Err(format!(
+                    "{} has changed since the patch was applied; refusing to undo",
+                    snapshot.path.display()
+                ));
+            }
+        }
+
+        let mut reverted = Vec::new();
+        for snapshot in &patch.snapshots {
+            let result = match &snapshot.before {
+                Some(content) => snapshot
+                    .path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(&snapshot.path, content)),
+                None if snapshot.path.exists() => std::fs::remove_file(&snapshot.path),
+                None => Ok(()),
+            };
+            result.map_err(|e| format!("Failed to restore {}: {}", snapshot.path.display(), e))?;
+            reverted.push(snapshot.path.to_string_lossy().to_string());
+        }
+
+        if let Some(history) = applied.get_mut(thread_id) {
+            history.pop();
+        }
+        Ok(reverted)
+    }
+}
+
+/// Reverts the most recent patch codex applied in `thread_id`.
+pub fn undo_last_patch(codex: &CodexAppServer, thread_id: &str) -> Result<Vec<String>, String> {
+    codex.patches.undo_last(thread_id)
+}
+
+/// Builds the preview of a pending patch approval, if its item was seen.
+pub(crate) fn patch_preview(
+    codex: &CodexAppServer,
+    approval_id: &RequestId,
+    thread_id: &str,
+    item_id: &str,
+) -> Option<PatchPreview> {
+    let files = codex.patches.proposed(item_id)?;
+    Some(PatchPreview {
+        approval_id: approval_id.clone(),
+        thread_id: thread_id.to_string(),
+        item_id: item_id.to_string(),
+        files,
+    })
+}
+
+/// Accepts every patch held for review in `thread_id`. Patches whose approval
+/// was already answered, e.g. auto-declined or interrupted, are skipped.
+pub async fn apply_previewed_patches(
+    codex: &CodexAppServer,
+    thread_id: &str,
+) -> Vec<crate::approvals::ApprovalResult> {
+    let batch = codex.patches.take_previewed(thread_id);
+    let mut results = Vec::new();
+    for preview in batch {
+        let Some(pending) = codex.approvals.get(&preview.approval_id) else {
+            continue;
+        };
+        let result = crate::approvals::respond_with_source(
+            codex,
+            preview.approval_id.clone(),
+            serde_json::json!({ "decision": "accept" }),
+            crate::approvals::DecisionSource::PreviewBatch,
+        )
+        .await;
+        results.push(crate::approvals::ApprovalResult {
+            request_id: preview.approval_id,
+            kind: pending.kind,
+            ok: result.is_ok(),
+            error: result.err(),
+        });
+    }
+    results
+}
+
+/// The proposed diff of a file-change approval `thread_id` is waiting on.
+pub fn get_pending_patch(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    approval_id: &RequestId,
+) -> Result<PatchPreview, String> {
+    let pending = codex
+        .approvals
+        .get(approval_id)
+        .filter(|p| p.thread_id.as_deref() == Some(thread_id))
+        .ok_or_else(|| format!("No pending approval {:?} for thread {}", approval_id, thread_id))?;
+    let item_id = pending
+        .item_id
+        .as_deref()
+        .filter(|_| pending.kind == crate::approvals::ApprovalKind::FileChange)
+        .ok_or_else(|| format!("Approval {:?} is not a file change", approval_id))?;
+    patch_preview(codex, approval_id, thread_id, item_id)
+        .ok_or_else(|| format!("Patch for approval {:?} is no longer available", approval_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tracks_proposed_changes_until_completion() {
+        let registry = PatchRegistry::default();
+        let threads = ThreadRegistry::default();
+        let item = json!({
+            "type": "fileChange",
+            "id": "item-1",
+            "status": "inProgress",
+            "changes": [
+                { "path": "src/lib.rs", "kind": { "type": "update", "move_path": null }, "diff": "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1,2 @@\n-old\n+new\n+more\n" },
+                { "path": "NEW.md", "kind": { "type": "add" }, "diff": "one\ntwo\n" },
+            ],
+        });
+        registry.observe(&json!({ "method": "item/started", "params": { "threadId": "t1", "turnId": "u1", "item": item } }), &threads);
+
+        let files = registry.proposed("item-1").unwrap();
+        assert_eq!((files[0].added, files[0].removed), (2, 1));
+        assert_eq!((files[1].kind.as_str(), files[1].added), ("add", 2));
+
+        registry.observe(&json!({ "method": "item/completed", "params": { "threadId": "t1", "turnId": "u1", "item": item } }), &threads);
+        assert!(registry.proposed("item-1").is_none());
+    }
+
+    #[test]
+    fn batches_previewed_patches_per_thread() {
+        let registry = PatchRegistry::default();
+        let threads = ThreadRegistry::default();
+        let preview = |id: i64, thread_id: &str| PatchPreview {
+            approval_id: RequestId::Integer(id),
+            thread_id: thread_id.to_string(),
+            item_id: format!("item-{}", id),
+            files: Vec::new(),
+        };
+        registry.add_preview(preview(1, "t1"));
+        registry.add_preview(preview(2, "t2"));
+        let batch = registry.add_preview(preview(3, "t1"));
+        assert_eq!(batch.iter().map(|p| p.item_id.as_str()).collect::<Vec<_>>(), ["item-1", "item-3"]);
+
+        registry.observe(&json!({ "method": "thread/closed", "params": { "threadId": "t1" } }), &threads);
+        assert!(registry.previewed("t1").is_empty());
+        assert_eq!(registry.take_previewed("t2").len(), 1);
+        assert!(registry.previewed("t2").is_empty());
+    }
+
+    #[test]
+    fn undoes_last_patch_unless_files_diverged() {
+        let dir = std::env::temp_dir().join(format!("codexia-patches-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.txt");
+        std::fs::write(&file, "before\n").unwrap();
+
+        let registry = PatchRegistry::default();
+        let threads = ThreadRegistry::default();
+        let item = json!({
+            "type": "fileChange",
+            "id": "item-1",
+            "status": "completed",
+            "changes": [{ "path": file.to_string_lossy(), "kind": { "type": "update", "move_path": null }, "diff": "-before\n+after\n" }],
+        });
+        let notify = |method: &str| json!({ "method": method, "params": { "threadId": "t1", "turnId": "u1", "item": item } });
+
+        registry.observe(&notify("item/started"), &threads);
+        registry.flush();
+        std::fs::write(&file, "after\n").unwrap();
+        registry.observe(&notify("item/completed"), &threads);
+        registry.flush();
+
+        std::fs::write(&file, "edited\n").unwrap();
+        assert!(registry.undo_last("t1").is_err());
+
+        std::fs::write(&file, "after\n").unwrap();
+        assert_eq!(registry.undo_last("t1").unwrap().len(), 1);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "before\n");
+        assert!(registry.undo_last("t1").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -66,7 +66,7 @@ pub async fn load_env_keys() -> Result<Vec<EnvStatusItem>, String> {
     let mut result = Vec::new();
 
     for provider in config.data {
-        let is_env_set = get_env(provider.env_key.clone()).is_ok();
+        let is_env_set = get_env(provider.env_key.clone()).await.is_ok();
 
         result.push(EnvStatusItem {
             provider: provider.model_provider,
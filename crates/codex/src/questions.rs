@@ -0,0 +1,169 @@
+//! Clarifying questions codex asks mid-turn (`item/tool/requestUserInput`).
+//!
+//! The turn is blocked until they are answered, which without a prompt
+//! looks like a hung session. Each question request is remembered per
+//! thread and announced as `codex-question-<thread_id>`, and
+//! [`answer_question`] answers the oldest one with free text.
+
+use std::sync::Mutex;
+
+use codex_app_server_protocol::RequestId;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::app_server::CodexAppServer;
+use crate::error::CodexiaError;
+
+pub fn question_event(thread_id: &str) -> String {
+    crate::utils::event_name("question", thread_id)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingQuestion {
+    pub request_id: RequestId,
+    pub thread_id: String,
+    /// Ids of the questions in the request; every one gets the answer.
+    pub question_ids: Vec<String>,
+}
+
+/// Unanswered question requests, oldest first.
+#[derive(Default)]
+pub struct QuestionRegistry {
+    pending: Mutex<Vec<PendingQuestion>>,
+}
+
+impl QuestionRegistry {
+    /// Records the `item/tool/requestUserInput` params of `request_id`.
+    pub(crate) fn insert(&self, request_id: RequestId, params: &Value) -> Option<PendingQuestion> {
+        let thread_id = params.get("threadId")?.as_str()?.to_string();
+        let question_ids = params
+            .get("questions")
+            .and_then(Value::as_array)
+            .map(|questions| {
+                questions
+                    .iter()
+                    .filter_map(|q| q.get("id")?.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let question = PendingQuestion {
+            request_id,
+            thread_id,
+            question_ids,
+        };
+        self.pending.lock().unwrap().push(question.clone());
+        Some(question)
+    }
+
+    fn take_oldest(&self, thread_id: &str) -> Option<PendingQuestion> {
+        let mut pending = self.pending.lock().unwrap();
+        let index = pending.iter().position(|q| q.thread_id == thread_id)?;
+        Some(pending.remove(index))
+    }
+
+    pub fn pending(&self, thread_id: &str) -> Vec<PendingQuestion> {
+        let pending = self.pending.lock().unwrap();
+        pending.iter().filter(|q| q.thread_id == thread_id).cloned().collect()
+    }
+
+    fn resolve(&self, request_id: &RequestId) {
+        self.pending.lock().unwrap().retain(|q| &q.request_id != request_id);
+    }
+
+    fn forget_thread(&self, thread_id: &str) {
+        self.pending.lock().unwrap().retain(|q| q.thread_id != thread_id);
+    }
+
+    /// Drops requests codex resolved, answered through another path or
+    /// abandoned with their thread.
+    pub fn observe(&self, notification: &Value) {
+        let Some(params) = notification.get("params") else {
+            return;
+        };
+        match notification.get("method").and_then(Value::as_str) {
+            Some("serverRequest/resolved") => {
+                if let Some(request_id) = params
+                    .get("requestId")
+                    .and_then(|id| serde_json::from_value::<RequestId>(id.clone()).ok())
+                {
+                    self.resolve(&request_id);
+                }
+            }
+            Some("thread/closed") => {
+                if let Some(thread_id) = params.get("threadId").and_then(Value::as_str) {
+                    self.forget_thread(thread_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The `item/tool/requestUserInput` response giving `text` as the answer to
+/// each of `question`'s questions.
+fn answer_response(question: &PendingQuestion, text: &str) -> Value {
+    let answers: serde_json::Map<String, Value> = question
+        .question_ids
+        .iter()
+        .map(|id| (id.clone(), json!({ "answers": [text] })))
+        .collect();
+    json!({ "answers": answers })
+}
+
+/// Answers the oldest question `thread_id` is waiting on with `text`.
+pub async fn answer_question(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    text: &str,
+) -> Result<PendingQuestion, CodexiaError> {
+    crate::thread::require_thread(codex, thread_id)?;
+    if text.trim().is_empty() {
+        return Err("Answer cannot be empty".into());
+    }
+    let question = codex
+        .questions
+        .take_oldest(thread_id)
+        .ok_or_else(|| format!("Session {} has no question waiting for an answer", thread_id))?;
+    let response = answer_response(&question, text);
+    if let Err(err) = codex.send_response(question.request_id.clone(), response).await {
+        codex.questions.pending.lock().unwrap().insert(0, question);
+        return Err(err.into());
+    }
+    Ok(question)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_oldest_question_of_thread() {
+        let registry = QuestionRegistry::default();
+        let params = |thread: &str, ids: &[&str]| {
+            json!({
+                "threadId": thread,
+                "turnId": "u1",
+                "itemId": "i1",
+                "questions": ids.iter().map(|id| json!({ "id": id, "question": "?" })).collect::<Vec<_>>(),
+            })
+        };
+        registry.insert(RequestId::Integer(1), &params("t1", &["scope", "target"]));
+        registry.insert(RequestId::Integer(2), &params("t2", &["name"]));
+        registry.insert(RequestId::Integer(3), &params("t1", &["confirm"]));
+
+        let first = registry.take_oldest("t1").unwrap();
+        assert_eq!(first.request_id, RequestId::Integer(1));
+        assert_eq!(
+            answer_response(&first, "only the api crate"),
+            json!({ "answers": {
+                "scope": { "answers": ["only the api crate"] },
+                "target": { "answers": ["only the api crate"] },
+            } })
+        );
+
+        registry.observe(&json!({ "method": "serverRequest/resolved", "params": { "threadId": "t1", "requestId": 3 } }));
+        assert!(registry.pending("t1").is_empty());
+        registry.observe(&json!({ "method": "thread/closed", "params": { "threadId": "t2" } }));
+        assert!(registry.take_oldest("t2").is_none());
+    }
+}
@@ -0,0 +1,145 @@
+//! Which models and providers accept codex's reasoning config keys.
+//!
+//! Local OSS servers and many OpenAI-compatible providers reject reasoning
+//! parameters, so `thread/start` drops those keys unless the selected model or
+//! provider is known to support them. The built-in map can be extended or
+//! overridden through [`CodexConfig::reasoning_capabilities`].
+//!
+//! [`CodexConfig::reasoning_capabilities`]: crate::settings::CodexConfig
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::settings::CodexConfig;
+
+/// `config` keys that only make sense for reasoning models.
+const REASONING_KEYS: &[&str] = &[
+    "show_raw_agent_reasoning",
+    "model_reasoning_summary",
+    "model_reasoning_effort",
+];
+
+/// Model and provider names mapped to whether they support reasoning. A model
+/// key ending in `*` matches by prefix.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReasoningCapabilities {
+    pub models: BTreeMap<String, bool>,
+    pub providers: BTreeMap<String, bool>,
+}
+
+fn builtin_capabilities() -> ReasoningCapabilities {
+    let models = ["gpt-5*", "o1*", "o3*", "o4*", "codex-*"]
+        .into_iter()
+        .map(|model| (model.to_string(), true))
+        .collect();
+    let providers = [("openai", true), ("oss", false), ("ollama", false), ("lmstudio", false)]
+        .into_iter()
+        .map(|(provider, supported)| (provider.to_string(), supported))
+        .collect();
+    ReasoningCapabilities { models, providers }
+}
+
+/// The built-in map with the user's overrides applied.
+pub fn reasoning_capabilities(config: &CodexConfig) -> ReasoningCapabilities {
+    let mut capabilities = builtin_capabilities();
+    let overrides = &config.reasoning_capabilities;
+    capabilities.models.extend(overrides.models.clone());
+    capabilities.providers.extend(overrides.providers.clone());
+    capabilities
+}
+
+impl ReasoningCapabilities {
+    fn model_support(&self, model: &str) -> Option<bool> {
+        if let Some(supported) = self.models.get(model) {
+            return Some(*supported);
+        }
+        // The longest matching prefix wins, so `gpt-5-mini*` can override `gpt-5*`.
+        self.models
+            .iter()
+            .filter_map(|(key, supported)| Some((key.strip_suffix('*')?, *supported)))
+            .filter(|(prefix, _)| model.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, supported)| supported)
+    }
+
+    /// Whether `model` on `provider` accepts reasoning keys. An unset provider
+    /// is codex's default, `openai`; anything unknown is assumed not to.
+    pub fn supports(&self, provider: Option<&str>, model: Option<&str>) -> bool {
+        let provider = provider.unwrap_or("openai");
+        // A provider marked unsupported wins: gpt-oss served by Ollama still
+        // rejects the keys.
+        match self.providers.get(provider) {
+            Some(false) => false,
+            provider_support => model
+                .and_then(|model| self.model_support(model))
+                .or(provider_support.copied())
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Removes reasoning keys from `params.config` when the selected model does
+/// not support them.
+pub fn strip_unsupported_reasoning(capabilities: &ReasoningCapabilities, params: &mut Value) {
+    let provider = params.get("modelProvider").and_then(Value::as_str);
+    let model = params.get("model").and_then(Value::as_str);
+    if capabilities.supports(provider, model) {
+        return;
+    }
+    let label = format!(
+        "{}/{}",
+        provider.unwrap_or("openai"),
+        model.unwrap_or("default")
+    );
+    let Some(Value::Object(config)) = params.get_mut("config") else {
+        return;
+    };
+    let removed: Vec<&str> = REASONING_KEYS
+        .iter()
+        .copied()
+        .filter(|key| config.remove(*key).is_some())
+        .collect();
+    if !removed.is_empty() {
+        log::info!(
+            "{} is not known to support reasoning, skipping {}",
+            label,
+            removed.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strips_reasoning_keys_for_unsupported_models() {
+        let capabilities = builtin_capabilities();
+        assert!(capabilities.supports(None, Some("gpt-5-codex")));
+        assert!(!capabilities.supports(Some("oss"), Some("gpt-oss:20b")));
+        assert!(!capabilities.supports(Some("openrouter"), Some("llama-3")));
+
+        let mut params = json!({
+            "model": "qwen3",
+            "modelProvider": "ollama",
+            "config": { "show_raw_agent_reasoning": true, "model_reasoning_summary": "auto", "view_image_tool": true },
+        });
+        strip_unsupported_reasoning(&capabilities, &mut params);
+        assert_eq!(params["config"], json!({ "view_image_tool": true }));
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let mut config = CodexConfig::default();
+        config.reasoning_capabilities.models.insert("deepseek-r1*".to_string(), true);
+        config.reasoning_capabilities.models.insert("o3-mini".to_string(), false);
+        let capabilities = reasoning_capabilities(&config);
+        assert!(capabilities.supports(Some("openrouter"), Some("deepseek-r1-distill")));
+        assert!(!capabilities.supports(None, Some("o3-mini")));
+        assert!(capabilities.supports(None, Some("o3-pro")));
+    }
+}
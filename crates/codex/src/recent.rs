@@ -0,0 +1,82 @@
+//! Bounded history of the notifications each thread received.
+//!
+//! Events are only emitted live, so a window that attaches to a thread
+//! mid-turn backfills from [`RecentEvents::recent`] before subscribing.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+/// Notifications kept per thread; the oldest are dropped first.
+const MAX_RECENT_EVENTS: usize = 1000;
+
+#[derive(Default)]
+pub struct RecentEvents {
+    events: Mutex<HashMap<String, VecDeque<Value>>>,
+}
+
+impl RecentEvents {
+    /// Records a serialized `ServerNotification`. A closed thread's history
+    /// is dropped.
+    pub fn observe(&self, notification: &Value) {
+        let Some(params) = notification.get("params") else {
+            return;
+        };
+        let Some(thread_id) = params
+            .get("threadId")
+            .and_then(Value::as_str)
+            .or_else(|| params.get("thread")?.get("id")?.as_str())
+        else {
+            return;
+        };
+
+        let mut events = self.events.lock().unwrap();
+        if notification.get("method").and_then(Value::as_str) == Some("thread/closed") {
+            events.remove(thread_id);
+            return;
+        }
+        let history = events.entry(thread_id.to_string()).or_default();
+        if history.len() == MAX_RECENT_EVENTS {
+            history.pop_front();
+        }
+        history.push_back(notification.clone());
+    }
+
+    /// The last `limit` notifications of `thread_id`, oldest first.
+    pub fn recent(&self, thread_id: &str, limit: usize) -> Vec<Value> {
+        let events = self.events.lock().unwrap();
+        let Some(history) = events.get(thread_id) else {
+            return Vec::new();
+        };
+        history
+            .iter()
+            .skip(history.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_bounded_history_per_thread() {
+        let recent = RecentEvents::default();
+        for i in 0..MAX_RECENT_EVENTS + 5 {
+            recent.observe(&json!({ "method": "item/agentMessage/delta", "params": { "threadId": "t1", "delta": i } }));
+        }
+        recent.observe(&json!({ "method": "turn/started", "params": { "threadId": "t2", "turn": { "id": "u1" } } }));
+
+        let last = recent.recent("t1", 2);
+        assert_eq!(last.len(), 2);
+        assert_eq!(last[1]["params"]["delta"], MAX_RECENT_EVENTS + 4);
+        assert_eq!(recent.recent("t1", usize::MAX).len(), MAX_RECENT_EVENTS);
+        assert_eq!(recent.recent("t2", 10).len(), 1);
+
+        recent.observe(&json!({ "method": "thread/closed", "params": { "threadId": "t1" } }));
+        assert!(recent.recent("t1", 10).is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+//! Resubmits turns that failed because the provider rate-limited them.
+//!
+//! Off unless [`CodexConfig::auto_retry_rate_limit`] is set. The app-server
+//! reports a failed turn with an `error` notification; when that error is a
+//! rate limit codex itself will not retry, the thread's last `turn/start` is
+//! sent again after an exponential backoff. Progress is emitted as
+//! `codex:rateLimitRetry` so the UI can show "retrying in Ns".
+//!
+//! [`CodexConfig::auto_retry_rate_limit`]: crate::settings::CodexConfig
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use codexia_shared::event_sink::EventSink;
+use serde_json::{Value, json};
+
+use crate::app_server::CodexAppServer;
+use crate::turn::start_turn;
+
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+pub const RATE_LIMIT_RETRY_EVENT: &str = "codex:rateLimitRetry";
+
+/// Delay before retry number `attempt` (1-based): 2s, 4s, 8s, ... up to a
+/// minute.
+fn backoff_delay(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    BASE_RETRY_DELAY.saturating_mul(factor).min(MAX_RETRY_DELAY)
+}
+
+fn has_status(value: &Value, status: u64) -> bool {
+    match value {
+        Value::Object(map) => map.iter().any(|(key, entry)| {
+            (key == "httpStatusCode" && entry.as_u64() == Some(status)) || has_status(entry, status)
+        }),
+        _ => false,
+    }
+}
+
+/// Whether a `TurnError` is a transient rate limit. Exhausted plan usage
+/// (`usageLimitExceeded`) is not: retrying cannot succeed until it resets.
+fn is_rate_limited(error: &Value) -> bool {
+    let info = error.get("codexErrorInfo").unwrap_or(&Value::Null);
+    if info.as_str() == Some("usageLimitExceeded") {
+        return false;
+    }
+    if has_status(info, 429) {
+        return true;
+    }
+    let message = error
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    message.contains("rate limit") || message.contains("too many requests")
+}
+
+#[derive(Default)]
+pub struct RetryRegistry {
+    /// `turn/start` params last sent per thread.
+    last_input: Mutex<HashMap<String, Value>>,
+    /// Consecutive rate-limit retries per thread.
+    attempts: Mutex<HashMap<String, u32>>,
+}
+
+impl RetryRegistry {
+    pub(crate) fn remember(&self, thread_id: &str, params: &Value) {
+        self.last_input
+            .lock()
+            .unwrap()
+            .insert(thread_id.to_string(), params.clone());
+    }
+
+    fn next_attempt(&self, thread_id: &str) -> u32 {
+        let mut attempts = self.attempts.lock().unwrap();
+        let attempt = attempts.entry(thread_id.to_string()).or_default();
+        *attempt += 1;
+        *attempt
+    }
+
+    fn reset(&self, thread_id: &str) {
+        self.attempts.lock().unwrap().remove(thread_id);
+    }
+
+    fn forget(&self, thread_id: &str) {
+        self.reset(thread_id);
+        self.last_input.lock().unwrap().remove(thread_id);
+    }
+}
+
+/// Handles a serialized `ServerNotification`, scheduling a retry for a turn
+/// that failed on a rate limit.
+pub fn observe(codex: &Arc<CodexAppServer>, event_sink: &Arc<dyn EventSink>, notification: &Value) {
+    let Some(method) = notification.get("method").and_then(Value::as_str) else {
+        return;
+    };
+    let Some(params) = notification.get("params") else {
+        return;
+    };
+    let Some(thread_id) = params.get("threadId").and_then(Value::as_str) else {
+        return;
+    };
+
+    match method {
+        "thread/closed" => codex.retries.forget(thread_id),
+        "turn/completed" => {
+            let status = params
+                .get("turn")
+                .and_then(|turn| turn.get("status"))
+                .and_then(Value::as_str);
+            if status == Some("completed") {
+                codex.retries.reset(thread_id);
+            }
+        }
+        "error" => {
+            let will_retry = params.get("willRetry").and_then(Value::as_bool) == Some(true);
+            let rate_limited = params.get("error").is_some_and(is_rate_limited);
+            if will_retry || !rate_limited || !codex.config().auto_retry_rate_limit {
+                return;
+            }
+            schedule_retry(codex, event_sink, thread_id);
+        }
+        _ => {}
+    }
+}
+
+fn schedule_retry(codex: &Arc<CodexAppServer>, event_sink: &Arc<dyn EventSink>, thread_id: &str) {
+    let Some(mut params) = codex.retries.last_input.lock().unwrap().get(thread_id).cloned() else {
+        return;
+    };
+    let attempt = codex.retries.next_attempt(thread_id);
+    if attempt > MAX_RATE_LIMIT_RETRIES {
+        codex.retries.reset(thread_id);
+        log::warn!("Giving up on rate-limited thread {} after {} retries", thread_id, MAX_RATE_LIMIT_RETRIES);
+        event_sink.emit(
+            RATE_LIMIT_RETRY_EVENT,
+            json!({
+                "threadId": thread_id,
+                "status": "gaveUp",
+                "attempt": attempt - 1,
+                "maxAttempts": MAX_RATE_LIMIT_RETRIES,
+                "error": format!("Still rate limited after {} retries", MAX_RATE_LIMIT_RETRIES),
+            }),
+        );
+        return;
+    }
+
+    let delay = backoff_delay(attempt);
+    log::info!("Thread {} was rate limited, retrying in {:?} ({}/{})", thread_id, delay, attempt, MAX_RATE_LIMIT_RETRIES);
+    event_sink.emit(
+        RATE_LIMIT_RETRY_EVENT,
+        json!({
+            "threadId": thread_id,
+            "status": "retrying",
+            "attempt": attempt,
+            "maxAttempts": MAX_RATE_LIMIT_RETRIES,
+            "delaySecs": delay.as_secs(),
+        }),
+    );
+
+    // The resubmitted message is a new user message as far as the UI is
+    // concerned, so it gets a fresh id.
+    if let Value::Object(map) = &mut params {
+        map.remove("clientUserMessageId");
+    }
+    let codex = Arc::clone(codex);
+    let event_sink = Arc::clone(event_sink);
+    let thread_id = thread_id.to_string();
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        if codex.threads.get(&thread_id).is_none() {
+            return;
+        }
+        if let Err(err) = start_turn(&codex, params).await {
+            log::warn!("Rate-limit retry of thread {} failed: {}", thread_id, err);
+            event_sink.emit(
+                RATE_LIMIT_RETRY_EVENT,
+                json!({ "threadId": thread_id, "status": "failed", "attempt": attempt, "error": err }),
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_rate_limits_and_backs_off() {
+        assert!(is_rate_limited(&json!({
+            "message": "stream error",
+            "codexErrorInfo": { "responseTooManyFailedAttempts": { "httpStatusCode": 429 } },
+        })));
+        assert!(is_rate_limited(&json!({ "message": "Rate limit reached for gpt-5", "codexErrorInfo": null })));
+        assert!(!is_rate_limited(&json!({ "message": "rate limit", "codexErrorInfo": "usageLimitExceeded" })));
+        assert!(!is_rate_limited(&json!({ "message": "bad request", "codexErrorInfo": "badRequest" })));
+
+        assert_eq!(backoff_delay(1), Duration::from_secs(2));
+        assert_eq!(backoff_delay(3), Duration::from_secs(8));
+        assert_eq!(backoff_delay(10), MAX_RETRY_DELAY);
+    }
+}
@@ -1,22 +1,75 @@
 use codexia_shared::event_sink::EventSink;
-use codex_app_server_protocol::ServerRequest;
+use codex_app_server_protocol::{RequestId, ServerRequest};
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::app_server::CodexAppServer;
+use crate::approvals::{ApprovalKind, approval_event, approval_prompt, schedule_auto_deny};
+use crate::patches::{PatchPreview, patch_preview};
+use crate::questions::question_event;
+
+pub const PATCH_PREVIEW_EVENT: &str = "codex/patch-preview";
+
+fn track_approval(
+    codex: &Arc<CodexAppServer>,
+    event_sink: &Arc<dyn EventSink>,
+    request_id: RequestId,
+    kind: ApprovalKind,
+    payload: &serde_json::Value,
+) {
+    codex.approvals.insert(request_id.clone(), kind, payload);
+    if let Some(secs) = codex.config().approval_auto_deny_secs {
+        schedule_auto_deny(
+            Arc::clone(codex),
+            Arc::clone(event_sink),
+            request_id,
+            Duration::from_secs(secs),
+        );
+    }
+}
+
+/// Emits the typed `codex-approval-<thread_id>` event next to the raw one.
+fn emit_approval_prompt(
+    codex: &CodexAppServer,
+    event_sink: &Arc<dyn EventSink>,
+    request_id: &RequestId,
+    kind: ApprovalKind,
+    payload: &serde_json::Value,
+) {
+    let thread_cwd = payload
+        .get("threadId")
+        .and_then(|id| id.as_str())
+        .and_then(|thread_id| codex.threads.cwd(thread_id))
+        .map(|cwd| cwd.to_string_lossy().to_string());
+    let Some(prompt) = approval_prompt(request_id, kind, payload, thread_cwd) else {
+        return;
+    };
+    if let Ok(value) = serde_json::to_value(&prompt) {
+        event_sink.emit(&approval_event(&prompt.thread_id), value);
+    }
+}
 
 // Handle server requests (approval requests)
-pub async fn handle_server_request(event_sink: &Arc<dyn EventSink>, server_request: ServerRequest) {
+pub async fn handle_server_request(
+    codex: &Arc<CodexAppServer>,
+    event_sink: &Arc<dyn EventSink>,
+    server_request: ServerRequest,
+) {
     match server_request {
         ServerRequest::CommandExecutionRequestApproval { request_id, params } => {
             if let Ok(mut payload) = serde_json::to_value(params) {
                 if let serde_json::Value::Object(ref mut map) = payload {
                     map.insert(
                         "requestId".to_string(),
-                        serde_json::to_value(request_id).unwrap_or(serde_json::Value::Null),
+                        serde_json::to_value(&request_id).unwrap_or(serde_json::Value::Null),
                     );
                     map.insert(
                         "type".to_string(),
                         serde_json::Value::String("commandExecution".to_string()),
                     );
                 }
+                emit_approval_prompt(codex, event_sink, &request_id, ApprovalKind::CommandExecution, &payload);
+                track_approval(codex, event_sink, request_id, ApprovalKind::CommandExecution, &payload);
                 event_sink.emit("codex/approval-request", payload);
             }
         }
@@ -25,13 +78,39 @@ pub async fn handle_server_request(event_sink: &Arc<dyn EventSink>, server_reque
                 if let serde_json::Value::Object(ref mut map) = payload {
                     map.insert(
                         "requestId".to_string(),
-                        serde_json::to_value(request_id).unwrap_or(serde_json::Value::Null),
+                        serde_json::to_value(&request_id).unwrap_or(serde_json::Value::Null),
                     );
                     map.insert(
                         "type".to_string(),
                         serde_json::Value::String("fileChange".to_string()),
                     );
+                    let str_param = |key: &str| map.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+                    let thread_id = str_param("threadId").to_string();
+                    let item_id = str_param("itemId").to_string();
+                    let preview = patch_preview(codex, &request_id, &thread_id, &item_id);
+                    if let Some(patch) = preview.as_ref().and_then(|p| serde_json::to_value(p).ok()) {
+                        map.insert("patch".to_string(), patch);
+                    }
+                    if codex.config().preview_mode {
+                        // Not auto-declined: the batch waits for an explicit
+                        // apply_previewed_patches.
+                        codex.approvals.insert(request_id.clone(), ApprovalKind::FileChange, &payload);
+                        let preview = preview.unwrap_or(PatchPreview {
+                            approval_id: request_id,
+                            thread_id: thread_id.clone(),
+                            item_id,
+                            files: Vec::new(),
+                        });
+                        let batch = codex.patches.add_preview(preview);
+                        event_sink.emit(
+                            PATCH_PREVIEW_EVENT,
+                            serde_json::json!({ "threadId": thread_id, "patches": batch }),
+                        );
+                        return;
+                    }
                 }
+                emit_approval_prompt(codex, event_sink, &request_id, ApprovalKind::FileChange, &payload);
+                track_approval(codex, event_sink, request_id, ApprovalKind::FileChange, &payload);
                 event_sink.emit("codex/approval-request", payload);
             }
         }
@@ -40,13 +119,16 @@ pub async fn handle_server_request(event_sink: &Arc<dyn EventSink>, server_reque
                 if let serde_json::Value::Object(ref mut map) = payload {
                     map.insert(
                         "requestId".to_string(),
-                        serde_json::to_value(request_id).unwrap_or(serde_json::Value::Null),
+                        serde_json::to_value(&request_id).unwrap_or(serde_json::Value::Null),
                     );
                     map.insert(
                         "type".to_string(),
                         serde_json::Value::String("requestUserInput".to_string()),
                     );
                 }
+                if let Some(question) = codex.questions.insert(request_id, &payload) {
+                    event_sink.emit(&question_event(&question.thread_id), payload.clone());
+                }
                 event_sink.emit("codex/request-user-input", payload);
             }
         }
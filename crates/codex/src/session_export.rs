@@ -0,0 +1,551 @@
+//! A stable, versioned JSON format for archiving sessions.
+//!
+//! Rollout files are codex's internal format and change between releases.
+//! [`export_session_json`] normalizes one into a [`SessionExport`] that only
+//! holds what a conversation is made of (messages, reasoning summaries, tool
+//! calls and their output), and [`import_session_json`] writes such a file
+//! back as a new rollout codex can resume. [`export_session_markdown`]
+//! renders the same export as a transcript for sharing.
+//!
+//! The web API can reach these without authentication, so its paths are
+//! confined: rollouts to the sessions directory, and export files to
+//! [`exports_dir`].
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::migrate::{RolloutFormat, detect_format};
+use crate::sessions::get_sessions_path;
+use crate::utils::{INJECTED_CONTEXT_MARKERS, parse_json_line};
+
+/// Bumped when [`SessionExport`] changes incompatibly.
+pub const EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportedConfig {
+    pub cwd: Option<String>,
+    pub model: Option<String>,
+    pub model_provider: Option<String>,
+    pub cli_version: Option<String>,
+    pub instructions: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExportedItem {
+    Message { role: String, text: String },
+    Reasoning { text: String },
+    ToolCall { call_id: String, name: String, arguments: String },
+    ToolOutput { call_id: String, output: String },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportedTurn {
+    pub started_at: Option<String>,
+    pub items: Vec<ExportedItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionExport {
+    pub version: u32,
+    pub session_id: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub config: ExportedConfig,
+    #[serde(default)]
+    pub turns: Vec<ExportedTurn>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub dest: String,
+    pub session_id: String,
+    pub turns: usize,
+    /// Rollout lines with nothing the export format can hold.
+    pub lines_skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub rollout_path: String,
+    /// Id of the new session; imports never reuse the exported id.
+    pub session_id: String,
+    pub turns: usize,
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(str::to_string)
+}
+
+/// Texts of a message's `content` or a reasoning item's `summary`.
+fn joined_text(parts: Option<&Value>) -> String {
+    parts
+        .and_then(Value::as_array)
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| part.get("text")?.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+fn output_text(output: Option<&Value>) -> String {
+    match output {
+        Some(Value::String(text)) => text.clone(),
+        Some(value) => value
+            .get("content")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| value.to_string()),
+        None => String::new(),
+    }
+}
+
+fn is_context(text: &str) -> bool {
+    let text = text.trim_start();
+    INJECTED_CONTEXT_MARKERS.iter().any(|marker| text.starts_with(marker))
+}
+
+/// Normalizes one `response_item` payload; `None` for item types the format
+/// does not cover.
+fn export_item(payload: &Value) -> Option<ExportedItem> {
+    let call_id = || str_field(payload, "call_id").unwrap_or_default();
+    match payload.get("type")?.as_str()? {
+        "message" => Some(ExportedItem::Message {
+            role: str_field(payload, "role")?,
+            text: joined_text(payload.get("content")),
+        }),
+        "reasoning" => Some(ExportedItem::Reasoning {
+            text: joined_text(payload.get("summary")),
+        }),
+        "function_call" => Some(ExportedItem::ToolCall {
+            call_id: call_id(),
+            name: str_field(payload, "name")?,
+            arguments: str_field(payload, "arguments").unwrap_or_default(),
+        }),
+        "custom_tool_call" => Some(ExportedItem::ToolCall {
+            call_id: call_id(),
+            name: str_field(payload, "name")?,
+            arguments: str_field(payload, "input").unwrap_or_default(),
+        }),
+        "local_shell_call" => Some(ExportedItem::ToolCall {
+            call_id: call_id(),
+            name: "shell".to_string(),
+            arguments: payload.get("action").map(Value::to_string).unwrap_or_default(),
+        }),
+        "function_call_output" | "custom_tool_call_output" => Some(ExportedItem::ToolOutput {
+            call_id: call_id(),
+            output: output_text(payload.get("output")),
+        }),
+        _ => None,
+    }
+}
+
+/// Builds the export from rollout lines, returning it with the number of
+/// skipped lines.
+fn export_lines(lines: &[&str]) -> Result<(SessionExport, usize), String> {
+    let mut export = SessionExport {
+        version: EXPORT_VERSION,
+        session_id: String::new(),
+        created_at: None,
+        config: ExportedConfig::default(),
+        turns: Vec::new(),
+    };
+    let mut skipped = 0;
+
+    for line in lines {
+        let Some(value) = parse_json_line(line) else {
+            skipped += 1;
+            continue;
+        };
+        let payload = value.get("payload").unwrap_or(&Value::Null);
+        let timestamp = str_field(&value, "timestamp");
+        match value.get("type").and_then(Value::as_str) {
+            Some("session_meta") => {
+                export.session_id = str_field(payload, "id").unwrap_or_default();
+                export.created_at = str_field(payload, "timestamp").or(timestamp);
+                let config = &mut export.config;
+                config.cwd = str_field(payload, "cwd");
+                config.cli_version = str_field(payload, "cli_version");
+                config.model_provider = str_field(payload, "model_provider");
+                config.instructions = str_field(payload, "instructions").or_else(|| {
+                    payload.get("base_instructions").and_then(|b| str_field(b, "text"))
+                });
+            }
+            Some("turn_context") => {
+                let config = &mut export.config;
+                config.model = config.model.take().or_else(|| str_field(payload, "model"));
+                config.cwd = config.cwd.take().or_else(|| str_field(payload, "cwd"));
+            }
+            Some("response_item") => {
+                let Some(item) = export_item(payload) else {
+                    skipped += 1;
+                    continue;
+                };
+                let starts_turn = matches!(
+                    &item,
+                    ExportedItem::Message { role, text } if role == "user" && !is_context(text)
+                );
+                if starts_turn || export.turns.is_empty() {
+                    export.turns.push(ExportedTurn {
+                        started_at: timestamp,
+                        items: Vec::new(),
+                    });
+                }
+                if let Some(turn) = export.turns.last_mut() {
+                    turn.items.push(item);
+                }
+            }
+            // Events and compaction markers are derived from the items.
+            _ => {}
+        }
+    }
+
+    if export.session_id.is_empty() {
+        return Err("Rollout has no session header".to_string());
+    }
+    Ok((export, skipped))
+}
+
+fn envelope(timestamp: &str, kind: &str, payload: Value) -> Value {
+    json!({ "timestamp": timestamp, "type": kind, "payload": payload })
+}
+
+fn rollout_item(item: &ExportedItem) -> Value {
+    match item {
+        ExportedItem::Message { role, text } => {
+            let part = if role == "assistant" { "output_text" } else { "input_text" };
+            json!({ "type": "message", "role": role, "content": [{ "type": part, "text": text }] })
+        }
+        ExportedItem::Reasoning { text } => json!({
+            "type": "reasoning",
+            "summary": [{ "type": "summary_text", "text": text }],
+            "content": null,
+            "encrypted_content": null,
+        }),
+        ExportedItem::ToolCall { call_id, name, arguments } => json!({
+            "type": "function_call",
+            "name": name,
+            "arguments": arguments,
+            "call_id": call_id,
+        }),
+        ExportedItem::ToolOutput { call_id, output } => json!({
+            "type": "function_call_output",
+            "call_id": call_id,
+            "output": output,
+        }),
+    }
+}
+
+/// The rollout lines for `export` as session `session_id`.
+fn rollout_lines(export: &SessionExport, session_id: &str, now: &str) -> Vec<Value> {
+    let config = &export.config;
+    let mut lines = vec![envelope(
+        now,
+        "session_meta",
+        json!({
+            "id": session_id,
+            "timestamp": export.created_at.as_deref().unwrap_or(now),
+            "cwd": config.cwd.as_deref().unwrap_or_default(),
+            "originator": "codexia_import",
+            "cli_version": config.cli_version.as_deref().unwrap_or_default(),
+            "instructions": config.instructions,
+            "model_provider": config.model_provider,
+        }),
+    )];
+    for turn in &export.turns {
+        let timestamp = turn.started_at.as_deref().unwrap_or(now);
+        for item in &turn.items {
+            lines.push(envelope(timestamp, "response_item", rollout_item(item)));
+            // Codexia renders the conversation from these events.
+            if let ExportedItem::Message { role, text } = item {
+                let event = match role.as_str() {
+                    "user" if !is_context(text) => Some("user_message"),
+                    "assistant" => Some("agent_message"),
+                    _ => None,
+                };
+                if let Some(event) = event {
+                    lines.push(envelope(timestamp, "event_msg", json!({ "type": event, "message": text })));
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// A code fence longer than any backtick run in `text`, so the text cannot
+/// close it early.
+fn fence_for(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn push_code_block(out: &mut String, language: &str, text: &str) {
+    let fence = fence_for(text);
+    out.push_str(&format!("{}{}\n{}\n{}\n\n", fence, language, text.trim_end(), fence));
+}
+
+/// Tool arguments are usually JSON; pretty-printed when they parse.
+fn pretty_arguments(arguments: &str) -> (&'static str, String) {
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(value @ (Value::Object(_) | Value::Array(_))) => {
+            ("json", serde_json::to_string_pretty(&value).unwrap_or_else(|_| arguments.to_string()))
+        }
+        _ => ("", arguments.to_string()),
+    }
+}
+
+/// The conversation as Markdown: a header with the model and start time,
+/// then each message, with tool calls and their output as code blocks.
+/// Injected context and reasoning summaries are left out.
+fn render_markdown(export: &SessionExport) -> String {
+    let config = &export.config;
+    let mut out = format!("# Codex session {}\n\n", export.session_id);
+    let model = match (&config.model, &config.model_provider) {
+        (Some(model), Some(provider)) => Some(format!("{} ({})", model, provider)),
+        (Some(model), None) => Some(model.clone()),
+        (None, provider) => provider.clone(),
+    };
+    let header = [
+        ("Model", model),
+        ("Started", export.created_at.clone()),
+        ("Working directory", config.cwd.clone()),
+        ("codex", config.cli_version.clone().filter(|v| !v.is_empty())),
+    ];
+    for (label, value) in header {
+        if let Some(value) = value {
+            out.push_str(&format!("- **{}:** {}\n", label, value));
+        }
+    }
+    out.push('\n');
+
+    for item in export.turns.iter().flat_map(|turn| &turn.items) {
+        match item {
+            ExportedItem::Message { role, text } if !is_context(text) && !text.trim().is_empty() => {
+                let speaker = match role.as_str() {
+                    "user" => "User",
+                    "assistant" => "Assistant",
+                    _ => continue,
+                };
+                out.push_str(&format!("## {}\n\n{}\n\n", speaker, text.trim()));
+            }
+            ExportedItem::ToolCall { name, arguments, .. } => {
+                out.push_str(&format!("**Tool call:** `{}`\n\n", name));
+                let (language, arguments) = pretty_arguments(arguments);
+                push_code_block(&mut out, language, &arguments);
+            }
+            ExportedItem::ToolOutput { output, .. } => {
+                out.push_str("**Output:**\n\n");
+                push_code_block(&mut out, "", output);
+            }
+            _ => {}
+        }
+    }
+    format!("{}\n", out.trim_end())
+}
+
+/// Reads a current-format rollout as an export, with its skipped line count.
+fn read_export(path: &Path) -> Result<(SessionExport, usize), String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    match lines.first().map(|line| detect_format(line)) {
+        Some(RolloutFormat::Current) => {}
+        Some(RolloutFormat::Legacy) => {
+            return Err(format!("{} uses the legacy rollout format; migrate it first", path.display()));
+        }
+        _ => return Err(format!("Unrecognized rollout format: {}", path.display())),
+    }
+    export_lines(&lines)
+}
+
+fn export_file(path: &Path, dest: &Path) -> Result<ExportReport, String> {
+    let (export, lines_skipped) = read_export(path)?;
+    let body = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(dest, body).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    Ok(ExportReport {
+        dest: dest.to_string_lossy().to_string(),
+        session_id: export.session_id,
+        turns: export.turns.len(),
+        lines_skipped,
+    })
+}
+
+fn import_file(path: &Path) -> Result<ImportReport, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let export: SessionExport = serde_json::from_str(&content)
+        .map_err(|e| format!("Not a Codexia session export: {}", e))?;
+    if export.version > EXPORT_VERSION {
+        return Err(format!(
+            "Export version {} is newer than the supported version {}",
+            export.version, EXPORT_VERSION
+        ));
+    }
+
+    let now = Utc::now();
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let dir: PathBuf = get_sessions_path()?.join(now.format("%Y/%m/%d").to_string());
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let rollout = dir.join(format!("rollout-{}-{}.jsonl", now.format("%Y-%m-%dT%H-%M-%S"), session_id));
+
+    let timestamp = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+    let mut body = rollout_lines(&export, &session_id, &timestamp)
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    body.push('\n');
+    fs::write(&rollout, body).map_err(|e| format!("Failed to write {}: {}", rollout.display(), e))?;
+    log::info!("imported session {} as {}", export.session_id, rollout.display());
+
+    Ok(ImportReport {
+        rollout_path: rollout.to_string_lossy().to_string(),
+        session_id,
+        turns: export.turns.len(),
+    })
+}
+
+/// Where the web API writes and reads export files, `~/.codexia/exports`.
+pub fn exports_dir() -> PathBuf {
+    crate::utils::codexia_dir().join("exports")
+}
+
+/// `path` inside `base`: a relative path without `..`, taken relative to
+/// `base`, or an absolute path below it.
+pub fn confine_to(base: &Path, path: &str) -> Result<PathBuf, String> {
+    let requested = Path::new(path);
+    let relative = match requested.strip_prefix(base) {
+        Ok(relative) => relative,
+        Err(_) if requested.is_absolute() => {
+            return Err(format!("{} is outside {}", path, base.display()));
+        }
+        Err(_) => requested,
+    };
+    let plain = relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if relative.as_os_str().is_empty() || !plain {
+        return Err(format!("'{}' must name a file inside {}", path, base.display()));
+    }
+    Ok(base.join(relative))
+}
+
+/// Writes the rollout at `file_path` to `dest` in the versioned export format.
+pub async fn export_session_json(file_path: String, dest: String) -> Result<ExportReport, String> {
+    tokio::task::spawn_blocking(move || export_file(Path::new(&file_path), Path::new(&dest)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// The rollout at `file_path` as a Markdown transcript, for the frontend to
+/// save or share.
+pub async fn export_session_markdown(file_path: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || read_export(Path::new(&file_path)).map(|(export, _)| render_markdown(&export)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Recreates an exported session as a new rollout under `CODEX_HOME`.
+pub async fn import_session_json(file_path: String) -> Result<ImportReport, String> {
+    tokio::task::spawn_blocking(move || import_file(Path::new(&file_path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confines_paths_to_the_base_directory() {
+        let base = Path::new("/home/me/.codexia/exports");
+        assert_eq!(confine_to(base, "fix-login.json").unwrap(), base.join("fix-login.json"));
+        assert_eq!(confine_to(base, "2025/a.json").unwrap(), base.join("2025/a.json"));
+        assert_eq!(confine_to(base, "/home/me/.codexia/exports/a.json").unwrap(), base.join("a.json"));
+        assert!(confine_to(base, "../../.ssh/authorized_keys").is_err());
+        assert!(confine_to(base, "/etc/passwd").is_err());
+        assert!(confine_to(base, "/home/me/.codexia/exports/../settings.json").is_err());
+        assert!(confine_to(base, "").is_err());
+    }
+
+    #[test]
+    fn exports_turns_and_round_trips() {
+        let lines = [
+            r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"s1","timestamp":"2025-09-01T10:00:00Z","cwd":"/repo","cli_version":"0.50.0","model_provider":"openai"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>/repo</environment_context>"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"turn_context","payload":{"cwd":"/repo","model":"gpt-5"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:02Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"list files"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:02Z","type":"event_msg","payload":{"type":"user_message","message":"list files"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:03Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"c1"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:04Z","type":"response_item","payload":{"type":"function_call_output","call_id":"c1","output":{"content":"a.rs"}}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:05Z","type":"response_item","payload":{"type":"web_search_call","status":"completed"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:06Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"a.rs"}]}}"#,
+        ];
+        let (export, skipped) = export_lines(&lines).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(export.session_id, "s1");
+        assert_eq!(export.config.model.as_deref(), Some("gpt-5"));
+        assert_eq!(export.turns.len(), 2);
+        assert_eq!(export.turns[1].started_at.as_deref(), Some("2025-09-01T10:00:02Z"));
+        assert_eq!(
+            export.turns[1].items[2],
+            ExportedItem::ToolOutput { call_id: "c1".to_string(), output: "a.rs".to_string() }
+        );
+
+        let rollout: Vec<String> = rollout_lines(&export, "s2", "2025-10-01T00:00:00.000Z")
+            .iter()
+            .map(Value::to_string)
+            .collect();
+        let rollout: Vec<&str> = rollout.iter().map(String::as_str).collect();
+        assert_eq!(detect_format(rollout[0]), RolloutFormat::Current);
+        let (reimported, skipped) = export_lines(&rollout).unwrap();
+        assert_eq!(skipped, 0);
+        assert_eq!(reimported.session_id, "s2");
+        assert_eq!(reimported.turns, export.turns);
+    }
+
+    #[test]
+    fn renders_markdown_transcript() {
+        let export = SessionExport {
+            version: EXPORT_VERSION,
+            session_id: "s1".to_string(),
+            created_at: Some("2025-09-01T10:00:00Z".to_string()),
+            config: ExportedConfig {
+                model: Some("gpt-5".to_string()),
+                model_provider: Some("openai".to_string()),
+                ..ExportedConfig::default()
+            },
+            turns: vec![ExportedTurn {
+                started_at: None,
+                items: vec![
+                    ExportedItem::Message { role: "user".to_string(), text: "<environment_context>x</environment_context>".to_string() },
+                    ExportedItem::Message { role: "user".to_string(), text: "list files".to_string() },
+                    ExportedItem::Reasoning { text: "thinking".to_string() },
+                    ExportedItem::ToolCall { call_id: "c1".to_string(), name: "shell".to_string(), arguments: r#"{"command":["ls"]}"#.to_string() },
+                    ExportedItem::ToolOutput { call_id: "c1".to_string(), output: "a.md\n```rust\n".to_string() },
+                    ExportedItem::Message { role: "assistant".to_string(), text: "Found a.md".to_string() },
+                ],
+            }],
+        };
+        let markdown = render_markdown(&export);
+        assert!(markdown.starts_with("# Codex session s1\n\n- **Model:** gpt-5 (openai)\n- **Started:** 2025-09-01T10:00:00Z\n\n"));
+        assert!(markdown.contains("## User\n\nlist files\n\n**Tool call:** `shell`\n\n```json\n{\n  \"command\": [\n"));
+        assert!(markdown.contains("**Output:**\n\n````\na.md\n```rust\n````\n\n## Assistant\n\nFound a.md\n"));
+        assert!(!markdown.contains("environment_context") && !markdown.contains("thinking"));
+    }
+}
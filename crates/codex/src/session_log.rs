@@ -0,0 +1,190 @@
+//! Optional per-thread log files.
+//!
+//! By default everything goes to the single global log. When
+//! `CODEXIA_LOG_PATH` names a directory, every app-server message that
+//! belongs to a thread, the requests written to stdin and the stdout lines
+//! that failed to parse are also appended to `<dir>/<thread_id>.log`, so one
+//! session can be debugged while several are running.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::redact::redact_value;
+
+pub const LOG_PATH_ENV: &str = "CODEXIA_LOG_PATH";
+
+/// Where lines that cannot be attributed to a thread go.
+const PROCESS_LOG: &str = "app-server";
+
+#[derive(Default)]
+pub struct SessionLogs {
+    dir: Option<PathBuf>,
+    files: Mutex<HashMap<String, File>>,
+}
+
+fn message_thread_id(message: &Value) -> Option<&str> {
+    let body = message.get("params").or_else(|| message.get("result"))?;
+    body.get("threadId")
+        .and_then(Value::as_str)
+        .or_else(|| body.get("thread")?.get("id")?.as_str())
+}
+
+/// Pulls `"threadId":"..."` out of a line that is not valid JSON.
+pub(crate) fn raw_thread_id(raw: &str) -> Option<&str> {
+    let start = raw.find("\"threadId\"")? + "\"threadId\"".len();
+    let rest = raw[start..].trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    let id = &rest[..rest.find('"')?];
+    (!id.is_empty()).then_some(id)
+}
+
+/// Whether `thread_id` can name a log file. Codex thread ids are UUIDs, so
+/// anything else, such as `../x`, is kept out of the path.
+pub(crate) fn is_log_name(thread_id: &str) -> bool {
+    !thread_id.is_empty() && thread_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Streaming deltas would drown out everything else.
+fn is_delta(message: &Value) -> bool {
+    message
+        .get("method")
+        .and_then(Value::as_str)
+        .is_some_and(|method| method.ends_with("/delta"))
+}
+
+impl SessionLogs {
+    /// Per-thread logging is on when [`LOG_PATH_ENV`] is an existing
+    /// directory or ends with a path separator.
+    pub fn from_env() -> Self {
+        let dir = std::env::var_os(LOG_PATH_ENV)
+            .map(PathBuf::from)
+            .filter(|path| {
+                path.is_dir() || path.to_string_lossy().ends_with(['/', '\\'])
+            });
+        if let Some(dir) = &dir {
+            log::info!("Writing per-session logs to {}", dir.display());
+        }
+        Self::with_dir(dir)
+    }
+
+    fn with_dir(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// The file the messages of `thread_id` go to, when logging is on and
+    /// the id is well-formed.
+    pub fn thread_log_path(&self, thread_id: &str) -> Option<PathBuf> {
+        if !is_log_name(thread_id) {
+            return None;
+        }
+        Some(self.dir.as_ref()?.join(format!("{}.log", thread_id)))
+    }
+
+    /// Appends to `<dir>/<name>.log`; a malformed name goes to the process log.
+    fn write(&self, name: &str, direction: &str, text: &str) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let name = if is_log_name(name) { name } else { PROCESS_LOG };
+        let mut files = self.files.lock().unwrap();
+        if !files.contains_key(name) {
+            let path = dir.join(format!("{}.log", name));
+            let opened = std::fs::create_dir_all(dir)
+                .and_then(|_| OpenOptions::new().create(true).append(true).open(&path));
+            match opened {
+                Ok(file) => {
+                    files.insert(name.to_string(), file);
+                }
+                Err(err) => {
+                    log::warn!("Failed to open session log {}: {}", path.display(), err);
+                    return;
+                }
+            }
+        }
+        if let Some(file) = files.get_mut(name) {
+            let _ = writeln!(file, "{} {} {}", Utc::now().to_rfc3339(), direction, text);
+        }
+    }
+
+    fn write_message(&self, direction: &str, message: &Value) {
+        if !self.is_enabled() || is_delta(message) {
+            return;
+        }
+        let Some(thread_id) = message_thread_id(message) else {
+            return;
+        };
+        let mut message = message.clone();
+        redact_value(&mut message);
+        self.write(thread_id, direction, &message.to_string());
+    }
+
+    /// A message written to the app-server's stdin.
+    pub(crate) fn outgoing(&self, message: &Value) {
+        self.write_message("->", message);
+    }
+
+    /// A message read from the app-server's stdout. The file of a closed
+    /// thread is released.
+    pub(crate) fn incoming(&self, message: &Value) {
+        self.write_message("<-", message);
+        if message.get("method").and_then(Value::as_str) == Some("thread/closed") {
+            if let Some(thread_id) = message_thread_id(message) {
+                self.files.lock().unwrap().remove(thread_id);
+            }
+        }
+    }
+
+    /// A stdout line that is not valid JSON.
+    pub(crate) fn parse_failure(&self, raw: &str, error: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let name = raw_thread_id(raw).unwrap_or(PROCESS_LOG);
+        self.write(name, "!!", &format!("parse error: {}: {}", error, raw));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn routes_messages_to_thread_files() {
+        let dir = std::env::temp_dir().join(format!("codexia-session-logs-{}", uuid::Uuid::new_v4()));
+        let logs = SessionLogs::with_dir(Some(dir.clone()));
+
+        logs.outgoing(&json!({ "id": 1, "method": "turn/start", "params": { "threadId": "t1", "input": [] } }));
+        logs.incoming(&json!({ "method": "item/agentMessage/delta", "params": { "threadId": "t1", "delta": "x" } }));
+        logs.incoming(&json!({ "id": 2, "result": { "thread": { "id": "t2" } } }));
+        logs.parse_failure(r#"{"method":"turn/started","params":{"threadId": "t1""#, "EOF");
+        logs.parse_failure("garbage", "expected value");
+        logs.incoming(&json!({ "method": "turn/started", "params": { "threadId": "../escape" } }));
+
+        let t1 = std::fs::read_to_string(dir.join("t1.log")).unwrap();
+        assert_eq!(t1.lines().count(), 2);
+        assert!(t1.contains("-> {") && t1.contains("!! parse error: EOF"));
+        assert!(dir.join("t2.log").exists());
+        assert!(dir.join("app-server.log").exists());
+        assert!(!dir.parent().unwrap().join("escape.log").exists());
+        assert!(logs.thread_log_path("../escape").is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let disabled = SessionLogs::default();
+        disabled.outgoing(&json!({ "method": "turn/start", "params": { "threadId": "t1" } }));
+        assert!(!disabled.is_enabled());
+    }
+}
@@ -0,0 +1,734 @@
+//! User-editable metadata for rollout files, kept in a sidecar next to each
+//! rollout (`rollout-….jsonl` → `rollout-….meta.json`) so codex's own files
+//! are never modified.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use futures::StreamExt;
+use nucleo::pattern::{AtomKind, CaseMatching, Normalization, Pattern};
+use nucleo::{Config, Matcher, Utf32String};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::approvals::ApprovalRecord;
+use crate::error::CodexiaError;
+use crate::sessions::{get_sessions_path, scan_jsonl_files};
+use crate::usage::TokenTotals;
+use crate::utils::{INJECTED_CONTEXT_MARKERS, extract_preview, file_mtime, parse_json_line, parse_ts};
+
+/// How many leading rollout lines are scanned for a derived title.
+const TITLE_SCAN_LINES: usize = 64;
+const TITLE_MAX_CHARS: usize = 80;
+/// Longest title [`set_session_title`] accepts.
+const CUSTOM_TITLE_MAX_CHARS: usize = 200;
+const DEFAULT_FUZZY_LIMIT: usize = 20;
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// Upper bound on [`search_sessions`] results, whatever limit is asked for.
+const MAX_SEARCH_LIMIT: usize = 500;
+/// Characters of message text kept on each side of a search match.
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+/// Bytes read from the end of a rollout to find its last event.
+const TAIL_BYTES: u64 = 16 * 1024;
+/// Rollouts parsed at once by [`load_sessions_from_disk`]; each holds a few
+/// file descriptors open.
+const LOAD_CONCURRENCY: usize = 16;
+
+/// Colors a session can be labeled with, so the UI can render fixed chips.
+pub const SESSION_COLORS: &[&str] = &["red", "orange", "yellow", "green", "blue", "purple", "gray"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// When Codexia started the session, in seconds since the epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<i64>,
+    /// When Codexia closed the session cleanly.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<i64>,
+    /// Approval decisions, when persisting them is enabled.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub approval_history: Vec<ApprovalRecord>,
+    /// Tokens used across every run of the session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenTotals>,
+}
+
+pub fn meta_path(rollout: &Path) -> PathBuf {
+    rollout.with_extension("meta.json")
+}
+
+/// Reads the sidecar of `rollout`; a missing or malformed sidecar yields the
+/// default metadata.
+pub fn read_session_meta(rollout: &Path) -> SessionMeta {
+    let Ok(content) = std::fs::read_to_string(meta_path(rollout)) else {
+        return SessionMeta::default();
+    };
+    serde_json::from_str(&content).unwrap_or_default()
+}
+
+/// One lock per sidecar, so concurrent updates (usage during a turn, a title
+/// change, an approval record) apply one after another instead of
+/// overwriting each other.
+static META_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn meta_lock(rollout: &Path) -> Arc<Mutex<()>> {
+    let mut locks = META_LOCKS.lock().unwrap();
+    Arc::clone(locks.entry(meta_path(rollout)).or_default())
+}
+
+/// Written to a temporary file and renamed over the sidecar, so a reader
+/// never sees it half written.
+fn write_session_meta(rollout: &Path, meta: &SessionMeta) -> Result<(), String> {
+    if !rollout.is_file() {
+        return Err(format!("Session file not found: {}", rollout.display()));
+    }
+    let content = serde_json::to_string_pretty(meta).map_err(|e| e.to_string())?;
+    let path = meta_path(rollout);
+    let tmp = path.with_extension(format!("json.{}.tmp", uuid::Uuid::new_v4()));
+    std::fs::write(&tmp, content).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        e.to_string()
+    })
+}
+
+/// Applies `update` to the sidecar of `rollout` and writes it back, holding
+/// the sidecar's lock throughout. Returns the updated metadata. Every write
+/// of a sidecar goes through here.
+pub fn update_session_meta(rollout: &Path, update: impl FnOnce(&mut SessionMeta)) -> Result<SessionMeta, String> {
+    let lock = meta_lock(rollout);
+    let _guard = lock.lock().unwrap();
+    let mut meta = read_session_meta(rollout);
+    update(&mut meta);
+    write_session_meta(rollout, &meta)?;
+    Ok(meta)
+}
+
+/// Title derived from the first user message of the rollout.
+pub fn derive_title(rollout: &Path) -> Option<String> {
+    let file = File::open(rollout).ok()?;
+    for line in BufReader::new(file).lines().take(TITLE_SCAN_LINES) {
+        let Ok(line) = line else { break };
+        let Some(value) = parse_json_line(&line) else { continue };
+        let payload = value.get("payload");
+        let is_user_message = value.get("type").and_then(Value::as_str) == Some("response_item")
+            && payload.and_then(|p| p.get("role")).and_then(Value::as_str) == Some("user");
+        if !is_user_message {
+            continue;
+        }
+        if let Some(preview) = extract_preview(&value) {
+            let first_line = preview.lines().next().unwrap_or_default();
+            return Some(first_line.chars().take(TITLE_MAX_CHARS).collect());
+        }
+    }
+    None
+}
+
+/// Sidecar title, falling back to the derived one.
+pub fn session_title(rollout: &Path, meta: &SessionMeta) -> Option<String> {
+    meta.title
+        .clone()
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| derive_title(rollout))
+}
+
+/// The rollout's `session_meta` header line.
+fn rollout_header(rollout: &Path) -> Option<Value> {
+    let file = File::open(rollout).ok()?;
+    let line = BufReader::new(file).lines().next()?.ok()?;
+    parse_json_line(&line)
+}
+
+fn header_session_id(header: &Value) -> Option<String> {
+    let payload = header.get("payload").unwrap_or(header);
+    payload.get("id")?.as_str().map(str::to_string)
+}
+
+fn header_timestamp(header: &Value) -> Option<i64> {
+    let payload = header.get("payload").unwrap_or(header);
+    let timestamp = payload.get("timestamp").or_else(|| header.get("timestamp"))?;
+    parse_ts(timestamp.as_str()?)
+}
+
+/// Timestamp of the last complete line of the rollout.
+fn last_event_timestamp(rollout: &Path) -> Option<i64> {
+    let mut file = File::open(rollout).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES))).ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    String::from_utf8_lossy(&tail)
+        .lines()
+        .rev()
+        .find_map(|line| parse_ts(parse_json_line(line)?.get("timestamp")?.as_str()?))
+}
+
+fn record_time(rollout: &Path, set: impl FnOnce(&mut SessionMeta, i64)) {
+    let now = chrono::Utc::now().timestamp();
+    if let Err(err) = update_session_meta(rollout, |meta| set(meta, now)) {
+        log::debug!("Not recording session time for {}: {}", rollout.display(), err);
+    }
+}
+
+/// Records now as the session's start. Skipped when codex has not written
+/// the rollout yet; the header timestamp is used instead.
+pub fn mark_session_started(rollout: &Path) {
+    record_time(rollout, |meta, now| meta.started_at = Some(now));
+}
+
+/// Records now as the session's clean end.
+pub fn mark_session_ended(rollout: &Path) {
+    record_time(rollout, |meta, now| meta.ended_at = Some(now));
+}
+
+/// Stores the session's running token total in the rollout's sidecar. A
+/// larger saved total is kept, since totals only grow and writes from
+/// background tasks may finish out of order.
+pub fn write_session_usage(rollout: &Path, usage: TokenTotals) {
+    let update = |meta: &mut SessionMeta| {
+        if meta.usage.is_none_or(|saved| saved.total_tokens <= usage.total_tokens) {
+            meta.usage = Some(usage);
+        }
+    };
+    if let Err(err) = update_session_meta(rollout, update) {
+        log::debug!("Not persisting usage for {}: {}", rollout.display(), err);
+    }
+}
+
+/// Appends an approval decision to the rollout's sidecar.
+pub fn append_approval_record(rollout: &Path, record: ApprovalRecord) {
+    if let Err(err) = update_session_meta(rollout, |meta| meta.approval_history.push(record)) {
+        log::warn!("Failed to persist approval for {}: {}", rollout.display(), err);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEntry {
+    pub file_path: String,
+    pub session_id: Option<String>,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    pub color: Option<String>,
+    /// Rollout modification time, in seconds since the epoch.
+    pub updated_at: Option<i64>,
+    /// Recorded start, falling back to the rollout header.
+    pub started_at: Option<i64>,
+    /// Recorded clean end; for sessions that did not close cleanly, the time
+    /// of the last event.
+    pub ended_at: Option<i64>,
+}
+
+fn session_entry(path: &Path) -> SessionEntry {
+    let meta = read_session_meta(path);
+    let header = rollout_header(path);
+    let updated_at = file_mtime(path);
+    SessionEntry {
+        file_path: path.to_string_lossy().to_string(),
+        session_id: header.as_ref().and_then(header_session_id),
+        title: session_title(path, &meta),
+        tags: meta.tags,
+        favorite: meta.favorite,
+        color: meta.color,
+        updated_at,
+        started_at: meta.started_at.or_else(|| header.as_ref().and_then(header_timestamp)),
+        ended_at: meta
+            .ended_at
+            .or_else(|| last_event_timestamp(path))
+            .or(updated_at),
+    }
+}
+
+/// Favorites first, then most recently updated. Ties fall back to the path
+/// so the order does not depend on which file finished parsing first.
+fn sort_sessions(sessions: &mut [SessionEntry]) {
+    sessions.sort_by(|a, b| {
+        b.favorite
+            .cmp(&a.favorite)
+            .then(b.updated_at.cmp(&a.updated_at))
+            .then(a.file_path.cmp(&b.file_path))
+    });
+}
+
+/// Lists every rollout with its sidecar metadata, favorites first and then
+/// most recently updated. Rollouts are parsed concurrently, at most
+/// [`LOAD_CONCURRENCY`] at a time.
+pub async fn load_sessions_from_disk() -> Result<Vec<SessionEntry>, String> {
+    let root = get_sessions_path()?;
+    let files = tokio::task::spawn_blocking(move || scan_jsonl_files(&root))
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut sessions: Vec<SessionEntry> = futures::stream::iter(files)
+        .map(|path| tokio::task::spawn_blocking(move || session_entry(&path)))
+        .buffer_unordered(LOAD_CONCURRENCY)
+        .filter_map(|entry| async move {
+            entry
+                .inspect_err(|err| log::warn!("Failed to load a session: {}", err))
+                .ok()
+        })
+        .collect()
+        .await;
+    sort_sessions(&mut sessions);
+    Ok(sessions)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub path: String,
+    pub session_id: Option<String>,
+    pub title: Option<String>,
+    /// User and assistant messages, not counting injected context.
+    pub message_count: usize,
+    /// Rollout modification time, in seconds since the epoch.
+    pub last_modified: Option<i64>,
+    /// Model of the latest turn.
+    pub model: Option<String>,
+}
+
+/// Messages and the latest model of a rollout. Lines are matched as text,
+/// so only `turn_context` lines are parsed.
+fn count_messages(rollout: &Path) -> (usize, Option<String>) {
+    let Ok(file) = File::open(rollout) else {
+        return (0, None);
+    };
+    let mut count = 0;
+    let mut model = None;
+    for line in BufReader::new(file).lines() {
+        let Ok(line) = line else { break };
+        if line.contains(r#""type":"turn_context""#) {
+            let latest = parse_json_line(&line)
+                .and_then(|value| value.get("payload")?.get("model")?.as_str().map(str::to_string));
+            model = latest.or(model);
+            continue;
+        }
+        let is_message = line.contains(r#""type":"response_item""#)
+            && line.contains(r#""type":"message""#)
+            && (line.contains(r#""role":"user""#) || line.contains(r#""role":"assistant""#));
+        if is_message && !INJECTED_CONTEXT_MARKERS.iter().any(|marker| line.contains(marker)) {
+            count += 1;
+        }
+    }
+    (count, model)
+}
+
+fn session_summary(path: &Path) -> SessionSummary {
+    let meta = read_session_meta(path);
+    let (message_count, model) = count_messages(path);
+    SessionSummary {
+        path: path.to_string_lossy().to_string(),
+        session_id: rollout_header(path).as_ref().and_then(header_session_id),
+        title: session_title(path, &meta),
+        message_count,
+        last_modified: file_mtime(path),
+        model,
+    }
+}
+
+/// Lightweight metadata of every rollout, most recently modified first,
+/// for sorting and previewing without loading the conversations.
+pub async fn list_session_summaries() -> Result<Vec<SessionSummary>, String> {
+    let root = get_sessions_path()?;
+    let files = tokio::task::spawn_blocking(move || scan_jsonl_files(&root))
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut summaries: Vec<SessionSummary> = futures::stream::iter(files)
+        .map(|path| tokio::task::spawn_blocking(move || session_summary(&path)))
+        .buffer_unordered(LOAD_CONCURRENCY)
+        .filter_map(|summary| async move {
+            summary
+                .inspect_err(|err| log::warn!("Failed to summarize a session: {}", err))
+                .ok()
+        })
+        .collect()
+        .await;
+    summaries.sort_by(|a, b| b.last_modified.cmp(&a.last_modified).then(a.path.cmp(&b.path)));
+    Ok(summaries)
+}
+
+/// Flips the favorite flag of a rollout in the sessions directory and
+/// returns the new state.
+pub fn toggle_favorite(file_path: &str) -> Result<bool, String> {
+    let rollout = crate::sessions::session_rollout(file_path)?;
+    let meta = update_session_meta(&rollout, |meta| meta.favorite = !meta.favorite)?;
+    Ok(meta.favorite)
+}
+
+/// `title` trimmed, with blank meaning no custom title.
+fn normalize_title(title: Option<String>) -> Result<Option<String>, String> {
+    let Some(title) = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) else {
+        return Ok(None);
+    };
+    if title.contains(['\n', '\r']) {
+        return Err("Session title must be a single line".to_string());
+    }
+    let chars = title.chars().count();
+    if chars > CUSTOM_TITLE_MAX_CHARS {
+        return Err(format!("Session title is {} characters; the limit is {}", chars, CUSTOM_TITLE_MAX_CHARS));
+    }
+    Ok(Some(title))
+}
+
+/// Sets (or with `None`, clears) the title of session `session_id` in its
+/// rollout's sidecar. Without one, lists fall back to the first user
+/// message. Returns the title now shown.
+pub async fn set_session_title(session_id: &str, title: Option<String>) -> Result<Option<String>, CodexiaError> {
+    let title = normalize_title(title)?;
+    let lookup_id = session_id.to_string();
+    tokio::task::spawn_blocking(move || {
+        let rollout = crate::sessions::find_rollout_path_for_session(&lookup_id)?;
+        let meta = update_session_meta(&rollout, |meta| meta.title = title)?;
+        Ok(session_title(&rollout, &meta))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn validate_color(color: &str) -> Result<String, String> {
+    let color = color.trim().to_ascii_lowercase();
+    if SESSION_COLORS.contains(&color.as_str()) {
+        Ok(color)
+    } else {
+        Err(format!(
+            "Unsupported session color '{}', expected one of: {}",
+            color,
+            SESSION_COLORS.join(", ")
+        ))
+    }
+}
+
+/// Sets (or with `None`, clears) the color label of a rollout in the
+/// sessions directory.
+pub fn set_session_color(file_path: &str, color: Option<String>) -> Result<Option<String>, String> {
+    let color = color
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| validate_color(&c))
+        .transpose()?;
+    let rollout = crate::sessions::session_rollout(file_path)?;
+    let meta = update_session_meta(&rollout, |meta| meta.color = color)?;
+    Ok(meta.color)
+}
+
+/// Sessions labeled with `color`, in the same order as
+/// [`load_sessions_from_disk`].
+pub async fn list_sessions_by_color(color: String) -> Result<Vec<SessionEntry>, String> {
+    let color = validate_color(&color)?;
+    let sessions = load_sessions_from_disk().await?;
+    Ok(sessions
+        .into_iter()
+        .filter(|s| s.color.as_deref() == Some(color.as_str()))
+        .collect())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMatch {
+    pub file_path: String,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub score: u32,
+}
+
+fn fuzzy_find(query: &str, limit: usize) -> Result<Vec<SessionMatch>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = Pattern::new(query, CaseMatching::Smart, Normalization::Smart, AtomKind::Fuzzy);
+    let mut matcher = Matcher::new(Config::DEFAULT);
+
+    let mut matches: Vec<SessionMatch> = scan_jsonl_files(&get_sessions_path()?)
+        .into_iter()
+        .filter_map(|path| {
+            let meta = read_session_meta(&path);
+            let title = session_title(&path, &meta);
+            let haystack = format!("{} {}", title.as_deref().unwrap_or_default(), meta.tags.join(" "));
+            let utf32 = Utf32String::from(haystack.as_str());
+            let score = pattern.score(utf32.slice(..), &mut matcher)?;
+            Some(SessionMatch {
+                file_path: path.to_string_lossy().to_string(),
+                title,
+                tags: meta.tags,
+                score,
+            })
+        })
+        .collect();
+
+    // Newer rollouts sort later by path, so ties favor recent sessions.
+    matches.sort_by(|a, b| b.score.cmp(&a.score).then(b.file_path.cmp(&a.file_path)));
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// Fuzzy-matches `query` against session titles and tags only, which is cheap
+/// enough for as-you-type filtering.
+pub async fn fuzzy_find_sessions(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SessionMatch>, String> {
+    let limit = limit.unwrap_or(DEFAULT_FUZZY_LIMIT);
+    tokio::task::spawn_blocking(move || fuzzy_find(&query, limit))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub file_path: String,
+    /// 1-based line of the rollout holding the message.
+    pub line_number: usize,
+    /// `user` or `assistant`.
+    pub role: String,
+    pub snippet: String,
+}
+
+/// Byte range of the first case-insensitive occurrence of `needle` (already
+/// lowercased) in `text`.
+fn find_ignore_case(text: &str, needle: &[char]) -> Option<(usize, usize)> {
+    text.char_indices().find_map(|(start, _)| {
+        let mut matched = 0;
+        for (offset, c) in text[start..].char_indices() {
+            for lower in c.to_lowercase() {
+                if needle.get(matched) != Some(&lower) {
+                    return None;
+                }
+                matched += 1;
+            }
+            if matched == needle.len() {
+                return Some((start, start + offset + c.len_utf8()));
+            }
+        }
+        None
+    })
+}
+
+/// The match with up to [`SNIPPET_CONTEXT_CHARS`] around it, on one line.
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    let before: Vec<char> = text[..start].chars().rev().take(SNIPPET_CONTEXT_CHARS + 1).collect();
+    let after: Vec<char> = text[end..].chars().take(SNIPPET_CONTEXT_CHARS + 1).collect();
+    let mut out = String::new();
+    if before.len() > SNIPPET_CONTEXT_CHARS {
+        out.push('…');
+    }
+    out.extend(before.iter().rev().skip(before.len().saturating_sub(SNIPPET_CONTEXT_CHARS)));
+    out.push_str(&text[start..end]);
+    out.extend(after.iter().take(SNIPPET_CONTEXT_CHARS));
+    if after.len() > SNIPPET_CONTEXT_CHARS {
+        out.push('…');
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Role and text of a user or assistant message line.
+fn message_text(value: &Value) -> Option<(&str, String)> {
+    if value.get("type").and_then(Value::as_str) != Some("response_item") {
+        return None;
+    }
+    let role = value.get("payload")?.get("role")?.as_str()?;
+    if role != "user" && role != "assistant" {
+        return None;
+    }
+    Some((role, extract_preview(value)?))
+}
+
+fn search_files(files: impl Iterator<Item = PathBuf>, query: &str, limit: usize) -> Vec<SearchHit> {
+    let needle: Vec<char> = query.trim().chars().flat_map(char::to_lowercase).collect();
+    let mut hits = Vec::new();
+    if needle.is_empty() || limit == 0 {
+        return hits;
+    }
+    for path in files {
+        let Ok(file) = File::open(&path) else { continue };
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let Ok(line) = line else { break };
+            let Some(value) = parse_json_line(&line) else { continue };
+            let Some((role, text)) = message_text(&value) else { continue };
+            let Some((start, end)) = find_ignore_case(&text, &needle) else { continue };
+            hits.push(SearchHit {
+                file_path: path.to_string_lossy().to_string(),
+                line_number: index + 1,
+                role: role.to_string(),
+                snippet: snippet(&text, start, end),
+            });
+            if hits.len() >= limit {
+                return hits;
+            }
+        }
+    }
+    hits
+}
+
+/// Case-insensitive full-text search of the user and assistant messages of
+/// every rollout, newest session first. Unlike [`fuzzy_find_sessions`] this
+/// reads whole rollouts, so it stops as soon as `limit` hits are found.
+pub async fn search_sessions(query: String, limit: Option<usize>) -> Result<Vec<SearchHit>, String> {
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+    let root = get_sessions_path()?;
+    tokio::task::spawn_blocking(move || search_files(scan_jsonl_files(&root).into_iter().rev(), &query, limit))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sidecar_sits_next_to_rollout() {
+        let path = Path::new("/tmp/sessions/2025/09/01/rollout-2025-09-01T10-00-00-abc.jsonl");
+        assert_eq!(
+            meta_path(path),
+            PathBuf::from("/tmp/sessions/2025/09/01/rollout-2025-09-01T10-00-00-abc.meta.json")
+        );
+    }
+
+    #[test]
+    fn sorts_sessions_independently_of_load_order() {
+        let entry = |path: &str, favorite: bool, updated_at: i64| SessionEntry {
+            file_path: path.to_string(),
+            session_id: None,
+            title: None,
+            tags: Vec::new(),
+            favorite,
+            color: None,
+            updated_at: Some(updated_at),
+            started_at: None,
+            ended_at: None,
+        };
+        let mut sessions = vec![entry("b", false, 5), entry("c", false, 9), entry("a", false, 5), entry("d", true, 1)];
+        sort_sessions(&mut sessions);
+        let order: Vec<&str> = sessions.iter().map(|s| s.file_path.as_str()).collect();
+        assert_eq!(order, ["d", "c", "a", "b"]);
+    }
+
+    #[test]
+    fn counts_messages_and_finds_latest_model() {
+        let dir = std::env::temp_dir().join(format!("codexia-summary-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rollout = dir.join("rollout-test.jsonl");
+        std::fs::write(
+            &rollout,
+            concat!(
+                r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"s1"}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:00:00Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>...</environment_context>"}]}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:00:01Z","type":"turn_context","payload":{"cwd":"/repo","model":"gpt-5"}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Fix it"}]}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:00:02Z","type":"response_item","payload":{"type":"reasoning","summary":[]}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:00:03Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Done"}]}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:01:00Z","type":"turn_context","payload":{"cwd":"/repo","model":"gpt-5-codex"}}"#, "\n",
+            ),
+        )
+        .unwrap();
+
+        let summary = session_summary(&rollout);
+        assert_eq!(summary.message_count, 2);
+        assert_eq!(summary.model.as_deref(), Some("gpt-5-codex"));
+        assert_eq!(summary.session_id.as_deref(), Some("s1"));
+        assert_eq!(summary.title.as_deref(), Some("Fix it"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn normalizes_custom_titles() {
+        assert_eq!(normalize_title(Some("  Login bug  ".to_string())).unwrap().as_deref(), Some("Login bug"));
+        assert_eq!(normalize_title(Some("   ".to_string())).unwrap(), None);
+        assert_eq!(normalize_title(None).unwrap(), None);
+        assert!(normalize_title(Some("two\nlines".to_string())).is_err());
+        assert!(normalize_title(Some("x".repeat(CUSTOM_TITLE_MAX_CHARS + 1))).is_err());
+    }
+
+    #[test]
+    fn validates_colors_against_palette() {
+        assert_eq!(validate_color(" Red ").unwrap(), "red");
+        assert!(validate_color("magenta").is_err());
+    }
+
+    #[test]
+    fn derives_title_from_first_user_message() {
+        let dir = std::env::temp_dir().join(format!("codexia-meta-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rollout = dir.join("rollout-test.jsonl");
+        std::fs::write(
+            &rollout,
+            concat!(
+                r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"x"}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:00:00Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>...</environment_context>"}]}}"#, "\n",
+                r#"{"timestamp":"2025-09-01T10:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"Fix the login bug\nDetails follow"}]}}"#, "\n",
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(derive_title(&rollout).as_deref(), Some("Fix the login bug"));
+
+        let header = rollout_header(&rollout).unwrap();
+        assert_eq!(header_timestamp(&header), parse_ts("2025-09-01T10:00:00Z"));
+        assert_eq!(last_event_timestamp(&rollout), parse_ts("2025-09-01T10:00:01Z"));
+        mark_session_ended(&rollout);
+        assert!(read_session_meta(&rollout).ended_at.is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_sidecar_updates_are_all_kept() {
+        let dir = std::env::temp_dir().join(format!("codexia-meta-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rollout = dir.join("rollout-test.jsonl");
+        std::fs::write(&rollout, "").unwrap();
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let rollout = &rollout;
+                scope.spawn(move || update_session_meta(rollout, |meta| meta.tags.push(format!("tag-{}", i))).unwrap());
+            }
+            scope.spawn(|| update_session_meta(&rollout, |meta| meta.favorite = !meta.favorite).unwrap());
+        });
+
+        let meta = read_session_meta(&rollout);
+        assert_eq!(meta.tags.len(), 8);
+        assert!(meta.favorite);
+        let files = std::fs::read_dir(&dir).unwrap().count();
+        assert_eq!(files, 2, "temporary sidecars were left behind");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn searches_message_text_ignoring_case() {
+        let dir = std::env::temp_dir().join(format!("codexia-search-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rollout = dir.join("rollout-test.jsonl");
+        std::fs::write(
+            &rollout,
+            concat!(
+                r#"{"type":"session_meta","payload":{"id":"x","instructions":"mention OAuth here"}}"#, "\n",
+                r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"How does the OAuth\nrefresh work?"}]}}"#, "\n",
+                r#"{"type":"response_item","payload":{"type":"function_call_output","output":"oauth"}}"#, "\n",
+                r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"The oauth token is refreshed lazily."}]}}"#, "\n",
+            ),
+        )
+        .unwrap();
+
+        let hits = search_files(std::iter::once(rollout.clone()), "OAUTH", 10);
+        let found: Vec<(usize, &str)> = hits.iter().map(|h| (h.line_number, h.role.as_str())).collect();
+        assert_eq!(found, [(2, "user"), (4, "assistant")]);
+        assert_eq!(hits[0].snippet, "How does the OAuth refresh work?");
+        assert_eq!(search_files(std::iter::once(rollout.clone()), "oauth", 1).len(), 1);
+        assert!(search_files(std::iter::once(rollout), "  ", 10).is_empty());
+
+        let long = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let (start, end) = find_ignore_case(&long, &['n', 'e', 'e', 'd', 'l', 'e']).unwrap();
+        let cut = snippet(&long, start, end);
+        assert!(cut.starts_with('…') && cut.ends_with('…') && cut.contains("needle"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
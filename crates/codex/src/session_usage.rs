@@ -0,0 +1,117 @@
+//! Running token usage per session.
+//!
+//! Every `thread/tokenUsage/updated` adds the usage of the latest model
+//! response to the thread's total, which is emitted as
+//! `codex-usage-<thread_id>` and saved in the session's sidecar. A resumed
+//! session continues from the saved total instead of starting at zero.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use codexia_shared::event_sink::EventSink;
+use serde_json::{Value, json};
+
+use crate::app_server::CodexAppServer;
+use crate::session_meta::{read_session_meta, write_session_usage};
+use crate::usage::TokenTotals;
+
+pub fn usage_event(thread_id: &str) -> String {
+    crate::utils::event_name("usage", thread_id)
+}
+
+#[derive(Default)]
+pub struct UsageRegistry {
+    totals: Mutex<HashMap<String, TokenTotals>>,
+}
+
+impl UsageRegistry {
+    /// Adds `last` to the thread's total, starting from `saved` the first
+    /// time the thread is seen, and returns the new total.
+    fn add(&self, thread_id: &str, last: &TokenTotals, saved: impl FnOnce() -> Option<TokenTotals>) -> TokenTotals {
+        let mut totals = self.totals.lock().unwrap();
+        let total = totals
+            .entry(thread_id.to_string())
+            .or_insert_with(|| saved().unwrap_or_default());
+        total.add(last);
+        *total
+    }
+
+    pub fn get(&self, thread_id: &str) -> Option<TokenTotals> {
+        self.totals.lock().unwrap().get(thread_id).copied()
+    }
+
+    fn forget(&self, thread_id: &str) {
+        self.totals.lock().unwrap().remove(thread_id);
+    }
+}
+
+/// Handles a serialized `ServerNotification`.
+pub fn observe(codex: &CodexAppServer, event_sink: &dyn EventSink, notification: &Value) {
+    let Some(params) = notification.get("params") else {
+        return;
+    };
+    let Some(thread_id) = params.get("threadId").and_then(Value::as_str) else {
+        return;
+    };
+    match notification.get("method").and_then(Value::as_str) {
+        Some("thread/tokenUsage/updated") => {
+            let Some(last) = params.get("tokenUsage").and_then(|usage| usage.get("last")) else {
+                return;
+            };
+            let rollout = codex.threads.get(thread_id).and_then(|session| session.rollout_path);
+            let total = codex.usage.add(thread_id, &TokenTotals::from_breakdown(last), || {
+                rollout.as_deref().and_then(|rollout| read_session_meta(rollout).usage)
+            });
+            if let Some(rollout) = rollout {
+                // Sidecar I/O stays off the stdout reader.
+                tokio::task::spawn_blocking(move || write_session_usage(&rollout, total));
+            }
+            event_sink.emit(
+                &usage_event(thread_id),
+                json!({
+                    "threadId": thread_id,
+                    "turnId": params.get("turnId"),
+                    "inputTokens": total.input_tokens,
+                    "cachedInputTokens": total.cached_input_tokens,
+                    "outputTokens": total.output_tokens,
+                    "reasoningOutputTokens": total.reasoning_output_tokens,
+                    "totalTokens": total.total_tokens,
+                }),
+            );
+        }
+        Some("thread/closed") => codex.usage.forget(thread_id),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continues_from_saved_total() {
+        let registry = UsageRegistry::default();
+        let last = TokenTotals::from_breakdown(&json!({
+            "inputTokens": 100,
+            "cachedInputTokens": 40,
+            "outputTokens": 20,
+            "reasoningOutputTokens": 5,
+            "totalTokens": 120,
+        }));
+        let saved = TokenTotals {
+            input_tokens: 1_000,
+            output_tokens: 300,
+            total_tokens: 1_300,
+            ..TokenTotals::default()
+        };
+
+        assert_eq!(registry.add("t1", &last, || Some(saved)).input_tokens, 1_100);
+        let total = registry.add("t1", &last, || panic!("the saved total is only read once"));
+        assert_eq!((total.input_tokens, total.output_tokens, total.total_tokens), (1_200, 340, 1_540));
+        assert_eq!(registry.add("t2", &last, || None), last);
+
+        registry.forget("t1");
+        assert_eq!(registry.get("t1"), None);
+        assert_eq!(usage_event("t1"), "codex-usage-t1");
+    }
+}
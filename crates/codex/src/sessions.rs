@@ -0,0 +1,287 @@
+//! Helpers for locating codex rollout files on disk.
+//!
+//! Codex writes each thread as `sessions/YYYY/MM/DD/rollout-<ts>-<uuid>.jsonl`
+//! under `CODEX_HOME`.
+
+use std::path::{Component, Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::error::CodexiaError;
+use crate::session_meta::meta_path;
+use crate::settings::load_codex_config;
+use crate::utils::try_codex_home;
+
+/// [`CodexConfig::sessions_dir`] when set, else `sessions` in the codex home.
+///
+/// [`CodexConfig::sessions_dir`]: crate::settings::CodexConfig
+pub fn get_sessions_path() -> Result<PathBuf, String> {
+    if let Some(dir) = load_codex_config().sessions_dir() {
+        return Ok(dir);
+    }
+    try_codex_home().map(|home| home.join("sessions")).map_err(|err| {
+        format!(
+            "Cannot locate the codex sessions directory: sessions_dir is not configured, {}",
+            err
+        )
+    })
+}
+
+/// Returns every `.jsonl` file below `root`, sorted by path.
+pub fn scan_jsonl_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("jsonl"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Derives the day a rollout was created from its `YYYY/MM/DD` parent directories.
+pub fn rollout_date(path: &Path) -> Option<NaiveDate> {
+    let day = path.parent()?;
+    let month = day.parent()?;
+    let year = month.parent()?;
+    let name = |p: &Path| p.file_name().and_then(|n| n.to_str()).map(str::to_string);
+    let date = format!("{}-{}-{}", name(year)?, name(month)?, name(day)?);
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()
+}
+
+/// Whether the file name ends in `-<uuid>.jsonl`, which codex relies on to
+/// find a thread's rollout.
+fn has_uuid_suffix(path: &Path) -> bool {
+    let Some(stem) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".jsonl"))
+    else {
+        return false;
+    };
+    stem.len() > 36
+        && stem.is_char_boundary(stem.len() - 36)
+        && uuid::Uuid::parse_str(&stem[stem.len() - 36..]).is_ok()
+}
+
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    // Renaming fails across filesystems; fall back to copying.
+    std::fs::rename(from, to).or_else(|_| {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)
+    })
+}
+
+fn move_rollout(root: &Path, file: &Path, dest_dir: &Path) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Sessions directory unavailable: {}", e))?;
+    let source = file
+        .canonicalize()
+        .map_err(|e| format!("Session file not found: {}: {}", file.display(), e))?;
+    if !source.starts_with(&root) || !source.is_file() {
+        return Err(format!("{} is not a session under {}", file.display(), root.display()));
+    }
+    if !has_uuid_suffix(&source) {
+        return Err(format!("{} is not a rollout-…-<uuid>.jsonl file", file.display()));
+    }
+    if dest_dir.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err("Destination must not contain '..'".to_string());
+    }
+    let dest_dir = if dest_dir.is_relative() {
+        root.join(dest_dir)
+    } else {
+        dest_dir.to_path_buf()
+    };
+    if !dest_dir.starts_with(&root) {
+        return Err(format!("Destination must be inside {}", root.display()));
+    }
+    std::fs::create_dir_all(&dest_dir).map_err(|e| format!("Failed to create {}: {}", dest_dir.display(), e))?;
+    // Re-checked after resolving symlinks in the destination.
+    let dest_dir = dest_dir.canonicalize().map_err(|e| e.to_string())?;
+    if !dest_dir.starts_with(&root) {
+        return Err(format!("Destination must be inside {}", root.display()));
+    }
+
+    let file_name = source.file_name().ok_or("Session file has no name")?;
+    let dest = dest_dir.join(file_name);
+    if dest == source {
+        return Ok(dest);
+    }
+    if dest.exists() {
+        return Err(format!("{} already exists", dest.display()));
+    }
+    move_file(&source, &dest).map_err(|e| format!("Failed to move {}: {}", source.display(), e))?;
+    let sidecar = meta_path(&source);
+    if sidecar.exists() {
+        if let Err(err) = move_file(&sidecar, &meta_path(&dest)) {
+            log::warn!("Moved {} but not its metadata: {}", dest.display(), err);
+        }
+    }
+    log::info!("moved session {} to {}", source.display(), dest.display());
+    Ok(dest)
+}
+
+/// Moves a rollout and its `.meta.json` sidecar to `dest_dir`, keeping the
+/// file name so codex still finds it by thread id. Both ends must be inside
+/// the sessions directory; a relative `dest_dir` is taken relative to it.
+pub fn move_session(file_path: &str, dest_dir: &str) -> Result<String, String> {
+    let root = get_sessions_path()?;
+    let dest = move_rollout(&root, Path::new(file_path), Path::new(dest_dir))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeleteResult {
+    pub path: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// `file` with symlinks resolved, if it is a `.jsonl` rollout inside `root`.
+pub(crate) fn rollout_under(root: &Path, file: &Path) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Sessions directory unavailable: {}", e))?;
+    let source = file
+        .canonicalize()
+        .map_err(|e| format!("Session file not found: {}: {}", file.display(), e))?;
+    if !source.starts_with(&root) || !source.is_file() {
+        return Err(format!("{} is not a session under {}", file.display(), root.display()));
+    }
+    if source.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+        return Err(format!("{} is not a .jsonl rollout", file.display()));
+    }
+    Ok(source)
+}
+
+fn delete_rollout(root: &Path, file: &Path) -> Result<(), String> {
+    let source = rollout_under(root, file)?;
+    std::fs::remove_file(&source).map_err(|e| format!("Failed to delete {}: {}", source.display(), e))?;
+    match std::fs::remove_file(meta_path(&source)) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            log::warn!("Deleted {} but not its metadata: {}", source.display(), err);
+        }
+        _ => {}
+    }
+    log::info!("deleted session {}", source.display());
+    Ok(())
+}
+
+fn delete_rollouts(root: &Path, file_paths: &[String]) -> Result<Vec<DeleteResult>, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Sessions directory unavailable: {}", e))?;
+    Ok(file_paths
+        .iter()
+        .map(|path| {
+            let result = delete_rollout(&root, Path::new(path));
+            DeleteResult {
+                path: path.clone(),
+                success: result.is_ok(),
+                error: result.err(),
+            }
+        })
+        .collect())
+}
+
+/// `file_path` resolved, if it is a rollout in the sessions directory.
+pub fn session_rollout(file_path: &str) -> Result<PathBuf, String> {
+    rollout_under(&get_sessions_path()?, Path::new(file_path))
+}
+
+/// Deletes each rollout in `file_paths` with its `.meta.json` sidecar,
+/// reporting every file separately so one failure does not stop the rest.
+/// Files outside the sessions directory are refused.
+pub fn delete_session_files(file_paths: &[String]) -> Result<Vec<DeleteResult>, String> {
+    delete_rollouts(&get_sessions_path()?, file_paths)
+}
+
+/// The newest rollout below `root` named after `session_id`.
+fn find_rollout_in(root: &Path, session_id: &str) -> Option<PathBuf> {
+    let suffix = format!("-{}.jsonl", session_id);
+    scan_jsonl_files(root)
+        .into_iter()
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.ends_with(&suffix)))
+        .max_by_key(|path| path.file_name().map(|n| n.to_os_string()))
+}
+
+/// Locates the rollout codex wrote for thread `session_id`.
+pub fn find_rollout_path_for_session(session_id: &str) -> Result<PathBuf, CodexiaError> {
+    if uuid::Uuid::parse_str(session_id).is_err() {
+        return Err(format!("'{}' is not a session id", session_id).into());
+    }
+    let root = get_sessions_path()?;
+    find_rollout_in(&root, session_id).ok_or_else(|| CodexiaError::SessionNotFound {
+        session_id: session_id.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_newest_rollout_of_session() {
+        let root = std::env::temp_dir().join(format!("codexia-find-{}", uuid::Uuid::new_v4()));
+        let id = uuid::Uuid::new_v4().to_string();
+        let older = root.join("2025").join("08").join("31");
+        let newer = root.join("2025").join("09").join("01");
+        std::fs::create_dir_all(&older).unwrap();
+        std::fs::create_dir_all(&newer).unwrap();
+        std::fs::write(older.join(format!("rollout-2025-08-31T09-00-00-{}.jsonl", id)), "{}\n").unwrap();
+        let latest = newer.join(format!("rollout-2025-09-01T10-00-00-{}.jsonl", id));
+        std::fs::write(&latest, "{}\n").unwrap();
+        std::fs::write(newer.join(format!("rollout-2025-09-01T11-00-00-{}.jsonl", uuid::Uuid::new_v4())), "{}\n").unwrap();
+
+        assert_eq!(find_rollout_in(&root, &id), Some(latest));
+        assert_eq!(find_rollout_in(&root, &uuid::Uuid::new_v4().to_string()), None);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn moves_rollout_with_sidecar_inside_root() {
+        let root = std::env::temp_dir().join(format!("codexia-move-{}", uuid::Uuid::new_v4()));
+        let day = root.join("2025").join("09").join("01");
+        std::fs::create_dir_all(&day).unwrap();
+        let rollout = day.join(format!("rollout-2025-09-01T10-00-00-{}.jsonl", uuid::Uuid::new_v4()));
+        std::fs::write(&rollout, "{}\n").unwrap();
+        std::fs::write(meta_path(&rollout), "{\"favorite\":true}").unwrap();
+        let stray = day.join("notes.jsonl");
+        std::fs::write(&stray, "{}\n").unwrap();
+
+        assert!(move_rollout(&root, &rollout, Path::new("../elsewhere")).is_err());
+        assert!(move_rollout(&root, &rollout, &std::env::temp_dir()).is_err());
+        assert!(move_rollout(&root, &stray, Path::new("projects")).is_err());
+
+        let moved = move_rollout(&root, &rollout, Path::new("projects/app")).unwrap();
+        assert!(moved.ends_with(Path::new("projects/app").join(rollout.file_name().unwrap())));
+        assert!(!rollout.exists() && !meta_path(&rollout).exists());
+        assert!(meta_path(&moved).exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn deletes_only_rollouts_inside_root() {
+        let base = std::env::temp_dir().join(format!("codexia-delete-{}", uuid::Uuid::new_v4()));
+        let root = base.join("sessions");
+        let day = root.join("2025").join("09").join("01");
+        std::fs::create_dir_all(&day).unwrap();
+        let rollout = day.join(format!("rollout-2025-09-01T10-00-00-{}.jsonl", uuid::Uuid::new_v4()));
+        std::fs::write(&rollout, "{}\n").unwrap();
+        std::fs::write(meta_path(&rollout), "{\"favorite\":true}").unwrap();
+        let outside = base.join("keep.jsonl");
+        std::fs::write(&outside, "{}\n").unwrap();
+
+        let paths = [&rollout, &outside, &day.join("gone.jsonl")].map(|p| p.to_string_lossy().to_string());
+        let results = delete_rollouts(&root, &paths).unwrap();
+        assert_eq!(results.iter().map(|r| r.success).collect::<Vec<_>>(), [true, false, false]);
+        assert!(results[1].error.as_deref().unwrap().contains("is not a session"));
+        assert!(!rollout.exists() && !meta_path(&rollout).exists());
+        assert!(outside.exists());
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
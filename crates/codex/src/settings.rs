@@ -0,0 +1,601 @@
+//! Codexia-side settings for the codex app-server, stored in
+//! `~/.codexia/codex.json`.
+//!
+//! These govern how Codexia drives the app-server; they are not codex's own
+//! `config.toml`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_server::{CodexAppServer, SHUTDOWN_TIMEOUT};
+use crate::line_reader::DEFAULT_MAX_LINE_BYTES;
+use crate::reasoning::ReasoningCapabilities;
+use crate::spawn::SpawnStrategy;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodexConfig {
+    /// Decline approval requests left unanswered for this many seconds.
+    /// `None` waits forever.
+    pub approval_auto_deny_secs: Option<u64>,
+    /// Executables or directories searched for codex before the built-in
+    /// locations.
+    pub extra_discovery_paths: Vec<String>,
+    /// Proxy URL for codex's HTTP and HTTPS traffic.
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that bypass [`CodexConfig::proxy`].
+    pub no_proxy: Option<String>,
+    /// Additions to the built-in reasoning support map.
+    pub reasoning_capabilities: ReasoningCapabilities,
+    /// OpenAI-compatible endpoint registered with codex as a model provider.
+    pub oss_provider: Option<OssProviderConfig>,
+    /// Resend a turn that failed on a provider rate limit, with exponential
+    /// backoff.
+    pub auto_retry_rate_limit: bool,
+    /// Instructions added to every new thread. They augment the model's
+    /// default instructions rather than replacing them.
+    pub system_prompt: Option<String>,
+    /// Hold every proposed patch of a thread for review as one batch instead
+    /// of asking about each; see [`crate::patches::PatchRegistry::add_preview`].
+    pub preview_mode: bool,
+    /// Longest app-server stdout line kept, in bytes; longer lines are cut
+    /// and dropped. Defaults to [`DEFAULT_MAX_LINE_BYTES`].
+    pub max_stdout_line_bytes: Option<usize>,
+    /// Emit a heartbeat for a busy thread every this many seconds without
+    /// events. `None` turns heartbeats off.
+    pub heartbeat_secs: Option<u64>,
+    /// Monorepo root new threads run in. A thread's own working directory
+    /// must lie inside it and is passed to the model as the area to focus on.
+    pub repo_root: Option<String>,
+    /// Also write every approval decision to the session's `.meta.json`.
+    pub persist_approval_history: bool,
+    /// Where codex keeps rollouts, for setups where the codex home cannot
+    /// be resolved. Defaults to `sessions` in the codex home.
+    pub sessions_dir: Option<String>,
+    /// Emit `codex-exec-progress-<thread_id>` events for command output
+    /// that shows a count or percentage.
+    pub exec_progress: bool,
+    /// How long shutdown waits for codex to exit on its own before stopping
+    /// it. Defaults to 3 seconds.
+    pub shutdown_timeout_secs: Option<u64>,
+    /// How codex is wrapped when spawned. `pty` needs the `script` binary
+    /// and fails to connect without it; unset behaves like `auto`.
+    pub spawn_strategy: Option<SpawnStrategy>,
+    /// Default `model_reasoning_effort` for new threads, one of
+    /// [`REASONING_EFFORTS`]. Threads can still override it.
+    pub reasoning_effort: Option<String>,
+    /// Default `model_reasoning_summary` for new threads, one of
+    /// [`REASONING_SUMMARIES`].
+    pub reasoning_summary: Option<String>,
+    /// Provider new threads use unless they name one, e.g. `azure`, `ollama`
+    /// or a key of `model_providers` in codex's `config.toml`. Takes
+    /// precedence over selecting [`CodexConfig::oss_provider`] per thread.
+    pub model_provider: Option<String>,
+    /// Extra `codex app-server` flags, e.g. `-c key=value` or
+    /// `--enable <feature>`; checked by [`CodexConfig::custom_args`].
+    pub custom_args: Vec<String>,
+    /// Extra environment variables for the codex process, e.g. a provider's
+    /// API key or base URL. Values of secret-looking names are redacted
+    /// wherever the launch command is shown or logged.
+    pub env: Option<HashMap<String, String>>,
+    /// Queue messages sent while a thread's turn is running and send them
+    /// once it completes; see [`crate::message_queue`].
+    pub queue_while_busy: bool,
+}
+
+/// Longest accepted [`CodexConfig::system_prompt`], so pasting a whole file
+/// by accident does not eat the context window.
+pub const MAX_SYSTEM_PROMPT_CHARS: usize = 16_000;
+
+/// Flags of `codex app-server` that take the next argument as their value.
+const VALUE_FLAGS: &[&str] = &["-c", "--config", "--enable", "--disable"];
+
+/// Checks the value of a `custom_args` flag.
+fn check_flag_value(flag: &str, value: &str) -> Result<(), String> {
+    if value.is_empty() || value.starts_with('-') {
+        return Err(format!("custom_args flag {} needs a value", flag));
+    }
+    if matches!(flag, "-c" | "--config") {
+        let Some((key, _)) = value.split_once('=') else {
+            return Err(format!("custom_args {} value '{}' must be key=value", flag, value));
+        };
+        if let Some(critical) = crate::defaults::safety_critical_key(key.trim()) {
+            log::warn!("Rejected custom_args override of {} ({})", critical, key.trim());
+            return Err(format!(
+                "custom_args may not override {}; set it through the access mode instead",
+                critical
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub const REASONING_EFFORTS: &[&str] = &["minimal", "low", "medium", "high"];
+pub const REASONING_SUMMARIES: &[&str] = &["auto", "concise", "detailed", "none"];
+
+/// TOML basic strings share JSON's quoting rules.
+fn toml_quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Environment variable the OSS provider's API key is passed in, so it never
+/// shows up on the command line.
+pub const OSS_API_KEY_ENV: &str = "CODEXIA_OSS_API_KEY";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OssProviderConfig {
+    /// Key under codex's `model_providers`; threads select it through
+    /// `modelProvider`.
+    pub provider_id: String,
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+impl OssProviderConfig {
+    fn api_key(&self) -> Option<&str> {
+        self.api_key.as_deref().map(str::trim).filter(|key| !key.is_empty())
+    }
+
+    /// `-c model_providers.<id>.*` overrides registering the provider.
+    pub fn config_args(&self) -> Result<Vec<String>, String> {
+        let id = self.provider_id.trim();
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Invalid OSS provider id '{}': use letters, digits, '-' or '_'",
+                self.provider_id
+            ));
+        }
+        let base_url = self.base_url.trim();
+        if base_url.is_empty() {
+            return Err(format!("OSS provider '{}' has no base URL", id));
+        }
+        let mut entries = vec![("name", toml_quote(id)), ("base_url", toml_quote(base_url))];
+        if self.api_key().is_some() {
+            entries.push(("env_key", toml_quote(OSS_API_KEY_ENV)));
+        }
+        Ok(entries
+            .into_iter()
+            .flat_map(|(key, value)| {
+                ["-c".to_string(), format!("model_providers.{}.{}={}", id, key, value)]
+            })
+            .collect())
+    }
+}
+
+impl CodexConfig {
+    /// Environment variables passing the proxy settings to the codex process.
+    /// Both spellings are set since tools disagree on which one they read.
+    pub fn proxy_env(&self) -> Vec<(&'static str, String)> {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let mut env = Vec::new();
+        if let Some(proxy) = non_empty(&self.proxy) {
+            for key in ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"] {
+                env.push((key, proxy.clone()));
+            }
+        }
+        if let Some(no_proxy) = non_empty(&self.no_proxy) {
+            for key in ["NO_PROXY", "no_proxy"] {
+                env.push((key, no_proxy.clone()));
+            }
+        }
+        env
+    }
+
+    /// The system prompt, if set, checked against [`MAX_SYSTEM_PROMPT_CHARS`].
+    pub fn system_prompt(&self) -> Result<Option<&str>, String> {
+        let Some(prompt) = self.system_prompt.as_deref().map(str::trim).filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
+        let chars = prompt.chars().count();
+        if chars > MAX_SYSTEM_PROMPT_CHARS {
+            return Err(format!(
+                "System prompt is {} characters; the limit is {}",
+                chars, MAX_SYSTEM_PROMPT_CHARS
+            ));
+        }
+        Ok(Some(prompt))
+    }
+
+    /// `-c model_reasoning_effort=...` and `-c model_reasoning_summary=...`
+    /// for the values that are set; an unknown value is an error.
+    pub fn reasoning_args(&self) -> Result<Vec<String>, String> {
+        let settings = [
+            ("model_reasoning_effort", &self.reasoning_effort, REASONING_EFFORTS),
+            ("model_reasoning_summary", &self.reasoning_summary, REASONING_SUMMARIES),
+        ];
+        let mut args = Vec::new();
+        for (key, value, allowed) in settings {
+            let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            if !allowed.contains(&value) {
+                return Err(format!("Unknown {} '{}'; expected one of: {}", key, value, allowed.join(", ")));
+            }
+            args.push("-c".to_string());
+            args.push(format!("{}={}", key, toml_quote(value)));
+        }
+        Ok(args)
+    }
+
+    /// `-c model_provider=...` when a default provider is set. The id is
+    /// passed on the command line, so only letters, digits, `-`, `_` and `.`
+    /// are accepted.
+    pub fn provider_args(&self) -> Result<Vec<String>, String> {
+        let Some(provider) = self.model_provider.as_deref() else {
+            return Ok(Vec::new());
+        };
+        let id = provider.trim();
+        if id.is_empty() {
+            return Err("Model provider must not be empty".to_string());
+        }
+        if !id.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) {
+            return Err(format!(
+                "Invalid model provider '{}': use letters, digits, '-', '_' or '.'",
+                provider
+            ));
+        }
+        Ok(vec!["-c".to_string(), format!("model_provider={}", toml_quote(id))])
+    }
+
+    /// [`CodexConfig::custom_args`] without blank entries, rejected when an
+    /// entry would be read as a subcommand or positional argument (a bare
+    /// word, `--`), a value flag lacks its value, or `-c` sets the sandbox or
+    /// approval policy. Flags `codex app-server` is not known to take are
+    /// passed on with a warning.
+    pub fn custom_args(&self) -> Result<Vec<String>, String> {
+        let args: Vec<&str> = self.custom_args.iter().map(|arg| arg.trim()).filter(|arg| !arg.is_empty()).collect();
+        let mut checked = Vec::with_capacity(args.len());
+        let mut iter = args.into_iter();
+        while let Some(arg) = iter.next() {
+            if arg.chars().any(char::is_control) {
+                return Err(format!("custom_args entry {:?} contains control characters", arg));
+            }
+            if arg == "--" {
+                return Err("custom_args may not contain a '--' separator".to_string());
+            }
+            if !arg.starts_with('-') {
+                return Err(format!(
+                    "custom_args entry '{}' would be read as a subcommand or argument; only flags are allowed",
+                    arg
+                ));
+            }
+            checked.push(arg.to_string());
+            // `-c` also takes its value attached, as in `-ckey=value`.
+            let inline = match arg.strip_prefix("-c").filter(|rest| !rest.is_empty() && !arg.starts_with("--")) {
+                Some(value) => Some(("-c", value)),
+                None => arg.split_once('='),
+            };
+            match inline {
+                Some((flag, value)) if VALUE_FLAGS.contains(&flag) => check_flag_value(flag, value)?,
+                _ if VALUE_FLAGS.contains(&arg) => {
+                    let value = iter.next().unwrap_or_default();
+                    check_flag_value(arg, value)?;
+                    checked.push(value.to_string());
+                }
+                _ => log::warn!("Passing unrecognized custom_args flag {} to codex app-server", arg),
+            }
+        }
+        Ok(checked)
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        self.shutdown_timeout_secs.map_or(SHUTDOWN_TIMEOUT, Duration::from_secs)
+    }
+
+    /// The configured sessions directory, if set.
+    pub fn sessions_dir(&self) -> Option<PathBuf> {
+        self.sessions_dir
+            .as_deref()
+            .map(str::trim)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// The repo root, if set, resolved to an existing directory.
+    pub fn repo_root(&self) -> Result<Option<PathBuf>, String> {
+        let Some(root) = self.repo_root.as_deref().map(str::trim).filter(|r| !r.is_empty()) else {
+            return Ok(None);
+        };
+        let resolved = PathBuf::from(root)
+            .canonicalize()
+            .map_err(|e| format!("Repo root {} is not accessible: {}", root, e))?;
+        if !resolved.is_dir() {
+            return Err(format!("Repo root {} is not a directory", root));
+        }
+        // Windows canonical paths carry a `\\?\` prefix codex cannot use.
+        let resolved = match resolved.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+            Some(plain) => PathBuf::from(plain),
+            None => resolved,
+        };
+        Ok(Some(resolved))
+    }
+
+    pub fn max_stdout_line_bytes(&self) -> usize {
+        self.max_stdout_line_bytes
+            .filter(|bytes| *bytes > 0)
+            .unwrap_or(DEFAULT_MAX_LINE_BYTES)
+    }
+
+    /// [`CodexConfig::env`] sorted by name, rejected when a name is not a
+    /// portable variable name (letters, digits and `_`, not starting with a
+    /// digit) or a value contains a NUL byte.
+    pub fn custom_env(&self) -> Result<Vec<(String, String)>, String> {
+        let mut env: Vec<(String, String)> = self
+            .env
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.trim().to_string(), value.clone()))
+            .collect();
+        env.sort();
+        for (key, value) in &env {
+            let valid = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(format!("Invalid environment variable name '{}'", key));
+            }
+            if value.contains('\0') {
+                return Err(format!("Environment variable {} contains a NUL byte", key));
+            }
+        }
+        Ok(env)
+    }
+
+    /// Environment variables for the codex process: the proxy settings, the
+    /// OSS provider's API key and [`CodexConfig::env`], which is left out
+    /// with a warning when invalid.
+    pub fn launch_env(&self) -> Vec<(String, String)> {
+        let mut env: Vec<(String, String)> = self
+            .proxy_env()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        if let Some(key) = self.oss_provider.as_ref().and_then(OssProviderConfig::api_key) {
+            env.push((OSS_API_KEY_ENV.to_string(), key.to_string()));
+        }
+        match self.custom_env() {
+            Ok(custom) => env.extend(custom),
+            Err(err) => log::warn!("Ignoring env settings: {}", err),
+        }
+        env
+    }
+}
+
+pub fn codex_config_path() -> PathBuf {
+    crate::utils::codexia_dir().join("codex.json")
+}
+
+/// Loads the stored settings, falling back to defaults when the file is
+/// missing or malformed.
+pub fn load_codex_config() -> CodexConfig {
+    let path = codex_config_path();
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return CodexConfig::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        log::warn!("Ignoring malformed {}: {}", path.display(), err);
+        CodexConfig::default()
+    })
+}
+
+pub fn save_codex_config(config: &CodexConfig) -> Result<(), String> {
+    let path = codex_config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Returns the settings in effect: the running app-server's, or the stored
+/// ones when codex is not connected.
+pub fn current_codex_config(codex: Option<&CodexAppServer>) -> CodexConfig {
+    codex.map(CodexAppServer::config).unwrap_or_else(load_codex_config)
+}
+
+/// Persists `config` and applies it to the running app-server, if any.
+pub fn update_codex_config(
+    codex: Option<&CodexAppServer>,
+    config: CodexConfig,
+) -> Result<CodexConfig, String> {
+    config.system_prompt()?;
+    config.repo_root()?;
+    config.reasoning_args()?;
+    config.provider_args()?;
+    config.custom_args()?;
+    config.custom_env()?;
+    save_codex_config(&config)?;
+    if let Some(codex) = codex {
+        codex.set_config(config.clone());
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_args_cannot_change_the_invocation() {
+        let args = |args: &[&str]| {
+            CodexConfig {
+                custom_args: args.iter().map(|a| a.to_string()).collect(),
+                ..CodexConfig::default()
+            }
+            .custom_args()
+        };
+        assert_eq!(
+            args(&["-c", "model_verbosity=\"low\"", " ", "--config=tools.web_search=true", "--enable", "rmcp_client"]).unwrap(),
+            ["-c", "model_verbosity=\"low\"", "--config=tools.web_search=true", "--enable", "rmcp_client"]
+        );
+
+        for hostile in [
+            &["exec", "rm -rf ~"][..],
+            &["--", "proto"],
+            &["-c", "--dangerously-bypass-approvals-and-sandbox"],
+            &["-c"],
+            &["-c", "sandbox_mode=\"danger-full-access\""],
+            &["--config=approval_policy.granular=true"],
+            &["-csandbox_mode=\"danger-full-access\""],
+            &["-c", "\"sandbox_mode\"=\"danger-full-access\""],
+            &["-c", "profile=\"yolo\""],
+            &["-c", "profiles.yolo.sandbox_mode=\"danger-full-access\""],
+            &["--config=profiles.yolo.approval_policy=\"never\""],
+            &["-c", "profiles.yolo={ sandbox_mode = \"danger-full-access\" }"],
+            &["-c", "no_equals_sign"],
+            &["-c", "model=\"x\"\nexec"],
+        ] {
+            assert!(args(hostile).is_err(), "{:?} was accepted", hostile);
+        }
+        // Unknown flags are passed on, only logged.
+        assert_eq!(args(&["--listen=stdio"]).unwrap(), ["--listen=stdio"]);
+    }
+
+    #[test]
+    fn custom_env_is_added_after_built_in_variables() {
+        let env = |pairs: &[(&str, &str)]| CodexConfig {
+            proxy: Some("http://proxy:8080".to_string()),
+            env: Some(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()),
+            ..CodexConfig::default()
+        };
+        let launch = env(&[("OPENAI_BASE_URL", "http://gw/v1"), ("AZURE_API_KEY", "sk-az")]).launch_env();
+        assert_eq!(launch.len(), 6);
+        assert_eq!(launch[4], ("AZURE_API_KEY".to_string(), "sk-az".to_string()));
+        assert_eq!(launch[5].0, "OPENAI_BASE_URL");
+
+        assert!(env(&[("1BAD", "x")]).custom_env().is_err());
+        assert!(env(&[("A=B", "x")]).custom_env().is_err());
+        // An invalid entry drops the custom variables, not the proxy.
+        assert_eq!(env(&[("BAD NAME", "x")]).launch_env().len(), 4);
+    }
+
+    #[test]
+    fn proxy_env_skips_blank_values() {
+        let config = CodexConfig {
+            proxy: Some("http://proxy.local:8080".to_string()),
+            no_proxy: Some("  ".to_string()),
+            ..CodexConfig::default()
+        };
+        let env = config.proxy_env();
+        assert_eq!(env.len(), 4);
+        assert!(env.iter().all(|(_, value)| value == "http://proxy.local:8080"));
+    }
+
+    #[test]
+    fn limits_system_prompt_length() {
+        let mut config = CodexConfig {
+            system_prompt: Some("  Prefer small commits.  ".to_string()),
+            ..CodexConfig::default()
+        };
+        assert_eq!(config.system_prompt().unwrap(), Some("Prefer small commits."));
+        config.system_prompt = Some("x".repeat(MAX_SYSTEM_PROMPT_CHARS + 1));
+        assert!(config.system_prompt().is_err());
+    }
+
+    #[test]
+    fn blank_sessions_dir_is_unset() {
+        let mut config = CodexConfig {
+            sessions_dir: Some("   ".to_string()),
+            ..CodexConfig::default()
+        };
+        assert_eq!(config.sessions_dir(), None);
+        config.sessions_dir = Some(" /data/codex/sessions ".to_string());
+        assert_eq!(config.sessions_dir(), Some(PathBuf::from("/data/codex/sessions")));
+    }
+
+    #[test]
+    fn reasoning_settings_become_config_overrides() {
+        let mut config = CodexConfig {
+            reasoning_effort: Some("high".to_string()),
+            reasoning_summary: Some(" ".to_string()),
+            ..CodexConfig::default()
+        };
+        assert_eq!(config.reasoning_args().unwrap(), vec!["-c", "model_reasoning_effort=\"high\""]);
+        config.reasoning_summary = Some("detailed".to_string());
+        assert_eq!(config.reasoning_args().unwrap()[3], "model_reasoning_summary=\"detailed\"");
+        config.reasoning_effort = Some("maximal".to_string());
+        assert!(config.reasoning_args().is_err());
+        assert!(CodexConfig::default().reasoning_args().unwrap().is_empty());
+    }
+
+    #[test]
+    fn oss_provider_becomes_config_overrides() {
+        let mut provider = OssProviderConfig {
+            provider_id: "lan-llm".to_string(),
+            base_url: "http://10.0.0.5:8000/v1".to_string(),
+            api_key: Some("sk-local".to_string()),
+        };
+        assert_eq!(
+            provider.config_args().unwrap(),
+            vec![
+                "-c",
+                "model_providers.lan-llm.name=\"lan-llm\"",
+                "-c",
+                "model_providers.lan-llm.base_url=\"http://10.0.0.5:8000/v1\"",
+                "-c",
+                "model_providers.lan-llm.env_key=\"CODEXIA_OSS_API_KEY\"",
+            ]
+        );
+        let config = CodexConfig {
+            oss_provider: Some(provider.clone()),
+            ..CodexConfig::default()
+        };
+        assert_eq!(config.launch_env(), vec![(OSS_API_KEY_ENV.to_string(), "sk-local".to_string())]);
+
+        provider.provider_id = "bad id".to_string();
+        assert!(provider.config_args().is_err());
+    }
+
+    #[test]
+    fn explicit_model_provider_is_selected_next_to_oss_provider() {
+        let mut config = CodexConfig {
+            oss_provider: Some(OssProviderConfig {
+                provider_id: "oss".to_string(),
+                base_url: "http://localhost:11434/v1".to_string(),
+                api_key: None,
+            }),
+            ..CodexConfig::default()
+        };
+        assert!(config.provider_args().unwrap().is_empty());
+
+        config.model_provider = Some(" azure ".to_string());
+        let args: Vec<String> = config
+            .oss_provider
+            .as_ref()
+            .unwrap()
+            .config_args()
+            .unwrap()
+            .into_iter()
+            .chain(config.provider_args().unwrap())
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                "-c",
+                "model_providers.oss.name=\"oss\"",
+                "-c",
+                "model_providers.oss.base_url=\"http://localhost:11434/v1\"",
+                "-c",
+                "model_provider=\"azure\"",
+            ]
+        );
+
+        for hostile in ["", "azure; rm -rf ~", "a b", "$(id)", "x\"y"] {
+            config.model_provider = Some(hostile.to_string());
+            assert!(config.provider_args().is_err(), "{:?}", hostile);
+        }
+    }
+}
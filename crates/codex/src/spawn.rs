@@ -0,0 +1,175 @@
+//! How the app-server process is wrapped when it is spawned.
+//!
+//! Some setups only see codex's output promptly when it believes it writes
+//! to a terminal, or when its stdio is line buffered. [`SpawnStrategy`] picks
+//! the wrapper: [`CodexConfig::spawn_strategy`] when set to anything but
+//! `auto`, else `CODEXIA_SPAWN_STRATEGY=pty|stdbuf|plain`, else none.
+//!
+//! [`CodexConfig::spawn_strategy`]: crate::settings::CodexConfig
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::discovery::CodexLaunch;
+use crate::error::CodexiaError;
+
+const SPAWN_STRATEGY_ENV: &str = "CODEXIA_SPAWN_STRATEGY";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpawnStrategy {
+    /// `CODEXIA_SPAWN_STRATEGY` if set, otherwise [`SpawnStrategy::Plain`].
+    #[default]
+    Auto,
+    /// Runs codex on a pseudo-terminal in raw mode through `script`, which
+    /// must be on `PATH`. Unix only.
+    Pty,
+    /// Runs codex under `stdbuf -oL -eL`, which must be on `PATH`. Unix only.
+    Stdbuf,
+    /// Spawns codex directly.
+    Plain,
+}
+
+impl SpawnStrategy {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pty" | "tty" | "script" => Some(Self::Pty),
+            "stdbuf" => Some(Self::Stdbuf),
+            "plain" | "none" => Some(Self::Plain),
+            _ => None,
+        }
+    }
+
+    /// The wrapper to use, and whether the user asked for it in the
+    /// settings, where a missing wrapper binary is an error rather than a
+    /// fallback to [`SpawnStrategy::Plain`].
+    fn resolve(self, env_value: Option<&str>) -> (Self, bool) {
+        match self {
+            Self::Auto => {
+                let from_env = env_value.and_then(|value| {
+                    let parsed = Self::parse(value);
+                    if parsed.is_none() {
+                        log::warn!("Ignoring unknown {} value '{}'", SPAWN_STRATEGY_ENV, value);
+                    }
+                    parsed
+                });
+                (from_env.unwrap_or(Self::Plain), false)
+            }
+            explicit => (explicit, true),
+        }
+    }
+
+    fn binary(self) -> Option<&'static str> {
+        match self {
+            Self::Pty => Some("script"),
+            Self::Stdbuf => Some("stdbuf"),
+            Self::Auto | Self::Plain => None,
+        }
+    }
+}
+
+fn find_on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// `value` as a single POSIX shell word.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// `script` running `codex app-server <args>` on a pty. Raw mode keeps the
+/// terminal from echoing our requests back, translating newlines or cutting
+/// long lines at the canonical-mode limit.
+fn pty_command(script: &Path, codex: &Path, args: &[String]) -> Command {
+    let mut command = Command::new(script);
+    if cfg!(target_os = "macos") {
+        command.args(["-q", "/dev/null", "sh", "-c", "stty raw -echo; exec \"$0\" \"$@\""]);
+        command.arg(codex).arg("app-server").args(args);
+    } else {
+        // util-linux `script` takes the command as one shell string.
+        let words: Vec<String> = std::iter::once(codex.to_string_lossy().to_string())
+            .chain(std::iter::once("app-server".to_string()))
+            .chain(args.iter().cloned())
+            .map(|word| shell_quote(&word))
+            .collect();
+        let inner = format!("stty raw -echo; exec {}", words.join(" "));
+        command.args(["-qfec", inner.as_str(), "/dev/null"]);
+    }
+    command
+}
+
+/// The command that starts `codex app-server <args>` with the configured
+/// wrapper.
+pub(crate) fn app_server_command(
+    launch: &CodexLaunch,
+    strategy: SpawnStrategy,
+    args: &[String],
+) -> Result<Command, CodexiaError> {
+    let direct = || {
+        let mut command = launch.command(["app-server"]);
+        command.args(args);
+        command
+    };
+    let env_value = std::env::var(SPAWN_STRATEGY_ENV).ok();
+    let (strategy, explicit) = strategy.resolve(env_value.as_deref());
+    let Some(binary) = strategy.binary() else {
+        return Ok(direct());
+    };
+
+    let unavailable = |reason: String| -> Result<Command, CodexiaError> {
+        if explicit {
+            return Err(CodexiaError::SpawnFailed(format!("spawn_strategy {:?}: {}", strategy, reason)));
+        }
+        log::warn!("Spawning codex directly, {:?} is unavailable: {}", strategy, reason);
+        Ok(direct())
+    };
+    let codex = match launch {
+        CodexLaunch::Native(path) if cfg!(unix) => path,
+        CodexLaunch::Native(_) => return unavailable("only supported on Unix".to_string()),
+        CodexLaunch::Wsl(_) => return unavailable("not supported for codex in WSL".to_string()),
+    };
+    let Some(wrapper) = find_on_path(binary) else {
+        return unavailable(format!("`{}` was not found on PATH", binary));
+    };
+
+    log::info!("codex app-server spawn strategy: {:?} ({})", strategy, wrapper.display());
+    Ok(match strategy {
+        SpawnStrategy::Pty => pty_command(&wrapper, codex, args),
+        _ => {
+            let mut command = Command::new(wrapper);
+            command.args(["-oL", "-eL"]).arg(codex).arg("app-server").args(args);
+            command
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_override_the_environment() {
+        assert_eq!(SpawnStrategy::Auto.resolve(None), (SpawnStrategy::Plain, false));
+        assert_eq!(SpawnStrategy::Auto.resolve(Some("PTY")), (SpawnStrategy::Pty, false));
+        assert_eq!(SpawnStrategy::Auto.resolve(Some("sometimes")), (SpawnStrategy::Plain, false));
+        assert_eq!(SpawnStrategy::Stdbuf.resolve(Some("pty")), (SpawnStrategy::Stdbuf, true));
+        assert_eq!(
+            serde_json::from_value::<SpawnStrategy>(serde_json::json!("stdbuf")).unwrap(),
+            SpawnStrategy::Stdbuf
+        );
+        assert_eq!(shell_quote("/opt/it's/codex"), r"'/opt/it'\''s/codex'");
+    }
+
+    #[test]
+    fn explicit_wrapper_for_wsl_is_rejected() {
+        let launch = CodexLaunch::Wsl("/usr/local/bin/codex".to_string());
+        let err = app_server_command(&launch, SpawnStrategy::Pty, &[]).unwrap_err();
+        assert!(matches!(err, CodexiaError::SpawnFailed(ref reason) if reason.contains("WSL")), "{}", err);
+        assert!(app_server_command(&launch, SpawnStrategy::Plain, &[]).is_ok());
+    }
+}
@@ -0,0 +1,119 @@
+//! Severity of the app-server's stderr lines.
+//!
+//! codex logs through `tracing`, so most stderr lines are info or warn
+//! output rather than errors. Each line is emitted on `codex:stderr` as a
+//! [`CodexLog`] with the level parsed from its prefix, `info` when it has
+//! none, and the thread it mentions, if any. Error lines that mention a
+//! thread are also emitted on `codex-error-<thread_id>`.
+
+use serde::Serialize;
+
+/// How many leading words may precede the level, e.g. a timestamp.
+const LEVEL_SCAN_WORDS: usize = 3;
+
+/// Span fields codex tags thread-scoped log lines with.
+const THREAD_FIELDS: &[&str] = &["thread_id=", "conversation_id=", "session_id="];
+
+pub fn error_event(thread_id: &str) -> String {
+    crate::utils::event_name("error", thread_id)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexLog {
+    pub level: LogLevel,
+    /// The line without terminal color codes.
+    pub message: String,
+    pub session_id: Option<String>,
+}
+
+/// Removes ANSI escape sequences such as `\x1b[33m`.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            // Parameters end at the first letter.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+fn parse_level(word: &str) -> Option<LogLevel> {
+    let word = word.trim_matches(|c: char| "[]():".contains(c));
+    match word.to_ascii_uppercase().as_str() {
+        "ERROR" | "ERR" | "FATAL" | "PANIC" => Some(LogLevel::Error),
+        "WARN" | "WARNING" => Some(LogLevel::Warn),
+        "INFO" => Some(LogLevel::Info),
+        "DEBUG" => Some(LogLevel::Debug),
+        "TRACE" => Some(LogLevel::Trace),
+        _ => None,
+    }
+}
+
+fn thread_id(message: &str) -> Option<String> {
+    THREAD_FIELDS.iter().find_map(|field| {
+        let start = message.find(field)? + field.len();
+        let id: String = message[start..]
+            .trim_start_matches('"')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        (!id.is_empty()).then_some(id)
+    })
+}
+
+pub fn parse_stderr_line(line: &str) -> CodexLog {
+    let message = strip_ansi(line);
+    let level = message
+        .split_whitespace()
+        .take(LEVEL_SCAN_WORDS)
+        .find_map(parse_level)
+        .unwrap_or(LogLevel::Info);
+    CodexLog {
+        level,
+        session_id: thread_id(&message),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_level_and_thread_from_tracing_lines() {
+        let log = parse_stderr_line(
+            "2025-09-01T10:00:00.123Z \x1b[33m WARN\x1b[0m codex_core::exec: thread_id=\"0199-ab\" command timed out",
+        );
+        assert_eq!(log.level, LogLevel::Warn);
+        assert_eq!(log.session_id.as_deref(), Some("0199-ab"));
+        assert!(!log.message.contains('\x1b'));
+
+        assert_eq!(parse_stderr_line("[ERROR] failed to refresh token").level, LogLevel::Error);
+        assert_eq!(parse_stderr_line("error: unexpected argument").level, LogLevel::Error);
+        assert_eq!(parse_stderr_line("Reading prompt from stdin...").level, LogLevel::Info);
+        assert_eq!(parse_stderr_line("a b c DEBUG too far in").level, LogLevel::Info);
+        assert_eq!(parse_stderr_line("plain").session_id, None);
+        assert_eq!(error_event("t1"), "codex-error-t1");
+    }
+}
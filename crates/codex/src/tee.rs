@@ -0,0 +1,197 @@
+//! Appends a plain transcript of selected threads to external files.
+//!
+//! Each completed user message, agent message, command execution and file
+//! change becomes one JSON line in the destination file, independent of
+//! codex's own rollout format. Optionally the app-server's stderr is written
+//! to the same file as `stderr` lines, so both streams appear in arrival
+//! order.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::Utc;
+use serde_json::{Value, json};
+
+struct TeeTarget {
+    path: PathBuf,
+    file: File,
+    include_stderr: bool,
+}
+
+impl TeeTarget {
+    fn write_line(&mut self, thread_id: &str, line: &Value) {
+        if let Err(err) = writeln!(self.file, "{}", line) {
+            log::warn!(
+                "Failed to tee thread {} to {}: {}",
+                thread_id,
+                self.path.display(),
+                err
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TeeRegistry {
+    targets: Mutex<HashMap<String, TeeTarget>>,
+}
+
+/// Builds the transcript line for a completed item, or `None` for item types
+/// that are not part of the transcript.
+fn transcript_line(thread_id: &str, turn_id: Option<&str>, item: &Value) -> Option<Value> {
+    let str_field = |key: &str| item.get(key).and_then(Value::as_str);
+    let mut line = match str_field("type")? {
+        "userMessage" => {
+            let text: Vec<&str> = item
+                .get("content")?
+                .as_array()?
+                .iter()
+                .filter_map(|input| input.get("text").and_then(Value::as_str))
+                .collect();
+            json!({ "kind": "user", "text": text.join("\n") })
+        }
+        "agentMessage" => json!({ "kind": "assistant", "text": str_field("text")? }),
+        "commandExecution" => json!({
+            "kind": "exec",
+            "command": str_field("command")?,
+            "cwd": item.get("cwd"),
+            "exitCode": item.get("exitCode"),
+            "status": item.get("status"),
+        }),
+        "fileChange" => {
+            let changes: Vec<Value> = item
+                .get("changes")?
+                .as_array()?
+                .iter()
+                .map(|change| {
+                    let kind = change.get("kind");
+                    json!({
+                        "path": change.get("path"),
+                        "kind": kind.and_then(|k| k.get("type")).or(kind),
+                    })
+                })
+                .collect();
+            json!({ "kind": "patch", "changes": changes, "status": item.get("status") })
+        }
+        _ => return None,
+    };
+
+    if let Value::Object(map) = &mut line {
+        map.insert("timestamp".to_string(), json!(Utc::now().to_rfc3339()));
+        map.insert("threadId".to_string(), json!(thread_id));
+        map.insert("turnId".to_string(), json!(turn_id));
+    }
+    Some(line)
+}
+
+impl TeeRegistry {
+    /// Starts appending `thread_id`'s transcript to `dest`, replacing any
+    /// previous destination for that thread.
+    pub fn start(&self, thread_id: &str, dest: &Path, include_stderr: bool) -> Result<(), String> {
+        if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dest)
+            .map_err(|e| format!("Failed to open {}: {}", dest.display(), e))?;
+        self.targets.lock().unwrap().insert(
+            thread_id.to_string(),
+            TeeTarget {
+                path: dest.to_path_buf(),
+                file,
+                include_stderr,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stops teeing `thread_id`. Returns the destination it was written to.
+    pub fn stop(&self, thread_id: &str) -> Option<PathBuf> {
+        self.targets
+            .lock()
+            .unwrap()
+            .remove(thread_id)
+            .map(|target| target.path)
+    }
+
+    /// Handles a serialized `ServerNotification`: appends completed items of
+    /// teed threads and stops teeing threads that were closed.
+    pub fn observe(&self, notification: &Value) {
+        let Some(method) = notification.get("method").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(params) = notification.get("params") else {
+            return;
+        };
+        let Some(thread_id) = params.get("threadId").and_then(Value::as_str) else {
+            return;
+        };
+
+        let mut targets = self.targets.lock().unwrap();
+        if method == "thread/closed" {
+            targets.remove(thread_id);
+            return;
+        }
+        if method != "item/completed" {
+            return;
+        }
+        let Some(target) = targets.get_mut(thread_id) else {
+            return;
+        };
+        let turn_id = params.get("turnId").and_then(Value::as_str);
+        let Some(line) = params
+            .get("item")
+            .and_then(|item| transcript_line(thread_id, turn_id, item))
+        else {
+            return;
+        };
+
+        target.write_line(thread_id, &line);
+    }
+
+    /// Appends an app-server stderr line to every tee that asked for it. The
+    /// process is shared by all threads, so the line is not thread-specific.
+    pub fn observe_stderr(&self, message: &str) {
+        let mut targets = self.targets.lock().unwrap();
+        for (thread_id, target) in targets.iter_mut().filter(|(_, t)| t.include_stderr) {
+            let line = json!({
+                "kind": "stderr",
+                "text": message,
+                "timestamp": Utc::now().to_rfc3339(),
+                "threadId": thread_id,
+            });
+            target.write_line(thread_id, &line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_transcript_items() {
+        let user = json!({ "type": "userMessage", "id": "1", "content": [{ "type": "text", "text": "hi", "text_elements": [] }] });
+        let line = transcript_line("t1", Some("turn-1"), &user).unwrap();
+        assert_eq!(line["kind"], "user");
+        assert_eq!(line["text"], "hi");
+        assert_eq!(line["threadId"], "t1");
+
+        let patch = json!({
+            "type": "fileChange",
+            "id": "2",
+            "changes": [{ "path": "src/main.rs", "kind": { "type": "update", "move_path": null }, "diff": "" }],
+            "status": "completed",
+        });
+        let line = transcript_line("t1", None, &patch).unwrap();
+        assert_eq!(line["changes"][0]["kind"], "update");
+
+        let reasoning = json!({ "type": "reasoning", "id": "3", "summary": [], "content": [] });
+        assert!(transcript_line("t1", None, &reasoning).is_none());
+    }
+}
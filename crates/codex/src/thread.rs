@@ -0,0 +1,796 @@
+//! Thread lifecycle helpers shared by the Tauri commands and the web handlers.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use codexia_shared::event_sink::EventSink;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::app_server::{CodexAppServer, ShutdownOutcome};
+use crate::connectivity::check_provider_connectivity;
+use crate::defaults::{
+    apply_defaults, get_default_config, normalize_approval_policy, reject_safety_overrides,
+};
+use crate::error::CodexiaError;
+use crate::reasoning::{reasoning_capabilities, strip_unsupported_reasoning};
+use crate::redact::redact_value;
+use crate::session_meta::{mark_session_ended, mark_session_started};
+use crate::turn::start_turn;
+
+/// Asked of the source thread by [`handoff_thread`].
+const HANDOFF_PROMPT: &str = "Summarize this conversation so a fresh session can continue the work: \
+the goal, decisions made, the current state of the code and the open next steps. \
+Reply with the summary only.";
+
+/// How long [`handoff_thread`] waits for the summary turn.
+const HANDOFF_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub fn session_started_event(thread_id: &str) -> String {
+    crate::utils::event_name("session-started", thread_id)
+}
+
+/// Emits `codex-session-started-<thread_id>` for the first `thread/started`
+/// of a thread, with its model (when the start response was already seen)
+/// and rollout path.
+pub(crate) fn observe_started(codex: &CodexAppServer, event_sink: &dyn EventSink, notification: &Value) {
+    if notification.get("method").and_then(Value::as_str) != Some("thread/started") {
+        return;
+    }
+    let Some(thread) = notification.get("params").and_then(|params| params.get("thread")) else {
+        return;
+    };
+    let Some(thread_id) = thread.get("id").and_then(Value::as_str) else {
+        return;
+    };
+    let Some(session) = codex.threads.mark_ready(thread_id) else {
+        return;
+    };
+    let rollout_path = thread
+        .get("path")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| session.rollout_path.map(|path| path.to_string_lossy().to_string()));
+    let model = session
+        .settings
+        .as_ref()
+        .and_then(|settings| settings.get("model"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    event_sink.emit(
+        &session_started_event(thread_id),
+        json!({ "threadId": thread_id, "model": model, "rolloutPath": rollout_path }),
+    );
+}
+
+fn response_thread_id(result: &Value) -> Option<&str> {
+    result.get("thread")?.get("id")?.as_str()
+}
+
+/// Fails with [`CodexiaError::WorkingDirMissing`] when `cwd` names a path
+/// that is not a directory, e.g. a project that was moved, instead of
+/// letting codex fail the start with a less helpful error. No or an empty
+/// `cwd` passes, leaving codex in its own working directory.
+pub(crate) fn check_cwd(params: &Value) -> Result<(), CodexiaError> {
+    let Some(cwd) = params.get("cwd").and_then(Value::as_str).map(str::trim) else {
+        return Ok(());
+    };
+    if cwd.is_empty() || std::path::Path::new(cwd).is_dir() {
+        return Ok(());
+    }
+    Err(CodexiaError::WorkingDirMissing { path: cwd.to_string() })
+}
+
+/// Rewrites `cwd` for a codex running under WSL.
+pub(crate) fn translate_cwd(codex: &CodexAppServer, params: &mut Value) {
+    if !codex.launch().is_wsl() {
+        return;
+    }
+    if let Some(Value::String(cwd)) = params.get_mut("cwd") {
+        *cwd = codex.launch().translate_path(cwd);
+    }
+}
+
+/// Remembers the effective settings from a start/resume/fork response so they
+/// can be read back later for the same thread.
+fn record_thread_response(codex: &CodexAppServer, result: &Value, config_overrides: Option<Value>) {
+    let Some(thread_id) = response_thread_id(result) else {
+        return;
+    };
+    let mut settings = result.clone();
+    if let Value::Object(map) = &mut settings {
+        map.remove("thread");
+    }
+    codex
+        .threads
+        .record_settings(thread_id, settings, config_overrides);
+    if let Some(path) = result
+        .get("thread")
+        .and_then(|thread| thread.get("path"))
+        .and_then(Value::as_str)
+    {
+        codex.threads.set_rollout_path(thread_id, std::path::PathBuf::from(path));
+    }
+}
+
+fn mark_started(codex: &CodexAppServer, result: &Value) {
+    let rollout = response_thread_id(result)
+        .and_then(|thread_id| codex.threads.get(thread_id))
+        .and_then(|session| session.rollout_path);
+    if let Some(rollout) = rollout {
+        mark_session_started(&rollout);
+    }
+}
+
+/// Prepends the configured system prompt to `developerInstructions`, which
+/// codex adds on top of the model's default instructions.
+fn apply_system_prompt(codex: &CodexAppServer, params: &mut Value) -> Result<(), String> {
+    let config = codex.config();
+    let Some(prompt) = config.system_prompt()? else {
+        return Ok(());
+    };
+    let Value::Object(map) = params else {
+        return Ok(());
+    };
+    let instructions = match map.get("developerInstructions").and_then(Value::as_str) {
+        Some(existing) if !existing.trim().is_empty() => format!("{}\n\n{}", prompt, existing),
+        _ => prompt.to_string(),
+    };
+    map.insert("developerInstructions".to_string(), Value::String(instructions));
+    Ok(())
+}
+
+/// Where in `root` the user is working: `None` for the root itself, the
+/// relative path otherwise. Fails when `cwd` is outside `root`.
+fn focused_subpath(root: &std::path::Path, cwd: &str) -> Result<Option<String>, String> {
+    let resolved = std::path::Path::new(cwd)
+        .canonicalize()
+        .map_err(|e| format!("Working directory {} is not accessible: {}", cwd, e))?;
+    // Compared in canonical form, which differs from `root` on Windows.
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let relative = resolved.strip_prefix(&canonical_root).map_err(|_| {
+        format!("Working directory {} is outside the repo root {}", cwd, root.display())
+    })?;
+    Ok((!relative.as_os_str().is_empty()).then(|| relative.to_string_lossy().replace('\\', "/")))
+}
+
+/// Runs the thread at the configured repo root, so codex can read and edit
+/// the whole repository, and tells the model which subdirectory the user
+/// opened through `developerInstructions`.
+fn apply_repo_root(codex: &CodexAppServer, params: &mut Value) -> Result<(), String> {
+    let Some(root) = codex.config().repo_root()? else {
+        return Ok(());
+    };
+    let Value::Object(map) = params else {
+        return Ok(());
+    };
+    let focus = match map.get("cwd").and_then(Value::as_str) {
+        Some(cwd) if !cwd.trim().is_empty() => focused_subpath(&root, cwd)?,
+        _ => None,
+    };
+    map.insert("cwd".to_string(), Value::String(root.to_string_lossy().to_string()));
+    if let Some(focus) = focus {
+        let note = format!(
+            "The user is working in `{}` within this repository. Concentrate on that directory \
+unless asked otherwise; the rest of the repository is available for context.",
+            focus
+        );
+        let instructions = match map.get("developerInstructions").and_then(Value::as_str) {
+            Some(existing) if !existing.trim().is_empty() => format!("{}\n\n{}", existing, note),
+            _ => note,
+        };
+        map.insert("developerInstructions".to_string(), Value::String(instructions));
+    }
+    Ok(())
+}
+
+/// In preview mode codex must ask before every patch: the sandbox is made
+/// read-only, so nothing is written without approval, and approvals go
+/// through `on-request` unless the thread is already `untrusted`.
+fn apply_preview_mode(codex: &CodexAppServer, params: &mut Value) {
+    if !codex.config().preview_mode {
+        return;
+    }
+    let Value::Object(map) = params else {
+        return;
+    };
+    map.insert("sandbox".to_string(), Value::String("read-only".to_string()));
+    if map.get("approvalPolicy").and_then(Value::as_str) != Some("untrusted") {
+        map.insert("approvalPolicy".to_string(), Value::String("on-request".to_string()));
+    }
+}
+
+/// Sends `thread/start`, filling unset fields from the stored defaults and
+/// dropping reasoning keys the selected model does not support. A
+/// connectivity probe for the selected provider runs alongside it; if the
+/// provider is unreachable the response gets a non-fatal
+/// `connectivityWarning` string, otherwise it is returned as is.
+pub async fn start_thread(codex: &CodexAppServer, mut params: Value) -> Result<Value, String> {
+    apply_defaults(&mut params, &get_default_config());
+    reject_safety_overrides(&params)?;
+    normalize_approval_policy(&mut params)?;
+    check_cwd(&params)?;
+    apply_preview_mode(codex, &mut params);
+    apply_repo_root(codex, &mut params)?;
+    apply_system_prompt(codex, &mut params)?;
+    translate_cwd(codex, &mut params);
+    strip_unsupported_reasoning(&reasoning_capabilities(&codex.config()), &mut params);
+    let provider = params
+        .get("modelProvider")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let config_overrides = params.get("config").filter(|c| !c.is_null()).cloned();
+    let developer_instructions = params
+        .get("developerInstructions")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let config = codex.config();
+    let (result, warning) = tokio::join!(
+        codex.send_request("thread/start", params),
+        check_provider_connectivity(provider.as_deref(), &config),
+    );
+
+    let mut result = result?;
+    record_thread_response(codex, &result, config_overrides);
+    if let Some(thread_id) = response_thread_id(&result) {
+        codex.threads.expect_start(thread_id);
+    }
+    if let (Some(thread_id), Some(instructions)) = (response_thread_id(&result), developer_instructions) {
+        codex.threads.set_developer_instructions(thread_id, instructions);
+    }
+    mark_started(codex, &result);
+    if let (Some(warning), Value::Object(map)) = (warning, &mut result) {
+        map.insert("connectivityWarning".to_string(), Value::String(warning));
+    }
+    Ok(result)
+}
+
+/// [`start_thread`] that [`cancel_thread_start`] can abort by `start_id`.
+///
+/// The app-server still finishes a cancelled start, so the thread it creates
+/// is unsubscribed and forgotten as soon as the response arrives.
+pub async fn start_thread_cancellable(
+    codex: &Arc<CodexAppServer>,
+    params: Value,
+    start_id: Option<String>,
+) -> Result<Value, String> {
+    let Some(start_id) = start_id else {
+        return start_thread(codex, params).await;
+    };
+    let cancelled = codex.starts.register(&start_id);
+    let task_codex = Arc::clone(codex);
+    let mut task = tokio::spawn(async move { start_thread(&task_codex, params).await });
+
+    tokio::select! {
+        result = &mut task => {
+            codex.starts.finish(&start_id);
+            result.map_err(|e| e.to_string())?
+        }
+        _ = cancelled => {
+            log::info!("thread start {} cancelled", start_id);
+            let codex = Arc::clone(codex);
+            tokio::spawn(async move {
+                let Ok(Ok(result)) = task.await else {
+                    return;
+                };
+                let Some(thread_id) = response_thread_id(&result) else {
+                    return;
+                };
+                forget_thread(&codex, thread_id);
+                if let Err(err) = codex
+                    .send_request("thread/unsubscribe", json!({ "threadId": thread_id }))
+                    .await
+                {
+                    log::warn!("Failed to unsubscribe cancelled thread {}: {}", thread_id, err);
+                }
+            });
+            Err(format!("Thread start {} was cancelled", start_id))
+        }
+    }
+}
+
+/// Drops everything kept locally for a thread we stopped listening to, as if
+/// the app-server had reported it closed.
+fn forget_thread(codex: &CodexAppServer, thread_id: &str) {
+    let closed = json!({ "method": "thread/closed", "params": { "threadId": thread_id } });
+    codex.threads.observe(&closed);
+    codex.recent.observe(&closed);
+    codex.tees.observe(&closed);
+    codex.patches.observe(&closed, &codex.threads);
+    codex.approvals.forget_history(thread_id);
+}
+
+/// Interrupts the thread's turn, waits until codex reports it stopped so the
+/// rollout is flushed, then unsubscribes from the thread. The wait is bounded
+/// by the shutdown timeout, after which the thread is unsubscribed anyway.
+pub async fn close_thread(codex: &CodexAppServer, thread_id: &str) -> Result<(), String> {
+    if let Some((turn_id, done)) = codex.threads.wait_for_turn_end(thread_id) {
+        codex
+            .send_priority_request(
+                "turn/interrupt",
+                json!({ "threadId": thread_id, "turnId": turn_id }),
+            )
+            .await?;
+        let timeout = codex.config().shutdown_timeout();
+        if tokio::time::timeout(timeout, done).await.is_err() {
+            log::warn!("Turn {} of thread {} did not stop within {:?}, unsubscribing anyway", turn_id, thread_id, timeout);
+        }
+    }
+    codex
+        .send_request("thread/unsubscribe", json!({ "threadId": thread_id }))
+        .await?;
+    if let Some(rollout) = codex.threads.get(thread_id).and_then(|s| s.rollout_path) {
+        mark_session_ended(&rollout);
+    }
+    forget_thread(codex, thread_id);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseResult {
+    pub thread_id: String,
+    pub closed: bool,
+    pub error: Option<String>,
+}
+
+/// Closes every open thread concurrently, each bounded by the shutdown
+/// timeout. The app-server keeps running.
+pub async fn close_all_threads(codex: &CodexAppServer) -> Vec<CloseResult> {
+    let thread_ids: Vec<String> = codex
+        .threads
+        .list()
+        .into_iter()
+        .map(|session| session.thread_id)
+        .collect();
+    let timeout = codex.config().shutdown_timeout();
+    let closes = thread_ids.into_iter().map(|thread_id| async move {
+        let result = tokio::time::timeout(timeout, close_thread(codex, &thread_id))
+            .await
+            .unwrap_or_else(|_| Err(format!("Timed out after {:?}", timeout)));
+        if let Err(err) = &result {
+            log::warn!("Failed to close thread {}: {}", thread_id, err);
+        }
+        CloseResult {
+            thread_id,
+            closed: result.is_ok(),
+            error: result.err(),
+        }
+    });
+    futures::future::join_all(closes).await
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShutdownSummary {
+    /// Threads closed cleanly.
+    pub closed: usize,
+    /// Threads that could not be closed; they are forgotten all the same.
+    pub failed: Vec<CloseResult>,
+    pub outcome: ShutdownOutcome,
+}
+
+fn shutdown_summary(results: Vec<CloseResult>, outcome: ShutdownOutcome) -> ShutdownSummary {
+    let (closed, failed): (Vec<_>, Vec<_>) = results.into_iter().partition(|r| r.closed);
+    ShutdownSummary {
+        closed: closed.len(),
+        failed,
+        outcome,
+    }
+}
+
+/// Closes every open thread concurrently, then stops the app-server, as on
+/// exit. Threads that fail to close are dropped from the registry anyway, so
+/// nothing is left pointing at the stopped process.
+pub async fn shutdown_all_sessions(codex: &CodexAppServer) -> ShutdownSummary {
+    let results = close_all_threads(codex).await;
+    for result in results.iter().filter(|r| !r.closed) {
+        forget_thread(codex, &result.thread_id);
+    }
+    let report = codex.shutdown().await;
+    let summary = shutdown_summary(results, report.outcome);
+    log::info!(
+        "Shut down codex: {} sessions closed, {} failed, {:?}",
+        summary.closed,
+        summary.failed.len(),
+        summary.outcome
+    );
+    summary
+}
+
+/// Cancels a pending [`start_thread_cancellable`]. Returns `false` when the
+/// start already finished or was never registered.
+pub fn cancel_thread_start(codex: &CodexAppServer, start_id: &str) -> bool {
+    codex.starts.cancel(start_id)
+}
+
+/// Fails with [`CodexiaError::SessionNotFound`] unless the app-server knows
+/// `thread_id`, e.g. because it was closed or the app-server restarted.
+pub fn require_thread(codex: &CodexAppServer, thread_id: &str) -> Result<(), CodexiaError> {
+    match codex.threads.get(thread_id) {
+        Some(_) => Ok(()),
+        None => Err(CodexiaError::SessionNotFound {
+            session_id: thread_id.to_string(),
+        }),
+    }
+}
+
+/// Notifications returned by [`get_recent_events`] when no limit is given.
+const DEFAULT_RECENT_EVENTS: usize = 200;
+
+/// The last `limit` notifications of `thread_id`, oldest first, so a newly
+/// attached window can backfill before listening for live events.
+pub fn get_recent_events(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    limit: Option<usize>,
+) -> Result<Vec<Value>, CodexiaError> {
+    require_thread(codex, thread_id)?;
+    Ok(codex
+        .recent
+        .recent(thread_id, limit.unwrap_or(DEFAULT_RECENT_EVENTS)))
+}
+
+/// The agent message being written in `thread_id`'s current turn, or the
+/// last completed one, so a reloaded window can restore half-written text.
+pub fn get_partial_message(
+    codex: &CodexAppServer,
+    thread_id: &str,
+) -> Result<Option<crate::threads::PartialMessage>, CodexiaError> {
+    let session = codex.threads.get(thread_id).ok_or_else(|| CodexiaError::SessionNotFound {
+        session_id: thread_id.to_string(),
+    })?;
+    Ok(session.partial_message)
+}
+
+/// The app-server command line serving `thread_id`. Every thread shares the
+/// one app-server process, so this is the same for all of them.
+pub fn get_session_launch_args(
+    codex: &CodexAppServer,
+    thread_id: &str,
+) -> Result<crate::launch_args::LaunchArgs, CodexiaError> {
+    require_thread(codex, thread_id)?;
+    Ok(codex.launch_args().clone())
+}
+
+/// Whether `requested` names the directory codex reports as `current`.
+/// `translated` is `requested` as the codex process sees it (a `/mnt` path
+/// under WSL).
+fn is_current_dir(current: Option<&std::path::Path>, requested: &std::path::Path, translated: &std::path::Path) -> bool {
+    let Some(current) = current else {
+        return false;
+    };
+    if current == translated {
+        return true;
+    }
+    matches!((current.canonicalize(), requested.canonicalize()), (Ok(a), Ok(b)) if a == b)
+}
+
+/// A thread's working directory is fixed when the app-server starts it, so
+/// it cannot move while the thread runs. Succeeds only when `path` already is
+/// the working directory; otherwise fails with
+/// [`CodexiaError::WorkingDirFixed`], and the thread can be resumed with
+/// `path` as `cwd` through [`resume_thread`] instead.
+pub fn set_session_working_directory(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    path: &str,
+) -> Result<(), CodexiaError> {
+    require_thread(codex, thread_id)?;
+    let path = path.trim();
+    let requested = std::path::Path::new(path);
+    if !requested.is_dir() {
+        return Err(CodexiaError::WorkingDirMissing { path: path.to_string() });
+    }
+    let translated = std::path::PathBuf::from(codex.launch().translate_path(path));
+    if is_current_dir(codex.threads.cwd(thread_id).as_deref(), requested, &translated) {
+        return Ok(());
+    }
+    Err(CodexiaError::WorkingDirFixed {
+        session_id: thread_id.to_string(),
+        path: path.to_string(),
+    })
+}
+
+/// Sends `thread/resume` and registers the resumed thread.
+pub async fn resume_thread(codex: &CodexAppServer, mut params: Value) -> Result<Value, String> {
+    reject_safety_overrides(&params)?;
+    normalize_approval_policy(&mut params)?;
+    check_cwd(&params)?;
+    apply_preview_mode(codex, &mut params);
+    translate_cwd(codex, &mut params);
+    let result = codex.send_request("thread/resume", params).await?;
+    record_thread_response(codex, &result, None);
+    Ok(result)
+}
+
+/// Resumes a thread from an earlier run of the app by its session id. The
+/// rollout is located first, so a deleted or moved session fails with
+/// [`CodexiaError::SessionNotFound`] instead of an opaque codex error.
+pub async fn resume_session(codex: &CodexAppServer, session_id: &str) -> Result<Value, CodexiaError> {
+    let lookup_id = session_id.to_string();
+    let rollout = tokio::task::spawn_blocking(move || crate::sessions::find_rollout_path_for_session(&lookup_id))
+        .await
+        .map_err(|e| e.to_string())??;
+    let path = codex.launch().translate_path(&rollout.to_string_lossy());
+    let params = json!({ "threadId": session_id, "path": path });
+    Ok(resume_thread(codex, params).await?)
+}
+
+/// Sends `thread/fork` and registers the new thread.
+pub async fn fork_thread(codex: &CodexAppServer, mut params: Value) -> Result<Value, String> {
+    reject_safety_overrides(&params)?;
+    normalize_approval_policy(&mut params)?;
+    check_cwd(&params)?;
+    apply_preview_mode(codex, &mut params);
+    translate_cwd(codex, &mut params);
+    let result = codex.send_request("thread/fork", params).await?;
+    record_thread_response(codex, &result, None);
+    mark_started(codex, &result);
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadPing {
+    /// The app-server process is running and knows about this thread.
+    pub alive: bool,
+    /// A turn is in flight, so silence may just mean the model is thinking.
+    pub busy: bool,
+    /// Milliseconds since the last notification for this thread.
+    pub last_event_age_ms: Option<u64>,
+}
+
+/// Reports whether a thread is alive and how long it has been silent, which
+/// tells a hung process apart from one that is busy thinking.
+pub async fn ping_thread(codex: &CodexAppServer, thread_id: &str) -> ThreadPing {
+    let running = codex.is_running().await;
+    match codex.threads.get(thread_id) {
+        Some(session) => ThreadPing {
+            alive: running,
+            busy: session.active_turn_id.is_some(),
+            last_event_age_ms: session
+                .last_event_at
+                .map(|at| at.elapsed().as_millis() as u64),
+        },
+        None => ThreadPing {
+            alive: false,
+            busy: false,
+            last_event_age_ms: None,
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionStatus {
+    /// The app-server is running and knows about this session.
+    pub active: bool,
+    /// Process id of the app-server running the session.
+    pub pid: Option<u32>,
+    /// Seconds since the app-server was spawned.
+    pub uptime_secs: Option<u64>,
+}
+
+/// Whether a session is live and which process serves it, so the UI can
+/// show how long it has been running and spot sessions whose process died.
+/// An unknown session is reported inactive rather than as an error.
+pub async fn session_status(codex: &CodexAppServer, thread_id: &str) -> SessionStatus {
+    if codex.threads.get(thread_id).is_none() || !codex.is_running().await {
+        return SessionStatus {
+            active: false,
+            pid: None,
+            uptime_secs: None,
+        };
+    }
+    SessionStatus {
+        active: true,
+        pid: codex.pid(),
+        uptime_secs: Some(codex.uptime().as_secs()),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Handoff {
+    /// The thread that continues the work.
+    pub thread_id: String,
+    /// Summary the source thread produced and the new one was seeded with.
+    pub summary: String,
+}
+
+/// Text of the agent message that completed `turn_id`, searching the newest
+/// notifications first.
+fn final_agent_message(events: &[Value], turn_id: &str) -> Option<String> {
+    events.iter().rev().find_map(|event| {
+        let params = event.get("params")?;
+        if event.get("method")?.as_str()? != "item/completed"
+            || params.get("turnId")?.as_str()? != turn_id
+        {
+            return None;
+        }
+        let item = params.get("item")?;
+        if item.get("type")?.as_str()? != "agentMessage" {
+            return None;
+        }
+        let text = item.get("text")?.as_str()?.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    })
+}
+
+/// Runs the handoff turn on `source_thread_id` and returns its final agent
+/// message. `done` is consumed so a failed start leaves no live waiter.
+async fn request_summary(
+    codex: &CodexAppServer,
+    source_thread_id: &str,
+    done: tokio::sync::oneshot::Receiver<()>,
+) -> Result<String, CodexiaError> {
+    let response = start_turn(
+        codex,
+        json!({
+            "threadId": source_thread_id,
+            "input": [{ "type": "text", "text": HANDOFF_PROMPT, "text_elements": [] }],
+        }),
+    )
+    .await?;
+    let turn_id = response
+        .get("turn")
+        .and_then(|turn| turn.get("id"))
+        .and_then(Value::as_str)
+        .ok_or("turn/start returned no turn id")?;
+    tokio::time::timeout(HANDOFF_TIMEOUT, done)
+        .await
+        .map_err(|_| "Timed out waiting for the session summary")?
+        .map_err(|_| "Session closed before the summary was ready")?;
+    Ok(final_agent_message(&codex.recent.recent(source_thread_id, usize::MAX), turn_id)
+        .ok_or("The session did not produce a summary")?)
+}
+
+/// Continues `source_thread_id` in a fresh thread: the source is asked for a
+/// summary, a thread is started with `params` and the summary becomes its
+/// first message. The source thread is left open.
+pub async fn handoff_thread(
+    codex: &CodexAppServer,
+    source_thread_id: &str,
+    params: Value,
+) -> Result<Handoff, CodexiaError> {
+    let source = codex.threads.get(source_thread_id).ok_or_else(|| CodexiaError::SessionNotFound {
+        session_id: source_thread_id.to_string(),
+    })?;
+    if source.active_turn_id.is_some() {
+        return Err(format!("Session {} has a turn in progress", source_thread_id).into());
+    }
+
+    let done = codex.threads.next_turn_end(source_thread_id);
+    let summary = request_summary(codex, source_thread_id, done).await;
+    codex.threads.drop_abandoned_waiters(source_thread_id);
+    let summary = summary?;
+
+    let started = start_thread(codex, params).await?;
+    let thread_id = response_thread_id(&started)
+        .ok_or("thread/start returned no thread id")?
+        .to_string();
+    let seed = format!(
+        "Continuing from a previous session. Its summary:\n\n{}",
+        summary
+    );
+    start_turn(
+        codex,
+        json!({
+            "threadId": thread_id,
+            "input": [{ "type": "text", "text": seed, "text_elements": [] }],
+        }),
+    )
+    .await?;
+    log::info!("Handed off {} to {}", source_thread_id, thread_id);
+    Ok(Handoff { thread_id, summary })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadConfig {
+    pub thread_id: String,
+    /// Effective settings reported by the app-server (model, modelProvider,
+    /// cwd, approvalPolicy, sandbox, ...).
+    pub settings: Option<Value>,
+    /// `config` overrides passed to `thread/start`.
+    pub config_overrides: Option<Value>,
+    /// Developer instructions passed to `thread/start`, including the
+    /// configured system prompt.
+    pub developer_instructions: Option<String>,
+}
+
+/// Returns the settings a running thread was started with, secrets redacted.
+pub fn get_thread_config(codex: &CodexAppServer, thread_id: &str) -> Result<ThreadConfig, CodexiaError> {
+    let session = codex.threads.get(thread_id).ok_or_else(|| CodexiaError::SessionNotFound {
+        session_id: thread_id.to_string(),
+    })?;
+
+    let mut settings = session.settings;
+    let mut config_overrides = session.config_overrides;
+    settings.iter_mut().for_each(redact_value);
+    config_overrides.iter_mut().for_each(redact_value);
+
+    Ok(ThreadConfig {
+        thread_id: session.thread_id,
+        settings,
+        config_overrides,
+        developer_instructions: session.developer_instructions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_closed_and_failed_sessions() {
+        let result = |thread_id: &str, error: Option<&str>| CloseResult {
+            thread_id: thread_id.to_string(),
+            closed: error.is_none(),
+            error: error.map(str::to_string),
+        };
+        let summary = shutdown_summary(
+            vec![result("t1", None), result("t2", Some("Timed out after 5s")), result("t3", None)],
+            ShutdownOutcome::Graceful,
+        );
+        assert_eq!(summary.closed, 2);
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].thread_id, "t2");
+        assert_eq!(serde_json::to_value(&summary).unwrap()["outcome"], "graceful");
+    }
+
+    #[test]
+    fn focuses_on_subpath_inside_repo_root() {
+        let base = std::env::temp_dir().join(format!("codexia-repo-{}", uuid::Uuid::new_v4()));
+        let root = base.join("mono");
+        let package = root.join("packages").join("web");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::create_dir_all(base.join("other")).unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let cwd = |path: std::path::PathBuf| path.to_string_lossy().to_string();
+        assert_eq!(focused_subpath(&root, &cwd(package)).unwrap().as_deref(), Some("packages/web"));
+        assert_eq!(focused_subpath(&root, &cwd(root.clone())).unwrap(), None);
+        assert!(focused_subpath(&root, &cwd(base.join("other"))).unwrap_err().contains("outside"));
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn recognizes_the_current_working_directory() {
+        let base = std::env::temp_dir().join(format!("codexia-cwd-{}", uuid::Uuid::new_v4()));
+        let repo = base.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::create_dir_all(base.join("other")).unwrap();
+
+        assert!(is_current_dir(Some(&repo), &repo.join("."), &repo.join(".")));
+        assert!(is_current_dir(Some(std::path::Path::new("/mnt/c/repo")), &repo, std::path::Path::new("/mnt/c/repo")));
+        assert!(!is_current_dir(Some(&repo), &base.join("other"), &base.join("other")));
+        assert!(!is_current_dir(None, &repo, &repo));
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn rejects_missing_working_directory() {
+        let missing = std::env::temp_dir().join(format!("codexia-gone-{}", uuid::Uuid::new_v4()));
+        let missing = missing.to_string_lossy().to_string();
+        assert_eq!(
+            check_cwd(&json!({ "cwd": missing })),
+            Err(CodexiaError::WorkingDirMissing { path: missing.clone() })
+        );
+        let tmp = std::env::temp_dir().to_string_lossy().to_string();
+        assert!(check_cwd(&json!({ "cwd": tmp })).is_ok());
+        assert!(check_cwd(&json!({ "cwd": "" })).is_ok());
+        assert!(check_cwd(&json!({ "model": "gpt-5" })).is_ok());
+    }
+
+    #[test]
+    fn picks_final_agent_message_of_turn() {
+        let completed = |turn: &str, item: Value| {
+            json!({ "method": "item/completed", "params": { "threadId": "t1", "turnId": turn, "item": item } })
+        };
+        let events = vec![
+            completed("u1", json!({ "type": "agentMessage", "id": "a", "text": "earlier" })),
+            completed("u2", json!({ "type": "agentMessage", "id": "b", "text": "draft" })),
+            completed("u2", json!({ "type": "commandExecution", "id": "c", "command": "ls" })),
+            completed("u2", json!({ "type": "agentMessage", "id": "d", "text": " summary " })),
+        ];
+        assert_eq!(final_agent_message(&events, "u2").as_deref(), Some("summary"));
+        assert!(final_agent_message(&events, "u3").is_none());
+    }
+}
@@ -0,0 +1,490 @@
+//! Per-thread bookkeeping derived from app-server notifications.
+//!
+//! A single `codex app-server` process hosts every thread, so the stdout
+//! reader feeds each notification through [`ThreadRegistry::observe`] to keep
+//! track of which threads exist and which of them have a turn in flight.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ThreadSession {
+    pub thread_id: String,
+    pub active_turn_id: Option<String>,
+    /// When the last notification for this thread arrived.
+    #[serde(skip)]
+    pub last_event_at: Option<Instant>,
+    /// When the active turn started.
+    #[serde(skip)]
+    pub turn_started_at: Option<Instant>,
+    /// Effective settings (model, provider, cwd, approval, sandbox, ...) as
+    /// last reported by the app-server.
+    #[serde(skip)]
+    pub settings: Option<Value>,
+    /// `config` overrides the thread was started with.
+    #[serde(skip)]
+    pub config_overrides: Option<Value>,
+    /// Developer instructions the thread was started with, including the
+    /// configured system prompt.
+    #[serde(skip)]
+    pub developer_instructions: Option<String>,
+    /// Rollout file the app-server reported for the thread.
+    #[serde(skip)]
+    pub rollout_path: Option<std::path::PathBuf>,
+    /// The latest agent message of the current turn, accumulated from deltas.
+    #[serde(skip)]
+    pub partial_message: Option<PartialMessage>,
+    /// Model to switch back to on the next turn, after a one-turn override.
+    #[serde(skip)]
+    pub restore_model: Option<String>,
+    /// `turn/start` fields the next turn sends, from
+    /// [`crate::turn::update_session_config`].
+    #[serde(skip)]
+    pub pending_overrides: serde_json::Map<String, Value>,
+    /// `thread/started` arrived, so codex is ready for turns.
+    #[serde(skip)]
+    pub ready: bool,
+    /// Started by this client before `thread/started` arrived; turns wait.
+    #[serde(skip)]
+    pub(crate) awaiting_start: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PartialMessage {
+    pub item_id: String,
+    pub turn_id: Option<String>,
+    pub text: String,
+    /// The message is still being written; `false` once its item completed.
+    pub streaming: bool,
+}
+
+impl ThreadSession {
+    fn new(thread_id: &str) -> Self {
+        Self {
+            thread_id: thread_id.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ThreadRegistry {
+    threads: Mutex<HashMap<String, ThreadSession>>,
+    /// Notified when the thread's active turn completes.
+    turn_waiters: Mutex<HashMap<String, Vec<tokio::sync::oneshot::Sender<()>>>>,
+    /// Notified when `thread/started` arrives for a thread being started.
+    ready_waiters: Mutex<HashMap<String, Vec<tokio::sync::oneshot::Sender<()>>>>,
+}
+
+fn notification_thread_id(params: &Value) -> Option<&str> {
+    params
+        .get("threadId")
+        .and_then(Value::as_str)
+        .or_else(|| params.get("thread")?.get("id")?.as_str())
+}
+
+impl ThreadRegistry {
+    /// Updates the registry from a serialized `ServerNotification`
+    /// (`{ "method": ..., "params": ... }`).
+    pub fn observe(&self, notification: &Value) {
+        let Some(method) = notification.get("method").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(params) = notification.get("params") else {
+            return;
+        };
+        let Some(thread_id) = notification_thread_id(params) else {
+            return;
+        };
+
+        // Locked before `turn_waiters`, as in `wait_for_turn_end`, and held
+        // until the active turn is cleared, so a waiter is either drained
+        // here or sees no active turn.
+        let mut threads = self.threads.lock().unwrap();
+        if matches!(method, "turn/completed" | "thread/closed") {
+            if let Some(waiters) = self.turn_waiters.lock().unwrap().remove(thread_id) {
+                for waiter in waiters {
+                    let _ = waiter.send(());
+                }
+            }
+        }
+
+        if method == "thread/closed" {
+            threads.remove(thread_id);
+            // Dropped senders release turns still waiting for the start.
+            self.ready_waiters.lock().unwrap().remove(thread_id);
+            return;
+        }
+
+        let session = threads
+            .entry(thread_id.to_string())
+            .or_insert_with(|| ThreadSession::new(thread_id));
+        session.last_event_at = Some(Instant::now());
+        match method {
+            "turn/started" => {
+                session.active_turn_id = params
+                    .get("turn")
+                    .and_then(|turn| turn.get("id"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                session.turn_started_at = Some(Instant::now());
+                session.partial_message = None;
+            }
+            "turn/completed" => {
+                session.active_turn_id = None;
+                session.turn_started_at = None;
+                if let Some(partial) = &mut session.partial_message {
+                    partial.streaming = false;
+                }
+            }
+            "item/started" | "item/completed" => {
+                let Some(item) = params
+                    .get("item")
+                    .filter(|item| item.get("type").and_then(Value::as_str) == Some("agentMessage"))
+                else {
+                    return;
+                };
+                let item_id = item.get("id").and_then(Value::as_str).unwrap_or_default();
+                let text = item.get("text").and_then(Value::as_str).unwrap_or_default();
+                session.partial_message = Some(PartialMessage {
+                    item_id: item_id.to_string(),
+                    turn_id: params.get("turnId").and_then(Value::as_str).map(str::to_string),
+                    text: text.to_string(),
+                    streaming: method == "item/started",
+                });
+            }
+            "item/agentMessage/delta" => {
+                let item_id = params.get("itemId").and_then(Value::as_str).unwrap_or_default();
+                let delta = params.get("delta").and_then(Value::as_str).unwrap_or_default();
+                match &mut session.partial_message {
+                    Some(partial) if partial.item_id == item_id => partial.text.push_str(delta),
+                    _ => {
+                        session.partial_message = Some(PartialMessage {
+                            item_id: item_id.to_string(),
+                            turn_id: params.get("turnId").and_then(Value::as_str).map(str::to_string),
+                            text: delta.to_string(),
+                            streaming: true,
+                        });
+                    }
+                }
+            }
+            "thread/settings/updated" => {
+                if let Some(settings) = params.get("threadSettings") {
+                    session.settings = Some(settings.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Registers a thread the client started, resumed or forked, before any
+    /// notification for it has arrived.
+    pub fn record_settings(&self, thread_id: &str, settings: Value, config_overrides: Option<Value>) {
+        let mut threads = self.threads.lock().unwrap();
+        let session = threads
+            .entry(thread_id.to_string())
+            .or_insert_with(|| ThreadSession::new(thread_id));
+        session.settings = Some(settings);
+        if config_overrides.is_some() {
+            session.config_overrides = config_overrides;
+        }
+    }
+
+    /// Marks the thread ready and releases turns waiting for it. Returns the
+    /// session only the first time, so the start is announced once.
+    pub(crate) fn mark_ready(&self, thread_id: &str) -> Option<ThreadSession> {
+        let mut threads = self.threads.lock().unwrap();
+        let session = threads
+            .entry(thread_id.to_string())
+            .or_insert_with(|| ThreadSession::new(thread_id));
+        if session.ready {
+            return None;
+        }
+        session.ready = true;
+        session.awaiting_start = false;
+        for waiter in self.ready_waiters.lock().unwrap().remove(thread_id).unwrap_or_default() {
+            let _ = waiter.send(());
+        }
+        Some(session.clone())
+    }
+
+    /// Makes turns on a thread this client just started wait for its
+    /// `thread/started`, unless that already arrived.
+    pub(crate) fn expect_start(&self, thread_id: &str) {
+        if let Some(session) = self.threads.lock().unwrap().get_mut(thread_id) {
+            session.awaiting_start = !session.ready;
+        }
+    }
+
+    /// A receiver firing once `thread/started` arrives, or `None` when the
+    /// thread is not waiting for it.
+    pub(crate) fn wait_until_ready(&self, thread_id: &str) -> Option<tokio::sync::oneshot::Receiver<()>> {
+        // Held across the registration so `mark_ready` cannot slip in between.
+        let threads = self.threads.lock().unwrap();
+        if !threads.get(thread_id)?.awaiting_start {
+            return None;
+        }
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.ready_waiters
+            .lock()
+            .unwrap()
+            .entry(thread_id.to_string())
+            .or_default()
+            .push(tx);
+        Some(rx)
+    }
+
+    pub fn set_developer_instructions(&self, thread_id: &str, instructions: String) {
+        if let Some(session) = self.threads.lock().unwrap().get_mut(thread_id) {
+            session.developer_instructions = Some(instructions);
+        }
+    }
+
+    pub fn set_rollout_path(&self, thread_id: &str, path: std::path::PathBuf) {
+        if let Some(session) = self.threads.lock().unwrap().get_mut(thread_id) {
+            session.rollout_path = Some(path);
+        }
+    }
+
+    /// Remembers `model` as the one to return to after a one-turn override.
+    /// An already pending model is kept, so repeated overrides still return
+    /// to the thread's own model.
+    pub(crate) fn set_restore_model(&self, thread_id: &str, model: String) {
+        if let Some(session) = self.threads.lock().unwrap().get_mut(thread_id) {
+            session.restore_model.get_or_insert(model);
+        }
+    }
+
+    /// Adds `overrides` to those of the next turn, replacing earlier values,
+    /// and returns them all. A model set here replaces a pending restore.
+    pub(crate) fn merge_pending_overrides(
+        &self,
+        thread_id: &str,
+        overrides: serde_json::Map<String, Value>,
+    ) -> Option<serde_json::Map<String, Value>> {
+        let mut threads = self.threads.lock().unwrap();
+        let session = threads.get_mut(thread_id)?;
+        if overrides.contains_key("model") {
+            session.restore_model = None;
+        }
+        session.pending_overrides.extend(overrides);
+        Some(session.pending_overrides.clone())
+    }
+
+    pub(crate) fn take_pending_overrides(&self, thread_id: &str) -> serde_json::Map<String, Value> {
+        self.threads
+            .lock()
+            .unwrap()
+            .get_mut(thread_id)
+            .map(|session| std::mem::take(&mut session.pending_overrides))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn take_restore_model(&self, thread_id: &str) -> Option<String> {
+        self.threads.lock().unwrap().get_mut(thread_id)?.restore_model.take()
+    }
+
+    /// Working directory last reported for the thread.
+    pub fn cwd(&self, thread_id: &str) -> Option<std::path::PathBuf> {
+        let threads = self.threads.lock().unwrap();
+        let cwd = threads.get(thread_id)?.settings.as_ref()?.get("cwd")?.as_str()?;
+        Some(std::path::PathBuf::from(cwd))
+    }
+
+    pub fn get(&self, thread_id: &str) -> Option<ThreadSession> {
+        self.threads.lock().unwrap().get(thread_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<ThreadSession> {
+        self.threads.lock().unwrap().values().cloned().collect()
+    }
+
+    pub fn remove(&self, thread_id: &str) -> Option<ThreadSession> {
+        self.threads.lock().unwrap().remove(thread_id)
+    }
+
+    /// The active turn of `thread_id` and a receiver firing once it
+    /// completes, or `None` when no turn is in flight.
+    pub(crate) fn wait_for_turn_end(
+        &self,
+        thread_id: &str,
+    ) -> Option<(String, tokio::sync::oneshot::Receiver<()>)> {
+        // Held across the registration so a `turn/completed` cannot slip in
+        // between reading the active turn and subscribing to its end.
+        let threads = self.threads.lock().unwrap();
+        let turn_id = threads.get(thread_id)?.active_turn_id.clone()?;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.turn_waiters
+            .lock()
+            .unwrap()
+            .entry(thread_id.to_string())
+            .or_default()
+            .push(tx);
+        Some((turn_id, rx))
+    }
+
+    /// A receiver firing when the next turn of `thread_id` completes,
+    /// whether or not it has started yet.
+    pub(crate) fn next_turn_end(&self, thread_id: &str) -> tokio::sync::oneshot::Receiver<()> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.turn_waiters
+            .lock()
+            .unwrap()
+            .entry(thread_id.to_string())
+            .or_default()
+            .push(tx);
+        rx
+    }
+
+    /// Drops waiters of `thread_id` whose receiver is gone, such as one
+    /// taken for a turn that then failed to start.
+    pub(crate) fn drop_abandoned_waiters(&self, thread_id: &str) {
+        let mut waiters = self.turn_waiters.lock().unwrap();
+        if let Some(list) = waiters.get_mut(thread_id) {
+            list.retain(|waiter| !waiter.is_closed());
+            if list.is_empty() {
+                waiters.remove(thread_id);
+            }
+        }
+    }
+
+    /// `(thread_id, turn_id)` for every thread with a turn in flight.
+    pub fn active_turns(&self) -> Vec<(String, String)> {
+        self.threads
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|s| Some((s.thread_id.clone(), s.active_turn_id.clone()?)))
+            .collect()
+    }
+}
+
+/// `thread/start` calls that can still be cancelled, keyed by a
+/// caller-chosen start id since the thread id is not known yet.
+#[derive(Default)]
+pub struct PendingStarts {
+    starts: Mutex<HashMap<String, tokio::sync::oneshot::Sender<()>>>,
+}
+
+impl PendingStarts {
+    pub(crate) fn register(&self, start_id: &str) -> tokio::sync::oneshot::Receiver<()> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.starts.lock().unwrap().insert(start_id.to_string(), tx);
+        rx
+    }
+
+    pub(crate) fn finish(&self, start_id: &str) {
+        self.starts.lock().unwrap().remove(start_id);
+    }
+
+    /// Cancels the start `start_id`. Returns `false` if it already finished.
+    pub fn cancel(&self, start_id: &str) -> bool {
+        match self.starts.lock().unwrap().remove(start_id) {
+            Some(tx) => tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn restores_the_original_model_once() {
+        let registry = ThreadRegistry::default();
+        registry.record_settings("t1", json!({ "model": "gpt-5-mini" }), None);
+        registry.set_restore_model("t1", "gpt-5-mini".to_string());
+        registry.set_restore_model("t1", "gpt-5".to_string());
+        assert_eq!(registry.take_restore_model("t1").as_deref(), Some("gpt-5-mini"));
+        assert_eq!(registry.take_restore_model("t1"), None);
+    }
+
+    #[test]
+    fn turns_wait_for_thread_started_once() {
+        let registry = ThreadRegistry::default();
+        registry.record_settings("t1", json!({ "model": "gpt-5" }), None);
+        assert!(registry.wait_until_ready("t1").is_none());
+
+        registry.expect_start("t1");
+        let mut ready = registry.wait_until_ready("t1").unwrap();
+        assert!(ready.try_recv().is_err());
+        assert_eq!(registry.mark_ready("t1").unwrap().settings.unwrap()["model"], "gpt-5");
+        assert!(ready.try_recv().is_ok());
+        assert!(registry.mark_ready("t1").is_none());
+        assert!(registry.wait_until_ready("t1").is_none());
+
+        // A start reported before the response leaves nothing to wait for.
+        registry.mark_ready("t2");
+        registry.record_settings("t2", json!({}), None);
+        registry.expect_start("t2");
+        assert!(registry.wait_until_ready("t2").is_none());
+    }
+
+    #[test]
+    fn tracks_turn_lifecycle() {
+        let registry = ThreadRegistry::default();
+        registry.observe(&json!({ "method": "thread/started", "params": { "thread": { "id": "t1" } } }));
+        registry.observe(&json!({ "method": "turn/started", "params": { "threadId": "t1", "turn": { "id": "turn-1" } } }));
+        assert_eq!(registry.active_turns(), vec![("t1".to_string(), "turn-1".to_string())]);
+
+        registry.observe(&json!({ "method": "turn/completed", "params": { "threadId": "t1", "turn": { "id": "turn-1" } } }));
+        assert!(registry.active_turns().is_empty());
+
+        registry.observe(&json!({ "method": "thread/closed", "params": { "threadId": "t1" } }));
+        assert!(registry.get("t1").is_none());
+    }
+
+    #[test]
+    fn accumulates_streaming_agent_message() {
+        let registry = ThreadRegistry::default();
+        let item = json!({ "type": "agentMessage", "id": "m1", "text": "" });
+        registry.observe(&json!({ "method": "item/started", "params": { "threadId": "t1", "turnId": "u1", "item": item } }));
+        for delta in ["Hel", "lo"] {
+            registry.observe(&json!({ "method": "item/agentMessage/delta", "params": { "threadId": "t1", "turnId": "u1", "itemId": "m1", "delta": delta } }));
+        }
+        let partial = registry.get("t1").unwrap().partial_message.unwrap();
+        assert_eq!((partial.text.as_str(), partial.streaming), ("Hello", true));
+
+        let item = json!({ "type": "agentMessage", "id": "m1", "text": "Hello!" });
+        registry.observe(&json!({ "method": "item/completed", "params": { "threadId": "t1", "turnId": "u1", "item": item } }));
+        let partial = registry.get("t1").unwrap().partial_message.unwrap();
+        assert_eq!((partial.text.as_str(), partial.streaming), ("Hello!", false));
+    }
+
+    #[test]
+    fn notifies_when_active_turn_completes() {
+        let registry = ThreadRegistry::default();
+        registry.observe(&json!({ "method": "turn/started", "params": { "threadId": "t1", "turn": { "id": "turn-1" } } }));
+        let (turn_id, mut done) = registry.wait_for_turn_end("t1").unwrap();
+        assert_eq!(turn_id, "turn-1");
+        assert!(done.try_recv().is_err());
+
+        registry.observe(&json!({ "method": "turn/completed", "params": { "threadId": "t1", "turn": { "id": "turn-1", "status": "interrupted" } } }));
+        assert!(done.try_recv().is_ok());
+        assert!(registry.wait_for_turn_end("t1").is_none());
+    }
+
+    #[test]
+    fn turn_end_waiters_racing_completion_still_fire() {
+        let registry = std::sync::Arc::new(ThreadRegistry::default());
+        for _ in 0..500 {
+            registry.observe(&json!({ "method": "turn/started", "params": { "threadId": "t1", "turn": { "id": "turn-1" } } }));
+            let completer = {
+                let registry = std::sync::Arc::clone(&registry);
+                std::thread::spawn(move || {
+                    registry.observe(&json!({ "method": "turn/completed", "params": { "threadId": "t1", "turn": { "id": "turn-1" } } }));
+                })
+            };
+            let waiter = registry.wait_for_turn_end("t1");
+            completer.join().unwrap();
+            if let Some((_, mut done)) = waiter {
+                assert!(done.try_recv().is_ok());
+            }
+        }
+    }
+}
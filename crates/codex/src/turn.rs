@@ -0,0 +1,466 @@
+//! Turn submission helpers shared by the Tauri commands and the web handlers.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use crate::app_server::CodexAppServer;
+use crate::defaults::normalize_approval_policy;
+use crate::error::CodexiaError;
+use crate::thread::translate_cwd;
+
+const CLIENT_USER_MESSAGE_ID: &str = "clientUserMessageId";
+
+/// How long [`interrupt_and_send`] waits for codex to abort the running turn.
+const INTERRUPT_ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a turn on a just-started thread waits for `thread/started`
+/// before it is sent anyway.
+const THREAD_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends `turn/start`, making sure the user message carries a
+/// `clientUserMessageId` the frontend can correlate with later events.
+///
+/// A caller-supplied id is kept as is; otherwise a fresh UUID is generated.
+/// The id is echoed back in the response next to the `turn` object.
+///
+/// On a thread this client just started, the turn is held until codex
+/// reports the thread started, so early input is not sent ahead of it. If
+/// that never happens the turn fails rather than run on a thread the UI
+/// has not been told about.
+pub async fn start_turn(codex: &CodexAppServer, mut params: Value) -> Result<Value, String> {
+    let submission_id = ensure_client_user_message_id(&mut params)?;
+    translate_cwd(codex, &mut params);
+    let mut pending = None;
+    if let Some(thread_id) = params.get("threadId").and_then(Value::as_str).map(str::to_string) {
+        if let Some(ready) = codex.threads.wait_until_ready(&thread_id) {
+            if tokio::time::timeout(THREAD_READY_TIMEOUT, ready).await.is_err() {
+                log::warn!("thread {} did not report started within {:?}", thread_id, THREAD_READY_TIMEOUT);
+                return Err(format!(
+                    "Session {} did not report started within {:?}; try again",
+                    thread_id, THREAD_READY_TIMEOUT
+                ));
+            }
+        }
+        // Fields the turn sets itself win over pending session changes.
+        let overrides = codex.threads.take_pending_overrides(&thread_id);
+        if let Value::Object(map) = &mut params {
+            for (key, value) in &overrides {
+                if map.get(key).is_none_or(Value::is_null) {
+                    map.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        // A model passed to turn/start sticks for later turns, so the turn
+        // after a one-turn override switches back explicitly.
+        let restore = match params.get("model") {
+            None | Some(Value::Null) => codex.threads.take_restore_model(&thread_id),
+            Some(_) => None,
+        };
+        if let Some(model) = restore {
+            params["model"] = Value::String(model);
+        }
+        codex.retries.remember(&thread_id, &params);
+        pending = Some((thread_id, overrides));
+    }
+
+    let mut result = match codex.send_request("turn/start", params).await {
+        Ok(result) => result,
+        Err(err) => {
+            // Not applied, so the next turn tries again.
+            if let Some((thread_id, overrides)) = pending.filter(|(_, o)| !o.is_empty()) {
+                let newer = codex.threads.take_pending_overrides(&thread_id);
+                let mut restored = overrides;
+                restored.extend(newer);
+                codex.threads.merge_pending_overrides(&thread_id, restored);
+            }
+            return Err(err);
+        }
+    };
+    if let Value::Object(ref mut map) = result {
+        map.insert(
+            CLIENT_USER_MESSAGE_ID.to_string(),
+            Value::String(submission_id),
+        );
+    }
+    Ok(result)
+}
+
+/// Starts a turn whose input is an image (typically a `data:` URL) followed by
+/// an optional caption.
+pub async fn start_image_turn(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    image_url: String,
+    caption: Option<String>,
+) -> Result<Value, String> {
+    let mut input = vec![json!({ "type": "image", "url": image_url })];
+    if let Some(caption) = caption.filter(|c| !c.trim().is_empty()) {
+        input.push(json!({ "type": "text", "text": caption, "text_elements": [] }));
+    }
+    start_turn(codex, json!({ "threadId": thread_id, "input": input })).await
+}
+
+/// Interrupts the turn in flight on `thread_id`; a no-op when nothing is
+/// running. Only the turn is stopped, the thread and the app-server's stdin
+/// stay usable for the next message.
+pub async fn interrupt_session(codex: &CodexAppServer, thread_id: &str) -> Result<(), String> {
+    let Some(turn_id) = codex.threads.get(thread_id).and_then(|session| session.active_turn_id) else {
+        return Ok(());
+    };
+    codex
+        .send_priority_request(
+            "turn/interrupt",
+            json!({ "threadId": thread_id, "turnId": turn_id }),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Interrupts the turn in flight on `thread_id`, waits until codex reports it
+/// completed, then sends `message` as a new turn. Without a running turn the
+/// message is sent right away.
+pub async fn interrupt_and_send(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    message: &str,
+) -> Result<Value, String> {
+    if message.trim().is_empty() {
+        return Err("Message cannot be empty".to_string());
+    }
+    if let Some((turn_id, done)) = codex.threads.wait_for_turn_end(thread_id) {
+        codex
+            .send_priority_request(
+                "turn/interrupt",
+                json!({ "threadId": thread_id, "turnId": turn_id }),
+            )
+            .await?;
+        tokio::time::timeout(INTERRUPT_ACK_TIMEOUT, done)
+            .await
+            .map_err(|_| format!("Timed out waiting for turn {} to stop", turn_id))?
+            .map_err(|_| format!("Stopped tracking turn {}", turn_id))?;
+    }
+    let params = json!({
+        "threadId": thread_id,
+        "input": [{ "type": "text", "text": message, "text_elements": [] }],
+    });
+    start_turn(codex, params).await
+}
+
+/// Sends `message` answered by `model` for this one turn. The thread's own
+/// model is restored on its next turn, so the override is never kept as
+/// the session default.
+pub async fn send_message_with_model(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    message: &str,
+    model: &str,
+) -> Result<Value, String> {
+    if message.trim().is_empty() {
+        return Err("Message cannot be empty".to_string());
+    }
+    let model = model.trim();
+    if model.is_empty() {
+        return Err("Model cannot be empty".to_string());
+    }
+    let session = codex
+        .threads
+        .get(thread_id)
+        .ok_or_else(|| format!("Session not found: {}", thread_id))?;
+    let current = session
+        .restore_model
+        .or_else(|| session.settings?.get("model")?.as_str().map(str::to_string))
+        .ok_or_else(|| {
+            format!("No model recorded for session {}, so it could not be restored after the turn", thread_id)
+        })?;
+
+    let params = json!({
+        "threadId": thread_id,
+        "input": [{ "type": "text", "text": message, "text_elements": [] }],
+        "model": model,
+    });
+    let result = start_turn(codex, params).await.map_err(|err| {
+        if err.contains("unknown field") && err.contains("model") {
+            format!("This codex version does not support per-turn model overrides: {}", err)
+        } else {
+            err
+        }
+    })?;
+    if model != current {
+        codex.threads.set_restore_model(thread_id, current);
+    }
+    Ok(result)
+}
+
+/// Session settings to change from the next turn on; unset fields are kept.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SessionConfigUpdate {
+    pub model: Option<String>,
+    #[serde(alias = "approvalPolicy")]
+    pub approval_policy: Option<Value>,
+    /// `read-only` or `workspace-write`. Full access is only granted when a
+    /// session starts.
+    #[serde(alias = "sandboxMode")]
+    pub sandbox_mode: Option<String>,
+}
+
+/// The `turn/start` `sandboxPolicy` for a `sandbox` mode name, keeping what
+/// it can of the thread's `current` policy: a workspace-write policy stays
+/// as it is, and network access carries over between modes.
+fn sandbox_policy(mode: &str, current: Option<&Value>) -> Result<Value, String> {
+    let current_type = current.and_then(|p| p.get("type")).and_then(Value::as_str);
+    let network_access = current
+        .and_then(|p| p.get("networkAccess"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    match mode.trim() {
+        "read-only" => Ok(json!({ "type": "readOnly", "networkAccess": network_access })),
+        "workspace-write" => match current {
+            Some(policy) if current_type == Some("workspaceWrite") => Ok(policy.clone()),
+            _ => Ok(json!({
+                "type": "workspaceWrite",
+                "writableRoots": [],
+                "networkAccess": network_access,
+                "excludeTmpdirEnvVar": false,
+                "excludeSlashTmp": false,
+            })),
+        },
+        "danger-full-access" => Err(
+            "Full access cannot be switched on in a running session; start a new session with it".to_string(),
+        ),
+        other => Err(format!("Unknown sandbox mode '{}'; expected read-only or workspace-write", other)),
+    }
+}
+
+/// The `turn/start` fields `update` sets, given the thread's `current`
+/// sandbox policy. Like the `config` overrides rejected on `thread/start`,
+/// changes that drop the safety net are refused: approval `never` at any
+/// time, and in preview mode anything but a read-only sandbox with approvals
+/// on request or for untrusted commands.
+fn config_overrides(
+    update: SessionConfigUpdate,
+    current: Option<&Value>,
+    preview_mode: bool,
+) -> Result<Map<String, Value>, String> {
+    let mut overrides = Map::new();
+    if let Some(model) = update.model {
+        let model = model.trim();
+        if model.is_empty() {
+            return Err("Model cannot be empty".to_string());
+        }
+        overrides.insert("model".to_string(), Value::String(model.to_string()));
+    }
+    if let Some(policy) = update.approval_policy.filter(|p| !p.is_null()) {
+        let mut checked = json!({ "approvalPolicy": policy });
+        normalize_approval_policy(&mut checked)?;
+        let policy = checked["approvalPolicy"].take();
+        match policy.as_str() {
+            Some("never") => {
+                return Err("Approval policy 'never' cannot be switched on in a running session".to_string());
+            }
+            Some("untrusted" | "on-request") => {}
+            _ if preview_mode => {
+                return Err("Preview mode asks before every patch; use on-request or untrusted".to_string());
+            }
+            _ => {}
+        }
+        overrides.insert("approvalPolicy".to_string(), policy);
+    }
+    if let Some(mode) = update.sandbox_mode {
+        let policy = sandbox_policy(&mode, current)?;
+        if preview_mode && policy["type"] != "readOnly" {
+            return Err("Preview mode keeps the sandbox read-only".to_string());
+        }
+        overrides.insert("sandboxPolicy".to_string(), policy);
+    }
+    if overrides.is_empty() {
+        return Err("Nothing to update; set model, approval_policy or sandbox_mode".to_string());
+    }
+    Ok(overrides)
+}
+
+/// Changes the model, approval policy or sandbox of `thread_id` without
+/// restarting codex. The app-server has no request for changing a thread's
+/// settings, but values passed to `turn/start` stick for later turns, so
+/// they are sent with the next turn; codex then reports the new settings in
+/// `thread/settings/updated`. Full access, approval `never` and, in preview
+/// mode, anything looser than read-only with approvals are refused. Returns
+/// every change still waiting for a turn.
+pub async fn update_session_config(
+    codex: &CodexAppServer,
+    thread_id: &str,
+    update: SessionConfigUpdate,
+) -> Result<Value, CodexiaError> {
+    crate::thread::require_thread(codex, thread_id)?;
+    // A change still waiting for its turn is what the next one builds on.
+    let current = codex.threads.get(thread_id).and_then(|session| {
+        session.pending_overrides.get("sandboxPolicy").cloned().or_else(|| {
+            let settings = session.settings?;
+            settings.get("sandboxPolicy").or_else(|| settings.get("sandbox")).cloned()
+        })
+    });
+    let overrides = config_overrides(update, current.as_ref(), codex.config().preview_mode)?;
+    let pending = codex
+        .threads
+        .merge_pending_overrides(thread_id, overrides)
+        .ok_or_else(|| format!("Session not found: {}", thread_id))?;
+    Ok(Value::Object(pending))
+}
+
+/// Starts a turn in plan mode so codex proposes a plan for `goal` before
+/// editing anything. The plan streams back through the usual
+/// `turn/plan/updated` and item notifications.
+pub async fn start_plan(codex: &CodexAppServer, thread_id: &str, goal: &str) -> Result<Value, String> {
+    if goal.trim().is_empty() {
+        return Err("Plan goal cannot be empty".to_string());
+    }
+    let settings = codex
+        .threads
+        .get(thread_id)
+        .and_then(|session| session.settings)
+        .ok_or_else(|| format!("Session not found: {}", thread_id))?;
+    let model = settings
+        .get("model")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("No model recorded for session {}", thread_id))?;
+    let effort = settings
+        .get("effort")
+        .or_else(|| settings.get("reasoningEffort"))
+        .cloned()
+        .unwrap_or(Value::Null);
+
+    let params = json!({
+        "threadId": thread_id,
+        "input": [{ "type": "text", "text": goal, "text_elements": [] }],
+        "collaborationMode": {
+            "mode": "plan",
+            "settings": {
+                "model": model,
+                "reasoning_effort": effort,
+                "developer_instructions": null,
+            },
+        },
+    });
+    start_turn(codex, params).await.map_err(|err| {
+        if err.contains("collaborationMode") || err.contains("experimental") {
+            format!("This codex version does not support plan mode: {}", err)
+        } else {
+            err
+        }
+    })
+}
+
+/// Asks codex to review the thread's uncommitted changes inline.
+pub async fn request_review(codex: &CodexAppServer, thread_id: &str) -> Result<Value, String> {
+    let params = json!({
+        "threadId": thread_id,
+        "target": { "type": "uncommittedChanges" },
+    });
+    codex.send_request("review/start", params).await.map_err(|err| {
+        if err.contains("review/start") || err.contains("method not found") {
+            format!("This codex version does not support reviews: {}", err)
+        } else {
+            err
+        }
+    })
+}
+
+fn ensure_client_user_message_id(params: &mut Value) -> Result<String, String> {
+    let map = params
+        .as_object_mut()
+        .ok_or_else(|| "turn/start params must be an object".to_string())?;
+
+    if let Some(id) = map
+        .get(CLIENT_USER_MESSAGE_ID)
+        .and_then(Value::as_str)
+        .filter(|id| !id.is_empty())
+    {
+        return Ok(id.to_string());
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    map.insert(CLIENT_USER_MESSAGE_ID.to_string(), Value::String(id.clone()));
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_id_when_missing() {
+        let mut params = json!({ "threadId": "t1", "input": [], "clientUserMessageId": null });
+        let id = ensure_client_user_message_id(&mut params).unwrap();
+        assert!(!id.is_empty());
+        assert_eq!(params[CLIENT_USER_MESSAGE_ID], json!(id));
+    }
+
+    #[test]
+    fn maps_session_config_to_turn_fields() {
+        let overrides = config_overrides(
+            SessionConfigUpdate {
+                model: Some(" gpt-5-codex ".to_string()),
+                approval_policy: Some(json!("ON_REQUEST")),
+                sandbox_mode: Some("read-only".to_string()),
+            },
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            Value::Object(overrides),
+            json!({
+                "model": "gpt-5-codex",
+                "approvalPolicy": "on-request",
+                "sandboxPolicy": { "type": "readOnly", "networkAccess": false },
+            })
+        );
+        let sandbox = |mode: &str| SessionConfigUpdate { sandbox_mode: Some(mode.to_string()), ..Default::default() };
+        assert!(config_overrides(sandbox("workspace_write"), None, false).is_err());
+        let approval = |policy: &str| SessionConfigUpdate {
+            approval_policy: Some(json!(policy)),
+            ..Default::default()
+        };
+        assert!(config_overrides(approval("sometimes"), None, false).is_err());
+        assert!(config_overrides(SessionConfigUpdate::default(), None, false).is_err());
+    }
+
+    #[test]
+    fn session_config_keeps_the_safety_net() {
+        let sandbox = |mode: &str| SessionConfigUpdate { sandbox_mode: Some(mode.to_string()), ..Default::default() };
+        let approval = |policy: &str| SessionConfigUpdate {
+            approval_policy: Some(json!(policy)),
+            ..Default::default()
+        };
+        assert!(config_overrides(sandbox("danger-full-access"), None, false).is_err());
+        assert!(config_overrides(approval("never"), None, false).is_err());
+        assert!(config_overrides(approval("on-failure"), None, false).is_ok());
+
+        // Preview mode forces read-only with approvals, so nothing looser fits.
+        assert!(config_overrides(sandbox("workspace-write"), None, true).is_err());
+        assert!(config_overrides(approval("on-failure"), None, true).is_err());
+        assert!(config_overrides(sandbox("read-only"), None, true).is_ok());
+        assert!(config_overrides(approval("untrusted"), None, true).is_ok());
+
+        // An existing workspace-write policy is kept rather than reset.
+        let current = json!({
+            "type": "workspaceWrite",
+            "writableRoots": ["/repo/target"],
+            "networkAccess": true,
+            "excludeTmpdirEnvVar": false,
+            "excludeSlashTmp": true,
+        });
+        let overrides = config_overrides(sandbox("workspace-write"), Some(&current), false).unwrap();
+        assert_eq!(overrides["sandboxPolicy"], current);
+        let overrides = config_overrides(sandbox("read-only"), Some(&current), false).unwrap();
+        assert_eq!(overrides["sandboxPolicy"], json!({ "type": "readOnly", "networkAccess": true }));
+    }
+
+    #[test]
+    fn keeps_caller_supplied_id() {
+        let mut params = json!({ "threadId": "t1", "input": [], "clientUserMessageId": "abc" });
+        assert_eq!(ensure_client_user_message_id(&mut params).unwrap(), "abc");
+    }
+}
@@ -0,0 +1,287 @@
+//! Historical token usage aggregated from rollout files.
+//!
+//! Files are streamed line by line so scanning a long history never holds more
+//! than one rollout line in memory at a time.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::sessions::{get_sessions_path, rollout_date, scan_jsonl_files};
+use crate::utils::parse_json_line;
+
+const UNKNOWN_MODEL: &str = "unknown";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenTotals {
+    pub input_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_output_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl TokenTotals {
+    fn from_usage(usage: &Value) -> Self {
+        let field = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+        Self {
+            input_tokens: field("input_tokens"),
+            cached_input_tokens: field("cached_input_tokens"),
+            output_tokens: field("output_tokens"),
+            reasoning_output_tokens: field("reasoning_output_tokens"),
+            total_tokens: field("total_tokens"),
+        }
+    }
+
+    /// Reads an app-server `TokenUsageBreakdown`, which is camelCase.
+    pub(crate) fn from_breakdown(usage: &Value) -> Self {
+        let field = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+        Self {
+            input_tokens: field("inputTokens"),
+            cached_input_tokens: field("cachedInputTokens"),
+            output_tokens: field("outputTokens"),
+            reasoning_output_tokens: field("reasoningOutputTokens"),
+            total_tokens: field("totalTokens"),
+        }
+    }
+
+    /// Usage added since `previous` for a running total, which only grows;
+    /// a smaller total means the count restarted from zero.
+    fn since(&self, previous: &TokenTotals) -> TokenTotals {
+        if self.total_tokens < previous.total_tokens {
+            return *self;
+        }
+        Self {
+            input_tokens: self.input_tokens.saturating_sub(previous.input_tokens),
+            cached_input_tokens: self.cached_input_tokens.saturating_sub(previous.cached_input_tokens),
+            output_tokens: self.output_tokens.saturating_sub(previous.output_tokens),
+            reasoning_output_tokens: self.reasoning_output_tokens.saturating_sub(previous.reasoning_output_tokens),
+            total_tokens: self.total_tokens - previous.total_tokens,
+        }
+    }
+
+    pub(crate) fn add(&mut self, other: &TokenTotals) {
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_output_tokens += other.reasoning_output_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: String,
+    pub model: String,
+    pub sessions: usize,
+    #[serde(flatten)]
+    pub tokens: TokenTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageStats {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub sessions: usize,
+    pub totals: TokenTotals,
+    /// One entry per (day, model), ordered by date then model.
+    pub series: Vec<DailyUsage>,
+}
+
+#[derive(Default)]
+struct Bucket {
+    tokens: TokenTotals,
+    sessions: HashSet<String>,
+}
+
+fn parse_date(value: Option<&str>, field: &str) -> Result<Option<NaiveDate>, String> {
+    value
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| {
+            NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d")
+                .map_err(|e| format!("Invalid {} date '{}': {}", field, v, e))
+        })
+        .transpose()
+}
+
+fn in_range(date: NaiveDate, from: Option<NaiveDate>, to: Option<NaiveDate>) -> bool {
+    from.map_or(true, |f| date >= f) && to.map_or(true, |t| date <= t)
+}
+
+/// Returns the session's running token total carried by a `token_count`
+/// event line, if any. Codex repeats these events with unchanged usage, so
+/// callers add the difference to the previous total.
+fn token_count_usage(value: &Value) -> Option<TokenTotals> {
+    let payload = value.get("payload")?;
+    if value.get("type").and_then(Value::as_str) != Some("event_msg")
+        || payload.get("type").and_then(Value::as_str) != Some("token_count")
+    {
+        return None;
+    }
+    let usage = payload.get("info")?.get("total_token_usage")?;
+    Some(TokenTotals::from_usage(usage))
+}
+
+fn line_date(value: &Value) -> Option<NaiveDate> {
+    let ts = value.get("timestamp").and_then(Value::as_str)?;
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc).date_naive())
+}
+
+fn accumulate_file(
+    path: &Path,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    buckets: &mut BTreeMap<(NaiveDate, String), Bucket>,
+) -> bool {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            log::warn!("usage stats: failed to open {}: {}", path.display(), err);
+            return false;
+        }
+    };
+    let fallback_date = rollout_date(path);
+    let session_key = path.to_string_lossy().to_string();
+    let mut model = UNKNOWN_MODEL.to_string();
+    let mut counted = false;
+    let mut running = TokenTotals::default();
+
+    for line in BufReader::new(file).lines() {
+        let Ok(line) = line else { break };
+        let Some(value) = parse_json_line(&line) else { continue };
+
+        if value.get("type").and_then(Value::as_str) == Some("turn_context") {
+            if let Some(m) = value
+                .get("payload")
+                .and_then(|p| p.get("model"))
+                .and_then(Value::as_str)
+            {
+                model = m.to_string();
+            }
+            continue;
+        }
+
+        let Some(total) = token_count_usage(&value) else { continue };
+        if total == running {
+            continue;
+        }
+        let tokens = total.since(&running);
+        running = total;
+        let Some(date) = line_date(&value).or(fallback_date) else { continue };
+        if !in_range(date, from, to) {
+            continue;
+        }
+
+        let bucket = buckets.entry((date, model.clone())).or_default();
+        bucket.tokens.add(&tokens);
+        bucket.sessions.insert(session_key.clone());
+        counted = true;
+    }
+
+    counted
+}
+
+fn collect_usage_stats(from: Option<String>, to: Option<String>) -> Result<UsageStats, String> {
+    let from_date = parse_date(from.as_deref(), "from")?;
+    let to_date = parse_date(to.as_deref(), "to")?;
+    let root = get_sessions_path()?;
+
+    let mut buckets: BTreeMap<(NaiveDate, String), Bucket> = BTreeMap::new();
+    let mut sessions = 0;
+
+    for path in scan_jsonl_files(&root) {
+        // A rollout can only contain events on or after the day it was created.
+        if let (Some(created), Some(to)) = (rollout_date(&path), to_date) {
+            if created > to {
+                continue;
+            }
+        }
+        if accumulate_file(&path, from_date, to_date, &mut buckets) {
+            sessions += 1;
+        }
+    }
+
+    let mut totals = TokenTotals::default();
+    let series = buckets
+        .into_iter()
+        .map(|((date, model), bucket)| {
+            totals.add(&bucket.tokens);
+            DailyUsage {
+                date: date.format("%Y-%m-%d").to_string(),
+                model,
+                sessions: bucket.sessions.len(),
+                tokens: bucket.tokens,
+            }
+        })
+        .collect();
+
+    Ok(UsageStats {
+        from,
+        to,
+        sessions,
+        totals,
+        series,
+    })
+}
+
+/// Sums token usage per day and model over rollouts in `[from, to]` (inclusive,
+/// `YYYY-MM-DD`). Either bound may be omitted.
+pub async fn get_usage_stats(from: Option<String>, to: Option<String>) -> Result<UsageStats, String> {
+    tokio::task::spawn_blocking(move || collect_usage_stats(from, to))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_count_usage_reads_total_token_usage() {
+        let line = r#"{"timestamp":"2025-09-01T10:00:00.000Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":900,"output_tokens":90,"total_tokens":990},"last_token_usage":{"input_tokens":100,"cached_input_tokens":40,"output_tokens":10,"reasoning_output_tokens":5,"total_tokens":110}}}}"#;
+        let value = parse_json_line(line).unwrap();
+
+        let tokens = token_count_usage(&value).expect("token_count line should parse");
+        assert_eq!(tokens.input_tokens, 900);
+        assert_eq!(tokens.output_tokens, 90);
+        assert_eq!(tokens.total_tokens, 990);
+        assert_eq!(line_date(&value), NaiveDate::from_ymd_opt(2025, 9, 1));
+    }
+
+    #[test]
+    fn repeated_token_counts_are_counted_once() {
+        let event = |total: u64| {
+            format!(
+                r#"{{"timestamp":"2025-09-01T10:00:00.000Z","type":"event_msg","payload":{{"type":"token_count","info":{{"total_token_usage":{{"input_tokens":{},"total_tokens":{}}},"last_token_usage":{{"input_tokens":100,"total_tokens":100}}}}}}}}"#,
+                total, total
+            )
+        };
+        let path = std::env::temp_dir().join(format!("codexia-usage-{}.jsonl", uuid::Uuid::new_v4()));
+        std::fs::write(&path, [event(100), event(100), event(250), event(250)].join("\n")).unwrap();
+
+        let mut buckets = BTreeMap::new();
+        assert!(accumulate_file(&path, None, None, &mut buckets));
+        std::fs::remove_file(&path).unwrap();
+        let bucket = buckets.values().next().unwrap();
+        assert_eq!((bucket.tokens.input_tokens, bucket.tokens.total_tokens), (250, 250));
+    }
+
+    #[test]
+    fn token_count_usage_ignores_other_events() {
+        let value = parse_json_line(r#"{"type":"event_msg","payload":{"type":"agent_message"}}"#).unwrap();
+        assert!(token_count_usage(&value).is_none());
+    }
+
+    #[test]
+    fn parse_date_rejects_malformed_input() {
+        assert!(parse_date(Some("2025/09/01"), "from").is_err());
+        assert_eq!(parse_date(Some(""), "from").unwrap(), None);
+    }
+}
@@ -5,10 +5,19 @@ use std::time::UNIX_EPOCH;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
+/// Name of a per-thread event, `codex-<kind>-<thread_id>`.
+pub fn event_name(kind: &str, thread_id: &str) -> String {
+    format!("codex-{}-{}", kind, thread_id)
+}
+
 pub fn parse_json_line(line: &str) -> Option<Value> {
     serde_json::from_str(line).ok()
 }
 
+/// Opening tags of the context codex injects as user messages; such a
+/// message is neither a turn nor something the user wrote.
+pub(crate) const INJECTED_CONTEXT_MARKERS: &[&str] = &["<environment_context>", "<user_instructions>"];
+
 pub fn extract_preview(value: &Value) -> Option<String> {
     let payload = value.get("payload")?;
     if let Some(content) = payload.get("content").and_then(|v| v.as_array()) {
@@ -55,18 +64,56 @@ pub fn file_created_time(path: &Path) -> Option<i64> {
     Some(duration.as_secs() as i64)
 }
 
-pub fn codex_home() -> PathBuf {
-    if let Some(path) = std::env::var_os("CODEX_HOME") {
-        return PathBuf::from(path);
-    }
+/// Overrides the `~/.codexia` directory holding Codexia's own files.
+pub const CODEXIA_HOME_ENV: &str = "CODEXIA_HOME";
 
+const NO_HOME: &str = "no home directory was found (tried the OS profile, HOME and USERPROFILE)";
+
+fn env_dir(key: &str) -> Option<PathBuf> {
+    std::env::var_os(key).filter(|value| !value.is_empty()).map(PathBuf::from)
+}
+
+/// The user's home directory. The OS lookup can fail in sandboxes, services
+/// and containers, so `HOME` and `USERPROFILE` are tried after it.
+pub fn home_dir() -> Option<PathBuf> {
     dirs::home_dir()
+        .or_else(|| env_dir("HOME"))
+        .or_else(|| env_dir("USERPROFILE"))
+}
+
+/// `CODEX_HOME`, else `~/.codex`.
+pub fn try_codex_home() -> Result<PathBuf, String> {
+    if let Some(path) = env_dir("CODEX_HOME") {
+        return Ok(path);
+    }
+    home_dir()
         .map(|home| home.join(".codex"))
-        .unwrap_or_else(|| PathBuf::from(".codex"))
+        .ok_or_else(|| format!("CODEX_HOME is not set and {}", NO_HOME))
 }
 
-pub fn codexia_history_path() -> PathBuf {
-    dirs::home_dir()
-        .map(|home| home.join(".codexia").join("history.jsonl"))
-        .unwrap_or_else(|| PathBuf::from(".codexia/history.jsonl"))
+/// Like [`try_codex_home`], falling back to `.codex` in the current directory.
+pub fn codex_home() -> PathBuf {
+    try_codex_home().unwrap_or_else(|_| PathBuf::from(".codex"))
+}
+
+/// `CODEXIA_HOME`, else `~/.codexia`.
+pub fn try_codexia_dir() -> Result<PathBuf, String> {
+    if let Some(path) = env_dir(CODEXIA_HOME_ENV) {
+        return Ok(path);
+    }
+    home_dir()
+        .map(|home| home.join(".codexia"))
+        .ok_or_else(|| format!("{} is not set and {}", CODEXIA_HOME_ENV, NO_HOME))
+}
+
+/// Like [`try_codexia_dir`], falling back to `.codexia` in the current
+/// directory.
+pub fn codexia_dir() -> PathBuf {
+    try_codexia_dir().unwrap_or_else(|_| PathBuf::from(".codexia"))
+}
+
+pub fn codexia_history_path() -> Result<PathBuf, String> {
+    try_codexia_dir()
+        .map(|dir| dir.join("history.jsonl"))
+        .map_err(|err| format!("Cannot locate the history file: {}", err))
 }
@@ -0,0 +1,271 @@
+//! Static checks of what a session would be started with, so the UI can
+//! flag mistakes inline instead of after a failed `thread/start`.
+//!
+//! Nothing is spawned besides the `codex -V` probes of discovery, and no
+//! network requests are made.
+
+use std::path::Path;
+
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::get_config_path;
+use crate::connectivity::LOCAL_PROVIDER_IDS;
+use crate::defaults::{normalize_approval_policy, reject_safety_overrides};
+use crate::discovery::discover_codex_launch;
+use crate::providers::RootConfig;
+use crate::settings::CodexConfig;
+
+/// `sandbox` values codex accepts on `thread/start`.
+const SANDBOX_MODES: &[&str] = &["read-only", "workspace-write", "danger-full-access"];
+
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Starting a session with this would fail or misbehave.
+    Error,
+    /// Probably a mistake, but codex may still accept it.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    /// `thread/start` param (`cwd`, `model`, ...) or settings field
+    /// (`proxy`, `repo_root`, ...) the issue is about.
+    pub field: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+fn issue(field: &str, severity: Severity, message: impl Into<String>) -> ConfigIssue {
+    ConfigIssue {
+        field: field.to_string(),
+        severity,
+        message: message.into(),
+    }
+}
+
+/// Providers codex will know about: built-in ones, those Codexia registers
+/// from `llms.json`, the configured OSS provider and `model_providers` in
+/// codex's `config.toml`.
+fn known_providers(config: &CodexConfig) -> Vec<String> {
+    let mut providers: Vec<String> = std::iter::once("openai")
+        .chain(LOCAL_PROVIDER_IDS.iter().copied())
+        .map(str::to_string)
+        .collect();
+    if let Ok(llms) = serde_json::from_str::<RootConfig>(include_str!("./llms.json")) {
+        providers.extend(llms.data.into_iter().map(|p| p.model_provider));
+    }
+    if let Some(provider) = &config.oss_provider {
+        providers.push(provider.provider_id.clone());
+    }
+    let toml_providers = get_config_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .and_then(|table| table.get("model_providers")?.as_table().cloned());
+    if let Some(table) = toml_providers {
+        providers.extend(table.keys().cloned());
+    }
+    providers
+}
+
+/// Checks the `thread/start` params against what codex accepts.
+fn check_params(params: &Value, known_providers: &[String], issues: &mut Vec<ConfigIssue>) {
+    match params.get("cwd") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(cwd)) if !cwd.trim().is_empty() => {
+            if !Path::new(cwd).is_dir() {
+                issues.push(issue("cwd", Severity::Error, format!("Working directory does not exist: {}", cwd)));
+            }
+        }
+        Some(_) => issues.push(issue("cwd", Severity::Error, "Working directory must be a non-empty path")),
+    }
+
+    match params.get("model") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(model)) if !model.trim().is_empty() => {}
+        Some(_) => issues.push(issue("model", Severity::Error, "Model must be a non-empty name")),
+    }
+
+    match params.get("modelProvider") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(provider)) if known_providers.iter().any(|known| known == provider) => {}
+        Some(Value::String(provider)) => issues.push(issue(
+            "modelProvider",
+            Severity::Warning,
+            format!("Unknown model provider '{}'; add it to model_providers in codex's config.toml", provider),
+        )),
+        Some(_) => issues.push(issue("modelProvider", Severity::Error, "Model provider must be a name")),
+    }
+
+    let mut normalized = params.clone();
+    if let Err(err) = normalize_approval_policy(&mut normalized) {
+        issues.push(issue("approvalPolicy", Severity::Error, err));
+    }
+
+    match params.get("sandbox") {
+        None | Some(Value::Null) => {}
+        Some(Value::String(mode)) if SANDBOX_MODES.contains(&mode.as_str()) => {}
+        Some(other) => issues.push(issue(
+            "sandbox",
+            Severity::Error,
+            format!("Unknown sandbox mode {}; expected one of: {}", other, SANDBOX_MODES.join(", ")),
+        )),
+    }
+
+    match params.get("config") {
+        None | Some(Value::Null) | Some(Value::Object(_)) => {
+            if let Err(err) = reject_safety_overrides(params) {
+                issues.push(issue("config", Severity::Error, err));
+            }
+        }
+        Some(_) => issues.push(issue("config", Severity::Error, "config overrides must be an object")),
+    }
+}
+
+/// Checks the Codexia settings that shape how codex is launched.
+fn check_settings(config: &CodexConfig, issues: &mut Vec<ConfigIssue>) {
+    for extra in &config.extra_discovery_paths {
+        let extra = extra.trim();
+        if !extra.is_empty() && !Path::new(extra).exists() {
+            issues.push(issue(
+                "extra_discovery_paths",
+                Severity::Warning,
+                format!("Discovery path does not exist: {}", extra),
+            ));
+        }
+    }
+
+    if let Some(proxy) = config.proxy.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        match Url::parse(proxy) {
+            Ok(url) if PROXY_SCHEMES.contains(&url.scheme()) && url.host_str().is_some() => {}
+            Ok(url) => issues.push(issue(
+                "proxy",
+                Severity::Error,
+                format!("Proxy must be an {} URL with a host, got scheme '{}'", PROXY_SCHEMES.join("/"), url.scheme()),
+            )),
+            Err(err) => issues.push(issue("proxy", Severity::Error, format!("Proxy is not a valid URL: {}", err))),
+        }
+    }
+    if let Some(no_proxy) = &config.no_proxy {
+        for entry in no_proxy.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            if entry.contains("://") || entry.contains(char::is_whitespace) {
+                issues.push(issue(
+                    "no_proxy",
+                    Severity::Warning,
+                    format!("no_proxy entries are host names or domains, not '{}'", entry),
+                ));
+            }
+        }
+    }
+
+    if let Some(Err(err)) = config.oss_provider.as_ref().map(|provider| provider.config_args()) {
+        issues.push(issue("oss_provider", Severity::Error, err));
+    }
+    if let Err(err) = config.system_prompt() {
+        issues.push(issue("system_prompt", Severity::Error, err));
+    }
+    if let Err(err) = config.repo_root() {
+        issues.push(issue("repo_root", Severity::Error, err));
+    }
+    if let Err(err) = config.reasoning_args() {
+        issues.push(issue("reasoning", Severity::Error, err));
+    }
+    if let Err(err) = config.provider_args() {
+        issues.push(issue("model_provider", Severity::Error, err));
+    }
+    if let Err(err) = config.custom_args() {
+        issues.push(issue("custom_args", Severity::Error, err));
+    }
+    if let Err(err) = config.custom_env() {
+        issues.push(issue("env", Severity::Error, err));
+    }
+    if let Some(dir) = config.sessions_dir().filter(|dir| !dir.is_dir()) {
+        issues.push(issue(
+            "sessions_dir",
+            Severity::Warning,
+            format!("Sessions directory does not exist: {}", dir.display()),
+        ));
+    }
+}
+
+/// Everything wrong with starting a session with `params` (`thread/start`
+/// params) under the settings `config`. Empty when nothing was found.
+pub async fn validate_config(params: &Value, config: &CodexConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    if discover_codex_launch().await.is_none() {
+        issues.push(issue(
+            "codex",
+            Severity::Error,
+            "codex was not found; install it or add its location to extra_discovery_paths",
+        ));
+    }
+    check_params(params, &known_providers(config), &mut issues);
+    check_settings(config, &mut issues);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(issues: &[ConfigIssue]) -> Vec<(&str, Severity)> {
+        issues.iter().map(|i| (i.field.as_str(), i.severity)).collect()
+    }
+
+    #[test]
+    fn flags_bad_thread_params() {
+        let providers = vec!["openai".to_string()];
+        let mut issues = Vec::new();
+        check_params(
+            &json!({
+                "cwd": "/definitely/not/here",
+                "model": "",
+                "modelProvider": "acme",
+                "approvalPolicy": "sometimes",
+                "sandbox": "workspace_write",
+                "config": { "sandbox_mode": "danger-full-access" },
+            }),
+            &providers,
+            &mut issues,
+        );
+        assert_eq!(
+            fields(&issues),
+            [
+                ("cwd", Severity::Error),
+                ("model", Severity::Error),
+                ("modelProvider", Severity::Warning),
+                ("approvalPolicy", Severity::Error),
+                ("sandbox", Severity::Error),
+                ("config", Severity::Error),
+            ]
+        );
+
+        let mut issues = Vec::new();
+        let cwd = std::env::temp_dir().to_string_lossy().to_string();
+        check_params(
+            &json!({ "cwd": cwd, "model": "gpt-5", "modelProvider": "openai", "approvalPolicy": "ON_REQUEST", "sandbox": "read-only" }),
+            &providers,
+            &mut issues,
+        );
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn flags_malformed_proxy_settings() {
+        let mut issues = Vec::new();
+        let config = CodexConfig {
+            proxy: Some("ftp://proxy.local".to_string()),
+            no_proxy: Some("localhost, http://internal".to_string()),
+            ..CodexConfig::default()
+        };
+        check_settings(&config, &mut issues);
+        assert_eq!(fields(&issues), [("proxy", Severity::Error), ("no_proxy", Severity::Warning)]);
+        assert_eq!(serde_json::to_value(&issues[0]).unwrap()["severity"], "error");
+    }
+}
@@ -1,6 +1,6 @@
 use codex_app_server_protocol::{
     CommandExecutionApprovalDecision, CommandExecutionRequestApprovalResponse,
-    FileChangeApprovalDecision, FileChangeRequestApprovalResponse, RequestId,
+    FileChangeApprovalDecision, RequestId,
 };
 use serde_json::Value;
 use tauri::State;
@@ -20,7 +20,7 @@ pub async fn respond_to_command_execution_approval(
         "codex:response: {}",
         serde_json::to_string(&result_value).unwrap_or_default()
     );
-    state.codex.send_response(request_id, result_value).await?;
+    codexia_codex::approvals::respond_to_approval(&state.codex, request_id, result_value).await?;
     Ok(())
 }
 
@@ -30,13 +30,7 @@ pub async fn respond_to_file_change_approval(
     decision: FileChangeApprovalDecision,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let result_value = to_value(FileChangeRequestApprovalResponse { decision })?;
-    println!(
-        "codex:response: {}",
-        serde_json::to_string(&result_value).unwrap_or_default()
-    );
-    state.codex.send_response(request_id, result_value).await?;
-    Ok(())
+    codexia_codex::approvals::respond_to_file_change_approval(&state.codex, request_id, decision).await
 }
 
 #[tauri::command]
@@ -51,4 +45,64 @@ pub async fn respond_to_request_user_input(
     );
     state.codex.send_response(request_id, response).await?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Answers the oldest clarifying question the thread is waiting on.
+#[tauri::command]
+pub async fn answer_question(
+    thread_id: String,
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::questions::PendingQuestion, codexia_codex::error::CodexiaError> {
+    codexia_codex::questions::answer_question(&state.codex, &thread_id, &text).await
+}
+
+/// Sends `decision` to every approval the thread is waiting on.
+#[tauri::command]
+pub async fn respond_to_all_pending(
+    thread_id: String,
+    decision: codexia_codex::approvals::ApprovalDecision,
+    state: State<'_, AppState>,
+) -> Result<Vec<codexia_codex::approvals::ApprovalResult>, codexia_codex::error::CodexiaError> {
+    codexia_codex::thread::require_thread(&state.codex, &thread_id)?;
+    Ok(codexia_codex::approvals::respond_to_all_pending(&state.codex, &thread_id, decision).await)
+}
+
+/// Deprecated: use [`respond_to_all_pending`], which can also allow for the
+/// rest of the session.
+#[tauri::command]
+pub async fn approve_all_pending(
+    thread_id: String,
+    approved: bool,
+    state: State<'_, AppState>,
+) -> Result<Vec<codexia_codex::approvals::ApprovalResult>, codexia_codex::error::CodexiaError> {
+    respond_to_all_pending(thread_id, approved.into(), state).await
+}
+
+/// Every approval decision made in the thread, oldest first.
+#[tauri::command]
+pub async fn get_approval_history(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<codexia_codex::approvals::ApprovalRecord>, codexia_codex::error::CodexiaError> {
+    codexia_codex::approvals::get_approval_history(&state.codex, &thread_id)
+}
+
+/// Accepts the batch of patches held back in preview mode.
+#[tauri::command]
+pub async fn apply_previewed_patches(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<codexia_codex::approvals::ApprovalResult>, codexia_codex::error::CodexiaError> {
+    codexia_codex::thread::require_thread(&state.codex, &thread_id)?;
+    Ok(codexia_codex::patches::apply_previewed_patches(&state.codex, &thread_id).await)
+}
+
+#[tauri::command]
+pub async fn get_pending_patch(
+    thread_id: String,
+    approval_id: RequestId,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::patches::PatchPreview, String> {
+    codexia_codex::patches::get_pending_patch(&state.codex, &thread_id, &approval_id)
+}
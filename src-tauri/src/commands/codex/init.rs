@@ -1,5 +1,7 @@
+use std::path::PathBuf;
+
 use codexia_codex::CodexInitializationState;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 use codexia_codex::{AppState, initialize_codex};
 
@@ -8,6 +10,26 @@ pub async fn codex_home() -> std::path::PathBuf {
     codexia_codex::utils::codex_home()
 }
 
+#[tauri::command]
+pub async fn codex_discovery_diagnostics() -> codexia_codex::discovery::DiscoveryReport {
+    codexia_codex::discovery::codex_discovery_diagnostics().await
+}
+
+#[tauri::command]
+pub async fn get_codex_help(subcommand: Option<String>) -> Result<String, String> {
+    codexia_codex::discovery::get_codex_help(subcommand).await
+}
+
+#[tauri::command]
+pub async fn bootstrap_check() -> codexia_codex::discovery::BootstrapStatus {
+    codexia_codex::discovery::bootstrap_check().await
+}
+
+#[tauri::command]
+pub async fn check_codex_version_info() -> Result<codexia_codex::discovery::CodexVersion, String> {
+    codexia_codex::discovery::check_codex_version_info().await
+}
+
 #[tauri::command]
 pub async fn initialize_codex_async(
     state: State<'_, AppState>,
@@ -35,4 +57,43 @@ pub async fn initialize_codex_async(
 
     init_state.initialized.store(true, std::sync::atomic::Ordering::SeqCst);
     Ok(())
-}
\ No newline at end of file
+}
+
+/// The file tauri-plugin-log writes to: `<app log dir>/<app name>.log`.
+fn app_log_file(app: &AppHandle) -> Option<PathBuf> {
+    let dir = app.path().app_log_dir().ok()?;
+    Some(dir.join(format!("{}.log", app.package_info().name)))
+}
+
+/// The application log, or the per-session log of `thread_id`.
+#[tauri::command]
+pub async fn get_log_path(
+    thread_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PathBuf, String> {
+    codexia_codex::log_tail::resolve_log_path(&state.codex.session_logs, thread_id.as_deref(), app_log_file(&app))
+}
+
+/// Emits the end of the log as `codexia-log-line` events, then follows it.
+/// Returns the path being streamed.
+#[tauri::command]
+pub async fn stream_log_file(
+    thread_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PathBuf, String> {
+    let path = codexia_codex::log_tail::resolve_log_path(
+        &state.codex.session_logs,
+        thread_id.as_deref(),
+        app_log_file(&app),
+    )?;
+    let event_sink = std::sync::Arc::new(crate::event_sink::TauriEventSink::new(app));
+    codexia_codex::log_tail::stream_log_file(event_sink, &path)?;
+    Ok(path)
+}
+
+#[tauri::command]
+pub async fn stop_log_stream(path: PathBuf) -> bool {
+    codexia_codex::log_tail::stop_log_stream(&path)
+}
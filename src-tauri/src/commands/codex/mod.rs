@@ -5,9 +5,12 @@ pub mod goal;
 pub mod init;
 pub mod models;
 pub mod review;
+pub mod sessions;
+pub mod settings;
 pub mod skills;
 pub mod thread;
 pub mod turn;
+pub mod usage;
 
 pub use account::*;
 pub use approval::*;
@@ -15,6 +18,9 @@ pub use goal::*;
 pub use init::*;
 pub use models::*;
 pub use review::*;
+pub use sessions::*;
+pub use settings::*;
 pub use skills::*;
 pub use thread::*;
-pub use turn::*;
\ No newline at end of file
+pub use turn::*;
+pub use usage::*;
\ No newline at end of file
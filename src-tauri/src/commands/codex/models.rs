@@ -13,11 +13,24 @@ pub async fn list_other_models() -> Result<Vec<codexia_codex::providers::Fronten
     codexia_codex::providers::load_and_fetch_models().await.map_err(|e| e.to_string())
 }
 
+/// Models configured in codex's `config.toml`, for the model picker.
+#[tauri::command]
+pub async fn list_available_models() -> Result<Vec<codexia_codex::config::models::ModelInfo>, String> {
+    codexia_codex::config::models::list_available_models()
+}
+
 #[tauri::command]
 pub async fn load_env_keys() -> Result<Vec<codexia_codex::providers::EnvStatusItem>, String> {
     codexia_codex::providers::load_env_keys().await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn check_oss_provider(
+    endpoint: Option<String>,
+) -> Result<codexia_codex::connectivity::OssProviderStatus, String> {
+    Ok(codexia_codex::connectivity::check_oss_provider(endpoint).await)
+}
+
 #[tauri::command]
 pub async fn model_list(state: State<'_, AppState>) -> Result<ModelListResponse, String> {
     let params = json!({});
@@ -1,6 +1,7 @@
 use codex_app_server_protocol::{
     ReviewStartParams, ReviewStartResponse,
 };
+use serde_json::Value;
 use tauri::State;
 
 use codexia_codex::AppState;
@@ -18,4 +19,20 @@ pub async fn start_review(
         .send_request("review/start", params_value)
         .await?;
     Ok(from_value(result)?)
-}
\ No newline at end of file
+}
+#[tauri::command]
+pub async fn request_review(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    codexia_codex::turn::request_review(&state.codex, &thread_id).await
+}
+
+#[tauri::command]
+pub async fn start_plan(
+    thread_id: String,
+    goal: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    codexia_codex::turn::start_plan(&state.codex, &thread_id, &goal).await
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use tauri::State;
+
+use codexia_codex::AppState;
+use codexia_codex::error::CodexiaError;
+use codexia_codex::migrate::MigrationReport;
+use codexia_codex::session_export::{ExportReport, ImportReport};
+use codexia_codex::session_meta::{SearchHit, SessionEntry, SessionMatch};
+use codexia_codex::sessions::DeleteResult;
+
+#[tauri::command]
+pub async fn migrate_session_file(
+    file_path: String,
+    dry_run: Option<bool>,
+) -> Result<MigrationReport, String> {
+    codexia_codex::migrate::migrate_session_file(file_path, dry_run.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn export_session_json(file_path: String, dest: String) -> Result<ExportReport, String> {
+    codexia_codex::session_export::export_session_json(file_path, dest).await
+}
+
+#[tauri::command]
+pub async fn export_session_markdown(file_path: String) -> Result<String, String> {
+    codexia_codex::session_export::export_session_markdown(file_path).await
+}
+
+#[tauri::command]
+pub async fn import_session_json(file_path: String) -> Result<ImportReport, String> {
+    codexia_codex::session_export::import_session_json(file_path).await
+}
+
+#[tauri::command]
+pub async fn tee_session_to_file(
+    thread_id: String,
+    dest: String,
+    include_stderr: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<(), CodexiaError> {
+    codexia_codex::thread::require_thread(&state.codex, &thread_id)?;
+    Ok(state
+        .codex
+        .tees
+        .start(&thread_id, Path::new(&dest), include_stderr.unwrap_or(false))?)
+}
+
+#[tauri::command]
+pub async fn untee_session(thread_id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.codex.tees.stop(&thread_id).is_some())
+}
+
+#[tauri::command]
+pub async fn fuzzy_find_sessions(
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SessionMatch>, String> {
+    codexia_codex::session_meta::fuzzy_find_sessions(query, limit).await
+}
+
+#[tauri::command]
+pub async fn search_sessions(query: String, limit: Option<usize>) -> Result<Vec<SearchHit>, String> {
+    codexia_codex::session_meta::search_sessions(query, limit).await
+}
+
+#[tauri::command]
+pub async fn load_sessions_from_disk() -> Result<Vec<SessionEntry>, String> {
+    codexia_codex::session_meta::load_sessions_from_disk().await
+}
+
+/// Message count, model and modification time of every rollout, most
+/// recently modified first.
+#[tauri::command]
+pub async fn list_session_summaries() -> Result<Vec<codexia_codex::session_meta::SessionSummary>, String> {
+    codexia_codex::session_meta::list_session_summaries().await
+}
+
+#[tauri::command]
+pub async fn toggle_session_favorite(file_path: String) -> Result<bool, String> {
+    codexia_codex::session_meta::toggle_favorite(&file_path)
+}
+
+#[tauri::command]
+pub async fn set_session_color(
+    file_path: String,
+    color: Option<String>,
+) -> Result<Option<String>, String> {
+    codexia_codex::session_meta::set_session_color(&file_path, color)
+}
+
+/// Sets or clears the custom title of a session, by session id.
+#[tauri::command]
+pub async fn set_session_title(
+    session_id: String,
+    title: Option<String>,
+) -> Result<Option<String>, codexia_codex::error::CodexiaError> {
+    codexia_codex::session_meta::set_session_title(&session_id, title).await
+}
+
+#[tauri::command]
+pub async fn move_session(file_path: String, dest_dir: String) -> Result<String, String> {
+    codexia_codex::sessions::move_session(&file_path, &dest_dir)
+}
+
+#[tauri::command]
+pub async fn delete_session_files(file_paths: Vec<String>) -> Result<Vec<DeleteResult>, String> {
+    codexia_codex::sessions::delete_session_files(&file_paths)
+}
+
+#[tauri::command]
+pub async fn list_sessions_by_color(color: String) -> Result<Vec<SessionEntry>, String> {
+    codexia_codex::session_meta::list_sessions_by_color(color).await
+}
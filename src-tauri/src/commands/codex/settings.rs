@@ -0,0 +1,74 @@
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+use codexia_codex::AppState;
+use codexia_codex::error::CodexiaError;
+use codexia_codex::settings::CodexConfig;
+
+#[tauri::command]
+pub async fn get_codex_config(app: AppHandle) -> Result<CodexConfig, String> {
+    let state = app.try_state::<AppState>();
+    Ok(codexia_codex::settings::current_codex_config(
+        state.as_ref().map(|s| s.codex.as_ref()),
+    ))
+}
+
+#[tauri::command]
+pub async fn set_codex_config(config: CodexConfig, app: AppHandle) -> Result<CodexConfig, String> {
+    let state = app.try_state::<AppState>();
+    codexia_codex::settings::update_codex_config(state.as_ref().map(|s| s.codex.as_ref()), config)
+}
+
+/// Static checks of `params` (`thread/start` params) and `config`, which
+/// defaults to the current settings, before a session is started.
+#[tauri::command]
+pub async fn validate_config(
+    params: Value,
+    config: Option<CodexConfig>,
+    app: AppHandle,
+) -> Result<Vec<codexia_codex::validation::ConfigIssue>, String> {
+    let config = config.unwrap_or_else(|| {
+        let state = app.try_state::<AppState>();
+        codexia_codex::settings::current_codex_config(state.as_ref().map(|s| s.codex.as_ref()))
+    });
+    Ok(codexia_codex::validation::validate_config(&params, &config).await)
+}
+
+/// The command line codex would be launched with under `config`, which
+/// defaults to the current settings. Nothing is spawned.
+#[tauri::command]
+pub async fn build_codex_command(
+    config: Option<CodexConfig>,
+    app: AppHandle,
+) -> Result<codexia_codex::launch_args::LaunchArgs, CodexiaError> {
+    let config = config.unwrap_or_else(|| {
+        let state = app.try_state::<AppState>();
+        codexia_codex::settings::current_codex_config(state.as_ref().map(|s| s.codex.as_ref()))
+    });
+    codexia_codex::app_server::build_codex_command(&config).await
+}
+
+/// Effective reasoning support map: built-ins plus the user's overrides.
+#[tauri::command]
+pub async fn get_reasoning_capabilities(
+    app: AppHandle,
+) -> Result<codexia_codex::reasoning::ReasoningCapabilities, String> {
+    let state = app.try_state::<AppState>();
+    let config = codexia_codex::settings::current_codex_config(state.as_ref().map(|s| s.codex.as_ref()));
+    Ok(codexia_codex::reasoning::reasoning_capabilities(&config))
+}
+
+#[tauri::command]
+pub async fn get_default_config() -> Result<Map<String, Value>, String> {
+    Ok(codexia_codex::defaults::get_default_config())
+}
+
+#[tauri::command]
+pub async fn list_approval_policies() -> Vec<&'static str> {
+    codexia_codex::defaults::list_approval_policies()
+}
+
+#[tauri::command]
+pub async fn set_default_config(config: Value) -> Result<Map<String, Value>, String> {
+    codexia_codex::defaults::set_default_config(config)
+}
@@ -6,50 +6,71 @@ use serde_json::{Value, json};
 use tauri::State;
 
 use codexia_codex::AppState;
+use codexia_codex::error::CodexiaError;
 
 use crate::commands::codex::common::{to_value, from_value};
 
 #[tauri::command]
 pub async fn start_thread(
     params: ThreadStartParams,
+    start_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params_value = to_value(params)?;
-    let result = state
-        .codex
-        .send_request("thread/start", params_value)
-        .await?;
-    Ok(from_value(result)?)
+    Ok(codexia_codex::thread::start_thread_cancellable(&state.codex, params_value, start_id).await?)
+}
+
+#[tauri::command]
+pub async fn cancel_session_start(
+    start_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, CodexiaError> {
+    Ok(codexia_codex::thread::cancel_thread_start(&state.codex, &start_id))
+}
+
+/// Starts a thread with `params` seeded with a summary of `source_thread_id`.
+#[tauri::command]
+pub async fn handoff_session(
+    source_thread_id: String,
+    params: ThreadStartParams,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::thread::Handoff, CodexiaError> {
+    let params_value = to_value(params)?;
+    codexia_codex::thread::handoff_thread(&state.codex, &source_thread_id, params_value).await
 }
 
 #[tauri::command]
 pub async fn resume_thread(
     params: ThreadResumeParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params_value = to_value(params)?;
-    let result = state
-        .codex
-        .send_request("thread/resume", params_value)
-        .await?;
-    Ok(from_value(result)?)
+    Ok(codexia_codex::thread::resume_thread(&state.codex, params_value).await?)
+}
+
+/// Resumes a session from an earlier run by id, reloading its rollout.
+#[tauri::command]
+pub async fn resume_codex_session(
+    session_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, CodexiaError> {
+    codexia_codex::thread::resume_session(&state.codex, &session_id).await
 }
 
 #[tauri::command]
 pub async fn fork_thread(
     params: ThreadForkParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params_value = to_value(params)?;
-    let result = state.codex.send_request("thread/fork", params_value).await?;
-    Ok(from_value(result)?)
+    Ok(codexia_codex::thread::fork_thread(&state.codex, params_value).await?)
 }
 
 #[tauri::command]
 pub async fn rollback_thread(
     params: ThreadRollbackParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params_value = to_value(params)?;
     let result = state
         .codex
@@ -62,7 +83,7 @@ pub async fn rollback_thread(
 pub async fn list_threads(
     params: ThreadListParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params_value = to_value(params)?;
     let result = state.codex.send_request("thread/list", params_value).await?;
     Ok(from_value(result)?)
@@ -72,7 +93,7 @@ pub async fn list_threads(
 pub async fn archive_thread(
     thread_id: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params = json!({
         "threadId": thread_id
     });
@@ -84,7 +105,7 @@ pub async fn archive_thread(
 pub async fn unarchive_thread(
     thread_id: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params = json!({
         "threadId": thread_id
     });
@@ -96,7 +117,7 @@ pub async fn unarchive_thread(
 pub async fn delete_thread(
     thread_id: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params = json!({
         "threadId": thread_id
     });
@@ -108,8 +129,94 @@ pub async fn delete_thread(
 pub async fn rename_thread(
     params: ThreadSetNameParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
     let params_value = to_value(params)?;
     let result = state.codex.send_request("thread/name/set", params_value).await?;
     Ok(from_value(result)?)
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn ping_session(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::thread::ThreadPing, CodexiaError> {
+    Ok(codexia_codex::thread::ping_thread(&state.codex, &thread_id).await)
+}
+
+#[tauri::command]
+pub async fn session_status(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::thread::SessionStatus, CodexiaError> {
+    Ok(codexia_codex::thread::session_status(&state.codex, &thread_id).await)
+}
+
+#[tauri::command]
+pub async fn get_recent_events(
+    thread_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Value>, CodexiaError> {
+    codexia_codex::thread::get_recent_events(&state.codex, &thread_id, limit)
+}
+
+#[tauri::command]
+pub async fn get_partial_message(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<codexia_codex::threads::PartialMessage>, CodexiaError> {
+    codexia_codex::thread::get_partial_message(&state.codex, &thread_id)
+}
+
+#[tauri::command]
+pub async fn get_session_launch_args(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::launch_args::LaunchArgs, CodexiaError> {
+    codexia_codex::thread::get_session_launch_args(&state.codex, &thread_id)
+}
+
+/// Fails with `workingDirFixed` unless `path` already is the working
+/// directory; the session has to be resumed there instead.
+#[tauri::command]
+pub async fn set_session_working_directory(
+    thread_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), CodexiaError> {
+    codexia_codex::thread::set_session_working_directory(&state.codex, &thread_id, &path)
+}
+
+/// Gracefully closes every open thread, e.g. before switching projects.
+#[tauri::command]
+pub async fn close_all_sessions(
+    state: State<'_, AppState>,
+) -> Result<Vec<codexia_codex::thread::CloseResult>, CodexiaError> {
+    Ok(codexia_codex::thread::close_all_threads(&state.codex).await)
+}
+
+/// Closes every thread and stops the codex process, as on exit. New sessions
+/// need an app restart afterwards.
+#[tauri::command]
+pub async fn shutdown_all_sessions(
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::thread::ShutdownSummary, CodexiaError> {
+    Ok(codexia_codex::thread::shutdown_all_sessions(&state.codex).await)
+}
+
+#[tauri::command]
+pub async fn get_session_config(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::thread::ThreadConfig, CodexiaError> {
+    codexia_codex::thread::get_thread_config(&state.codex, &thread_id)
+}
+
+#[tauri::command]
+pub async fn undo_last_patch(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, CodexiaError> {
+    codexia_codex::thread::require_thread(&state.codex, &thread_id)?;
+    Ok(codexia_codex::patches::undo_last_patch(&state.codex, &thread_id)?)
+}
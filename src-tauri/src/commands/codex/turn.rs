@@ -1,10 +1,14 @@
 use codex_app_server_protocol::{
     TurnInterruptParams, TurnStartParams, TurnSteerParams,
 };
+use base64::Engine;
 use serde_json::Value;
-use tauri::State;
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
 use codexia_codex::AppState;
+use codexia_codex::error::CodexiaError;
+use codexia_codex::thread::require_thread;
 
 use crate::commands::codex::common::{to_value, from_value};
 
@@ -12,17 +16,18 @@ use crate::commands::codex::common::{to_value, from_value};
 pub async fn turn_start(
     params: TurnStartParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
+    require_thread(&state.codex, &params.thread_id)?;
     let params_value = to_value(params)?;
-    let result = state.codex.send_request("turn/start", params_value).await?;
-    Ok(from_value(result)?)
+    Ok(codexia_codex::turn::start_turn(&state.codex, params_value).await?)
 }
 
 #[tauri::command]
 pub async fn turn_steer(
     params: TurnSteerParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
+    require_thread(&state.codex, &params.thread_id)?;
     let params_value = to_value(params)?;
     let result = state.codex.send_request("turn/steer", params_value).await?;
     Ok(from_value(result)?)
@@ -32,11 +37,137 @@ pub async fn turn_steer(
 pub async fn turn_interrupt(
     params: TurnInterruptParams,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CodexiaError> {
+    require_thread(&state.codex, &params.thread_id)?;
     let params_value = to_value(params)?;
     let result = state
         .codex
-        .send_request("turn/interrupt", params_value)
+        .send_priority_request("turn/interrupt", params_value)
         .await?;
     Ok(from_value(result)?)
-}
\ No newline at end of file
+}
+
+/// Stops the thread's running turn, if any, keeping the session usable.
+#[tauri::command]
+pub async fn interrupt_session(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), CodexiaError> {
+    require_thread(&state.codex, &thread_id)?;
+    Ok(codexia_codex::turn::interrupt_session(&state.codex, &thread_id).await?)
+}
+
+/// Stops the running turn and sends `message` once codex has acknowledged it.
+#[tauri::command]
+pub async fn interrupt_and_send(
+    thread_id: String,
+    message: String,
+    state: State<'_, AppState>,
+) -> Result<Value, CodexiaError> {
+    require_thread(&state.codex, &thread_id)?;
+    Ok(codexia_codex::turn::interrupt_and_send(&state.codex, &thread_id, &message).await?)
+}
+
+/// Sends `message` answered by `model`, for this turn only.
+#[tauri::command]
+pub async fn send_message_with_model(
+    thread_id: String,
+    message: String,
+    model: String,
+    state: State<'_, AppState>,
+) -> Result<Value, CodexiaError> {
+    require_thread(&state.codex, &thread_id)?;
+    Ok(codexia_codex::turn::send_message_with_model(&state.codex, &thread_id, &message, &model).await?)
+}
+
+/// Sends the given files from the thread's working directory, plus an
+/// optional message, as one turn.
+#[tauri::command]
+pub async fn attach_files(
+    thread_id: String,
+    paths: Vec<String>,
+    message: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::attachments::AttachResult, CodexiaError> {
+    codexia_codex::attachments::attach_files(&state.codex, &thread_id, paths, message).await
+}
+
+/// Starts a turn with text and local image items, in order. Unreadable,
+/// oversized or unsupported images fail the send.
+#[tauri::command]
+pub async fn send_user_input_with_items(
+    thread_id: String,
+    items: Vec<codexia_codex::attachments::InputItemPayload>,
+    state: State<'_, AppState>,
+) -> Result<Value, CodexiaError> {
+    codexia_codex::attachments::send_input_items(&state.codex, &thread_id, items).await
+}
+
+/// Sends `message` as a new turn, or queues it behind the running turn when
+/// `queue_while_busy` is on.
+#[tauri::command]
+pub async fn send_message(
+    thread_id: String,
+    message: String,
+    state: State<'_, AppState>,
+) -> Result<codexia_codex::message_queue::SendResult, CodexiaError> {
+    codexia_codex::message_queue::send_message(&state.codex, &thread_id, &message).await
+}
+
+#[tauri::command]
+pub async fn get_queued_messages(
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<codexia_codex::message_queue::QueuedMessage>, CodexiaError> {
+    Ok(state.codex.messages.queued(&thread_id))
+}
+
+/// Drops the messages waiting behind the running turn; returns how many.
+#[tauri::command]
+pub async fn clear_queue(thread_id: String, state: State<'_, AppState>) -> Result<usize, CodexiaError> {
+    Ok(state.codex.messages.clear(&thread_id))
+}
+
+/// Changes the model, approval policy or sandbox mode from the next turn on.
+/// Returns the changes waiting for that turn.
+#[tauri::command]
+pub async fn update_session_config(
+    thread_id: String,
+    config: codexia_codex::turn::SessionConfigUpdate,
+    state: State<'_, AppState>,
+) -> Result<Value, CodexiaError> {
+    codexia_codex::turn::update_session_config(&state.codex, &thread_id, config).await
+}
+
+fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(rgba).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+/// Submits the image currently on the system clipboard, plus an optional
+/// caption, as a new turn on `thread_id`.
+#[tauri::command]
+pub async fn send_clipboard_image(
+    thread_id: String,
+    caption: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Value, CodexiaError> {
+    require_thread(&state.codex, &thread_id)?;
+    let image = app
+        .clipboard()
+        .read_image()
+        .map_err(|_| "Clipboard does not contain an image".to_string())?;
+    let png = encode_png(image.rgba(), image.width(), image.height())?;
+    let url = format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+    );
+    Ok(codexia_codex::turn::start_image_turn(&state.codex, &thread_id, url, caption).await?)
+}
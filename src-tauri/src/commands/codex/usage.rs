@@ -0,0 +1,9 @@
+use codexia_codex::usage::UsageStats;
+
+#[tauri::command]
+pub async fn get_usage_stats(
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<UsageStats, String> {
+    codexia_codex::usage::get_usage_stats(from, to).await
+}
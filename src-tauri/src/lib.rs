@@ -33,6 +33,7 @@ pub fn run() {
             .plugin(tauri_plugin_process::init())
             .plugin(tauri_plugin_shell::init())
             .plugin(tauri_plugin_dialog::init())
+            .plugin(tauri_plugin_clipboard_manager::init())
             .plugin(tauri_plugin_screenshots::init())
             .plugin(tauri_plugin_updater::Builder::new().build());
 
@@ -56,9 +57,13 @@ pub fn run() {
             .manage(SleepState::default())
             .invoke_handler(tauri::generate_handler![
                 commands::codex::list_other_models,
+                commands::codex::list_available_models,
                 commands::codex::load_env_keys,
                 commands::codex::start_thread,
+                commands::codex::cancel_session_start,
+                commands::codex::handoff_session,
                 commands::codex::resume_thread,
+                commands::codex::resume_codex_session,
                 commands::codex::fork_thread,
                 commands::codex::rollback_thread,
                 commands::codex::list_threads,
@@ -66,9 +71,29 @@ pub fn run() {
                 commands::codex::unarchive_thread,
                 commands::codex::delete_thread,
                 commands::codex::rename_thread,
+                commands::codex::ping_session,
+                commands::codex::session_status,
+                commands::codex::get_session_config,
+                commands::codex::get_session_launch_args,
+                commands::codex::set_session_working_directory,
+                commands::codex::get_recent_events,
+                commands::codex::get_partial_message,
+                commands::codex::close_all_sessions,
+                commands::codex::shutdown_all_sessions,
+                commands::codex::undo_last_patch,
                 commands::codex::turn_start,
+                commands::codex::send_clipboard_image,
                 commands::codex::turn_steer,
                 commands::codex::turn_interrupt,
+                commands::codex::interrupt_session,
+                commands::codex::interrupt_and_send,
+                commands::codex::send_message_with_model,
+                commands::codex::attach_files,
+                commands::codex::send_user_input_with_items,
+                commands::codex::send_message,
+                commands::codex::get_queued_messages,
+                commands::codex::clear_queue,
+                commands::codex::update_session_config,
                 commands::codex::model_list,
                 commands::codex::account_rate_limits,
                 commands::codex::get_account,
@@ -76,13 +101,54 @@ pub fn run() {
                 commands::codex::skills_list,
                 commands::codex::skills_config_write,
                 commands::codex::start_review,
+                commands::codex::request_review,
+                commands::codex::start_plan,
                 commands::codex::thread_goal_set,
                 commands::codex::thread_goal_get,
                 commands::codex::thread_goal_clear,
                 commands::codex::respond_to_command_execution_approval,
                 commands::codex::respond_to_file_change_approval,
                 commands::codex::respond_to_request_user_input,
+                commands::codex::answer_question,
+                commands::codex::respond_to_all_pending,
+                commands::codex::approve_all_pending,
+                commands::codex::get_pending_patch,
+                commands::codex::apply_previewed_patches,
+                commands::codex::get_approval_history,
                 commands::codex::initialize_codex_async,
+                commands::codex::get_usage_stats,
+                commands::codex::check_oss_provider,
+                commands::codex::codex_discovery_diagnostics,
+                commands::codex::get_log_path,
+                commands::codex::stream_log_file,
+                commands::codex::stop_log_stream,
+                commands::codex::bootstrap_check,
+                commands::codex::check_codex_version_info,
+                commands::codex::get_codex_help,
+                commands::codex::migrate_session_file,
+                commands::codex::export_session_json,
+                commands::codex::export_session_markdown,
+                commands::codex::import_session_json,
+                commands::codex::tee_session_to_file,
+                commands::codex::untee_session,
+                commands::codex::fuzzy_find_sessions,
+                commands::codex::search_sessions,
+                commands::codex::load_sessions_from_disk,
+                commands::codex::list_session_summaries,
+                commands::codex::toggle_session_favorite,
+                commands::codex::set_session_color,
+                commands::codex::set_session_title,
+                commands::codex::list_sessions_by_color,
+                commands::codex::move_session,
+                commands::codex::delete_session_files,
+                commands::codex::get_codex_config,
+                commands::codex::set_codex_config,
+                commands::codex::validate_config,
+                commands::codex::build_codex_command,
+                commands::codex::get_reasoning_capabilities,
+                commands::codex::get_default_config,
+                commands::codex::set_default_config,
+                commands::codex::list_approval_policies,
                 commands::fs::read_directory,
                 commands::fs::get_home_directory,
                 commands::fs::search_files,
@@ -234,6 +300,9 @@ pub fn run() {
 
                 cc::scan::start_session_scanner();
 
+                #[cfg(unix)]
+                listen_for_sigterm(app.handle().clone());
+
                 tauri::async_runtime::spawn(async {
                     tokio::task::spawn_blocking(codexia_git::scan_all_orphan_worktrees)
                         .await
@@ -266,6 +335,10 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app, _event| {
+            if let tauri::RunEvent::Exit = &_event {
+                shutdown_codex(_app);
+            }
+
             // macOS: clicking the Dock icon when the main window is hidden should show it.
             #[cfg(target_os = "macos")]
             {
@@ -286,3 +359,38 @@ pub fn run() {
 fn quit_app(app: tauri::AppHandle) {
     app.exit(0);
 }
+
+/// Stops the codex app-server before the process exits so in-flight turns are
+/// interrupted and rollouts are flushed instead of being truncated.
+fn shutdown_codex(app: &tauri::AppHandle) {
+    use tauri::Manager;
+    if let Some(state) = app.try_state::<codexia_codex::AppState>() {
+        let codex = state.codex.clone();
+        let summary = tauri::async_runtime::block_on(async move {
+            codexia_codex::thread::shutdown_all_sessions(&codex).await
+        });
+        if summary.outcome.is_forced() {
+            log::warn!("codex did not exit on its own ({:?}); its last session may be incomplete", summary.outcome);
+        }
+        for failed in &summary.failed {
+            log::warn!("Session {} did not close on exit: {:?}", failed.thread_id, failed.error);
+        }
+    }
+}
+
+/// Routes SIGTERM through `app.exit` so it gets the same cleanup as a normal quit.
+#[cfg(unix)]
+fn listen_for_sigterm(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                if sigterm.recv().await.is_some() {
+                    log::info!("Received SIGTERM, exiting");
+                    app.exit(0);
+                }
+            }
+            Err(err) => log::warn!("Failed to install SIGTERM handler: {}", err),
+        }
+    });
+}
@@ -1,5 +1,97 @@
-/** codex:stderr — raw stderr line from the codex process */
-export type CodexStderrEvent = { message: string };
+import type { RequestId } from '@/bindings';
+import type { ToolRequestUserInputParams } from '@/bindings/v2';
+import type { PatchPreview, QueuedMessage } from '@/services/tauri/codex';
+
+/** codex:stderr — stderr line from the codex process, with the level parsed from its prefix */
+export type CodexStderrEvent = {
+  level: 'error' | 'warn' | 'info' | 'debug' | 'trace';
+  message: string;
+  sessionId: string | null;
+};
 
 /** codex:parseError — stdout line that failed JSON parsing */
 export type CodexParseErrorEvent = { error: string; raw: string };
+
+/** codex:unknownNotification — a notification method this build does not know yet */
+export type CodexUnknownNotificationEvent = { method: string; params: unknown };
+
+/** codex:closed — the codex app-server stdout ended (shutdown or crash) */
+export type CodexClosedEvent = { expected: boolean; exitCode: number | null; signal: number | null };
+
+/** codex:lineTruncated — an oversized stdout line was dropped, so an event is missing */
+export type CodexLineTruncatedEvent = { bytes: number; limit: number; threadId: string | null };
+
+/** Name of a per-thread event, `codex-<kind>-<threadId>`; matches `event_name` in the codex crate */
+export const codexEventName = (kind: string, threadId: string) => `codex-${kind}-${threadId}`;
+
+/** codex-error-<threadId> — an error line on codex's stderr that mentions the thread */
+export type CodexThreadErrorEvent = CodexStderrEvent & { sessionId: string };
+
+/** codex-session-exited-<threadId> — the codex process running the thread exited; sends now fail */
+export type CodexSessionExitedEvent = {
+  threadId: string;
+  expected: boolean;
+  exitCode: number | null;
+  signal: number | null;
+};
+
+/** codex-queue-<threadId> — a queued message was sent, or failed to send and is queued again */
+export type CodexQueueEvent = { threadId: string; queued: QueuedMessage[]; error: string | null };
+
+/** codexia-log-line — a line of the log followed by `streamLogFile` */
+export type CodexiaLogLineEvent = { path: string; line: string };
+
+/** codex-heartbeat-<threadId> — the thread's turn is still running but has been quiet */
+export type CodexHeartbeatEvent = { threadId: string; turnId: string; elapsedSecs: number };
+
+/** codex-session-started-<threadId> — codex reported the thread started and is ready for turns */
+export type CodexSessionStartedEvent = { threadId: string; model: string | null; rolloutPath: string | null };
+
+/** codex-usage-<threadId> — tokens used by the session so far, including earlier runs */
+export type CodexUsageEvent = {
+  threadId: string;
+  turnId: string | null;
+  inputTokens: number;
+  cachedInputTokens: number;
+  outputTokens: number;
+  reasoningOutputTokens: number;
+  totalTokens: number;
+};
+
+/** codex-question-<threadId> — the turn waits for an answer; reply with `answerQuestion` */
+export type CodexQuestionEvent = ToolRequestUserInputParams & { requestId: RequestId; type: 'requestUserInput' };
+
+/** codex-exec-progress-<threadId> — best-effort completion of a running command, 0 to 1 */
+export type CodexExecProgressEvent = { threadId: string; turnId: string | null; itemId: string; fraction: number };
+
+/** codex:rateLimitRetry — a rate-limited turn is being resent, or retrying gave up */
+export type CodexRateLimitRetryEvent = {
+  threadId: string;
+  status: 'retrying' | 'gaveUp' | 'failed';
+  attempt: number;
+  maxAttempts?: number;
+  delaySecs?: number;
+  error?: string;
+};
+
+/** codex/patch-preview — preview mode held another patch; `patches` is the thread's whole batch */
+export type CodexPatchPreviewEvent = { threadId: string; patches: PatchPreview[] };
+
+/** codex-approval-<threadId> — an approval request for the dialog; `paths` is empty for commands */
+export type CodexApprovalEvent = {
+  approval_id: RequestId;
+  thread_id: string;
+  kind: 'exec' | 'patch';
+  command: string | null;
+  cwd: string | null;
+  reason: string | null;
+  paths: string[];
+};
+
+/** codex/approval-timeout — an unanswered approval was auto-declined */
+export type CodexApprovalTimeoutEvent = {
+  requestId: RequestId;
+  threadId: string | null;
+  type: 'commandExecution' | 'fileChange';
+  timeoutSecs: number;
+};
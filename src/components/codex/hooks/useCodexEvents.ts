@@ -1,4 +1,5 @@
 import { useEffect, useRef } from 'react';
+import type { CodexApprovalTimeoutEvent } from '@/components/codex/CodexInternalEvent';
 import { useApprovalStore, useCodexStore, useRequestUserInputStore } from '@/components/codex/stores';
 import { isDesktopTauri } from '@/hooks/runtime';
 import { getAccountWithParams } from '@/services';
@@ -8,6 +9,10 @@ import { useServerNotificationHandler } from './useServerNotificationHandler';
 import { useSseEventBridge } from './useSseEventBridge';
 import { useTauriEventListeners } from './useTauriEventListeners';
 
+// Module-level so its identity is stable across renders.
+const handleApprovalTimeout = ({ requestId }: CodexApprovalTimeoutEvent) =>
+  useApprovalStore.getState().removeApproval(requestId);
+
 export function useCodexEvents(enabled = true) {
   // Read volatile values via refs so downstream hooks never need to re-run
   // their effects when they change — re-registering Tauri listeners on every
@@ -59,6 +64,7 @@ export function useCodexEvents(enabled = true) {
   const sharedHandlers = {
     enabled,
     onApproval,
+    onApprovalTimeout: handleApprovalTimeout,
     onUserInputRequest,
     onNotification: handleServerNotification,
   };
@@ -1,11 +1,13 @@
 import { useEffect } from 'react';
 import type { ServerNotification } from '@/bindings/ServerNotification';
+import type { CodexApprovalTimeoutEvent } from '@/components/codex/CodexInternalEvent';
 import type { ApprovalRequest, RequestUserInputRequest } from '@/components/codex/stores';
 import { buildUrl } from '@/hooks/runtime';
 
 interface SseEventHandlers {
   enabled: boolean;
   onApproval: (payload: ApprovalRequest) => void;
+  onApprovalTimeout: (payload: CodexApprovalTimeoutEvent) => void;
   onUserInputRequest: (payload: RequestUserInputRequest) => void;
   onNotification: (payload: ServerNotification) => void;
 }
@@ -15,6 +17,7 @@ interface SseEventHandlers {
 export function useSseEventBridge({
   enabled,
   onApproval,
+  onApprovalTimeout,
   onUserInputRequest,
   onNotification,
 }: SseEventHandlers) {
@@ -38,6 +41,10 @@ export function useSseEventBridge({
           onApproval(envelope.payload as ApprovalRequest);
           return;
         }
+        if (envelope.event === 'codex/approval-timeout') {
+          onApprovalTimeout(envelope.payload as CodexApprovalTimeoutEvent);
+          return;
+        }
         if (envelope.event === 'codex/request-user-input') {
           onUserInputRequest(envelope.payload as RequestUserInputRequest);
           return;
@@ -58,5 +65,5 @@ export function useSseEventBridge({
     return () => {
       es.close();
     };
-  }, [enabled, onApproval, onUserInputRequest, onNotification]);
+  }, [enabled, onApproval, onApprovalTimeout, onUserInputRequest, onNotification]);
 }
@@ -1,22 +1,36 @@
 import { listen } from '@tauri-apps/api/event';
-import { useEffect } from 'react';
+import { createElement, useEffect } from 'react';
 import type { ServerNotification } from '@/bindings/ServerNotification';
-import type { CodexParseErrorEvent, CodexStderrEvent } from '@/components/codex/CodexInternalEvent';
+import type {
+  CodexApprovalTimeoutEvent,
+  CodexClosedEvent,
+  CodexLineTruncatedEvent,
+  CodexParseErrorEvent,
+  CodexPatchPreviewEvent,
+  CodexRateLimitRetryEvent,
+  CodexStderrEvent,
+  CodexUnknownNotificationEvent,
+} from '@/components/codex/CodexInternalEvent';
+import { ToastAction } from '@/components/ui/toast';
+import { toast } from '@/components/ui/use-toast';
+import { applyPreviewedPatches } from '@/services/tauri/codex';
 import type { ApprovalRequest, RequestUserInputRequest } from '@/components/codex/stores';
 
 interface TauriEventHandlers {
   enabled: boolean;
   onApproval: (payload: ApprovalRequest) => void;
+  onApprovalTimeout: (payload: CodexApprovalTimeoutEvent) => void;
   onUserInputRequest: (payload: RequestUserInputRequest) => void;
   onNotification: (payload: ServerNotification) => void;
 }
 
 // Registers Tauri native event listeners (approval requests, user input
-// requests, server notifications, stderr, parse errors) and cleans them
+// requests, server notifications, stderr, parse errors, process exit) and cleans them
 // up on unmount.
 export function useTauriEventListeners({
   enabled,
   onApproval,
+  onApprovalTimeout,
   onUserInputRequest,
   onNotification,
 }: TauriEventHandlers) {
@@ -48,6 +62,10 @@ export function useTauriEventListeners({
       onApproval(event.payload);
     });
 
+    void registerListener<CodexApprovalTimeoutEvent>('codex/approval-timeout', (event) => {
+      onApprovalTimeout(event.payload);
+    });
+
     void registerListener<RequestUserInputRequest>('codex/request-user-input', (event) => {
       onUserInputRequest(event.payload);
     });
@@ -57,7 +75,10 @@ export function useTauriEventListeners({
     });
 
     void registerListener<CodexStderrEvent>('codex:stderr', (event) => {
-      console.error('[useTauriEventListeners] codex stderr:', event.payload.message);
+      const { level, message } = event.payload;
+      const log =
+        level === 'error' ? console.error : level === 'warn' ? console.warn : console.debug;
+      log('[useTauriEventListeners] codex stderr:', message);
     });
 
     void registerListener<CodexParseErrorEvent>('codex:parseError', (event) => {
@@ -68,9 +89,76 @@ export function useTauriEventListeners({
       );
     });
 
+    void registerListener<CodexUnknownNotificationEvent>('codex:unknownNotification', (event) => {
+      console.debug(
+        '[useTauriEventListeners] codex unknown notification:',
+        event.payload.method,
+        event.payload.params
+      );
+    });
+
+    void registerListener<CodexLineTruncatedEvent>('codex:lineTruncated', (event) => {
+      const { bytes, limit } = event.payload;
+      console.warn('[useTauriEventListeners] codex line dropped:', bytes, 'bytes, limit', limit);
+      toast({
+        title: 'Codex output incomplete',
+        description: `An event of ${Math.round(bytes / 1024)} KB exceeded the ${Math.round(limit / 1024)} KB limit and was dropped.`,
+        variant: 'destructive',
+      });
+    });
+
+    void registerListener<CodexRateLimitRetryEvent>('codex:rateLimitRetry', (event) => {
+      const { status, attempt, maxAttempts, delaySecs, error } = event.payload;
+      if (status === 'retrying') {
+        toast({
+          title: 'Rate limited',
+          description: `Retrying in ${delaySecs}s (${attempt}/${maxAttempts}).`,
+        });
+        return;
+      }
+      toast({
+        title: 'Retry failed',
+        description: error ?? 'The rate-limited turn could not be resent.',
+        variant: 'destructive',
+      });
+    });
+
+    void registerListener<CodexPatchPreviewEvent>('codex/patch-preview', (event) => {
+      const { threadId, patches } = event.payload;
+      const files = patches.reduce((count, patch) => count + patch.files.length, 0);
+      toast({
+        title: 'Patches ready for review',
+        description: `${patches.length} patch(es) touching ${files} file(s) are waiting to be applied.`,
+        action: createElement(
+          ToastAction,
+          {
+            altText: 'Apply previewed patches',
+            onClick: () => {
+              void applyPreviewedPatches(threadId);
+            },
+          },
+          'Apply all'
+        ),
+      });
+    });
+
+    void registerListener<CodexClosedEvent>('codex:closed', (event) => {
+      const { expected, exitCode, signal } = event.payload;
+      if (expected || exitCode === 0) {
+        return;
+      }
+      const detail = exitCode !== null ? `code ${exitCode}` : signal !== null ? `signal ${signal}` : 'unknown status';
+      console.error('[useTauriEventListeners] codex app-server terminated unexpectedly:', detail);
+      toast({
+        title: 'Codex stopped unexpectedly',
+        description: `The codex app-server exited (${detail}).`,
+        variant: 'destructive',
+      });
+    });
+
     return () => {
       cancelled = true;
       unlisteners.forEach((unlisten) => unlisten());
     };
-  }, [enabled, onApproval, onUserInputRequest, onNotification]);
+  }, [enabled, onApproval, onApprovalTimeout, onUserInputRequest, onNotification]);
 }
@@ -6,7 +6,13 @@ import type {
   FileChangeApprovalDecision,
   FileChangeRequestApprovalParams,
 } from '@/bindings/v2';
-import { respondToCommandExecutionApproval, respondToFileChangeApproval } from '@/services';
+import {
+  respondToAllPending,
+  respondToCommandExecutionApproval,
+  respondToFileChangeApproval,
+  type ApprovalDecision,
+  type PatchPreview,
+} from '@/services';
 
 export type ApprovalRequest =
   | (CommandExecutionRequestApprovalParams & {
@@ -16,6 +22,8 @@ export type ApprovalRequest =
   | (FileChangeRequestApprovalParams & {
       type: 'fileChange';
       requestId: RequestId;
+      /** Proposed diff, when the change was seen before the request. */
+      patch?: PatchPreview;
     });
 
 interface ApprovalStore {
@@ -30,6 +38,8 @@ interface ApprovalStore {
     isCommandExecution: boolean,
     decision: CommandExecutionApprovalDecision | FileChangeApprovalDecision
   ) => Promise<void>;
+  respondToAll: (threadId: string, decision: ApprovalDecision) => Promise<void>;
+  removeApproval: (requestId: RequestId) => void;
   clearCurrent: () => void;
 }
 
@@ -71,6 +81,33 @@ export const useApprovalStore = create<ApprovalStore>((set, _get) => ({
     }
   },
 
+  respondToAll: async (threadId, decision) => {
+    const results = await respondToAllPending(threadId, decision);
+    const answered = new Set(results.filter((r) => r.ok).map((r) => r.request_id));
+    set((state) => {
+      const pending = state.pendingApprovals.filter((a) => !answered.has(a.requestId));
+      return {
+        pendingApprovals: pending,
+        currentApproval: pending[0] || null,
+      };
+    });
+    const failed = results.filter((r) => !r.ok);
+    if (failed.length > 0) {
+      console.error('Failed to respond to some approvals:', failed);
+    }
+  },
+
+  // Drops a request that was resolved without the user, e.g. auto-declined.
+  removeApproval: (requestId) => {
+    set((state) => {
+      const pending = state.pendingApprovals.filter((a) => a.requestId !== requestId);
+      return {
+        pendingApprovals: pending,
+        currentApproval: pending[0] || null,
+      };
+    });
+  },
+
   clearCurrent: () => {
     set((state) => ({
       currentApproval: state.pendingApprovals[1] || null,
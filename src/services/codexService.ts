@@ -14,10 +14,12 @@ import { useCodexStore, useConfigStore } from '@/components/codex/stores';
 import { useWorkspaceStore } from '@/stores';
 import { useSettingsStore } from '@/stores/settings';
 import { convertThreadHistoryToEvents } from '@/utils/threadHistoryConverter';
+import { toast } from '@/components/ui/use-toast';
 import {
   threadStart as apiThreadStart,
   gitCreateWorktree,
   listThreads,
+  setDefaultConfig,
   skillList,
   threadFork,
   threadResume,
@@ -284,8 +286,17 @@ export const codexService = {
       };
       const response = await apiThreadStart(params);
       const thread = response.thread;
+      if (response.connectivityWarning) {
+        toast({ title: response.connectivityWarning, variant: 'destructive' });
+      }
 
       set({ ...syncThreadToStore(thread.id, thread, []) });
+      // Remember the model as a default for clients that leave it unset. The
+      // access mode is left out so one permissive thread does not loosen
+      // every thread started after it.
+      void setDefaultConfig({ model, modelProvider }).catch((error) =>
+        console.warn('[CodexService] Failed to store default config:', error)
+      );
 
       console.log('[CodexService] threadStart completed successfully');
       return thread;
@@ -1,5 +1,6 @@
-import type { RequestId, ThreadId } from '@/bindings';
+import type { RequestId, ServerNotification, ThreadId } from '@/bindings';
 import type {
+  AskForApproval,
   CommandExecutionApprovalDecision,
   FileChangeApprovalDecision,
   GetAccountParams,
@@ -56,11 +57,39 @@ export async function listModels() {
   return await postJson<ModelListResponse>('/api/codex/model/list', params);
 }
 
-export async function threadStart(params: ThreadStartParams) {
+/** `connectivityWarning` is set when the model provider could not be reached. */
+export type ThreadStartResult = ThreadStartResponse & { connectivityWarning?: string };
+
+/** Pass a `startId` to make the start cancellable via `cancelSessionStart`. */
+export async function threadStart(params: ThreadStartParams, startId?: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ThreadStartResult>('start_thread', { params, startId: startId ?? null });
+  }
+  return await postJson<ThreadStartResult>('/api/codex/thread/start', {
+    ...params,
+    startId: startId ?? null,
+  });
+}
+
+/** Returns false when the start already finished. */
+export async function cancelSessionStart(startId: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<boolean>('cancel_session_start', { startId });
+  }
+  return await postJson<boolean>('/api/codex/thread/cancel-start', { startId });
+}
+
+export interface Handoff {
+  thread_id: string;
+  summary: string;
+}
+
+/** Starts a new thread seeded with a summary of `sourceThreadId`. */
+export async function handoffSession(sourceThreadId: ThreadId, params: ThreadStartParams) {
   if (isDesktopTauri()) {
-    return await invokeTauri<ThreadStartResponse>('start_thread', { params });
+    return await invokeTauri<Handoff>('handoff_session', { sourceThreadId, params });
   }
-  return await postJson<ThreadStartResponse>('/api/codex/thread/start', params);
+  return await postJson<Handoff>('/api/codex/thread/handoff', { sourceThreadId, params });
 }
 
 export async function threadResume(params: ThreadResumeParams) {
@@ -70,6 +99,14 @@ export async function threadResume(params: ThreadResumeParams) {
   return await postJson<ThreadResumeResponse>('/api/codex/thread/resume', params);
 }
 
+/** Resumes a session from an earlier run by id; fails with `sessionNotFound` if its rollout is gone. */
+export async function resumeCodexSession(sessionId: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ThreadResumeResponse>('resume_codex_session', { sessionId });
+  }
+  return await postJson<ThreadResumeResponse>('/api/codex/session/resume', { sessionId });
+}
+
 export async function threadFork(params: ThreadForkParams) {
   if (isDesktopTauri()) {
     return await invokeTauri<ThreadForkResponse>('fork_thread', { params });
@@ -84,11 +121,47 @@ export async function threadRollback(params: ThreadRollbackParams) {
   return await postJson<ThreadRollbackResponse>('/api/codex/thread/rollback', params);
 }
 
+/** `clientUserMessageId` echoes the id the user message was submitted with. */
+export type TurnStartResult = TurnStartResponse & { clientUserMessageId: string };
+
 export async function turnStart(params: TurnStartParams) {
   if (isDesktopTauri()) {
-    return await invokeTauri<TurnStartResponse>('turn_start', { params });
+    return await invokeTauri<TurnStartResult>('turn_start', { params });
   }
-  return await postJson<TurnStartResponse>('/api/codex/turn/start', params);
+  return await postJson<TurnStartResult>('/api/codex/turn/start', params);
+}
+
+async function readBrowserClipboardImage(): Promise<string> {
+  const items = await navigator.clipboard.read();
+  for (const item of items) {
+    const type = item.types.find((t) => t.startsWith('image/'));
+    if (!type) continue;
+    const blob = await item.getType(type);
+    return await new Promise<string>((resolve, reject) => {
+      const reader = new FileReader();
+      reader.onload = () => resolve(reader.result as string);
+      reader.onerror = () => reject(reader.error);
+      reader.readAsDataURL(blob);
+    });
+  }
+  throw new Error('Clipboard does not contain an image');
+}
+
+/** Sends the clipboard image (and optional caption) as a new turn. */
+export async function sendClipboardImage(threadId: ThreadId, caption?: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<TurnStartResult>('send_clipboard_image', {
+      threadId,
+      caption: caption ?? null,
+    });
+  }
+  // In the browser the clipboard belongs to the client, not the web server.
+  const url = await readBrowserClipboardImage();
+  const input: TurnStartParams['input'] = [{ type: 'image', url }];
+  if (caption?.trim()) {
+    input.push({ type: 'text', text: caption, text_elements: [] });
+  }
+  return await postJson<TurnStartResult>('/api/codex/turn/start', { threadId, input });
 }
 
 export async function turnSteer(params: TurnSteerParams) {
@@ -105,6 +178,109 @@ export async function turnInterrupt(params: TurnInterruptParams) {
   return await postJson('/api/codex/turn/interrupt', params);
 }
 
+/** Stops the thread's running turn, if any; the session stays usable. */
+export async function interruptSession(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<void>('interrupt_session', { threadId });
+  }
+  return await postJson<void>('/api/codex/session/interrupt', { threadId });
+}
+
+/** Stops the running turn and sends `message` as a fresh turn once codex has acknowledged the stop. */
+export async function interruptAndSend(threadId: string, message: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<TurnStartResult>('interrupt_and_send', { threadId, message });
+  }
+  return await postJson<TurnStartResult>('/api/codex/turn/interrupt-and-send', { threadId, message });
+}
+
+/** Sends `message` answered by `model` for this turn only; the thread's own model is restored afterwards. */
+export async function sendMessageWithModel(threadId: string, message: string, model: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<TurnStartResult>('send_message_with_model', { threadId, message, model });
+  }
+  return await postJson<TurnStartResult>('/api/codex/turn/send-with-model', { threadId, message, model });
+}
+
+export interface AttachResult {
+  included: Array<{ path: string; kind: 'text' | 'image' }>;
+  skipped: Array<{ path: string; reason: string }>;
+  /** Absent when nothing could be attached and there was no message. */
+  turn: TurnStartResult | null;
+}
+
+/** Sends files from the thread's working directory, plus an optional message, as one turn. */
+export async function attachFiles(threadId: ThreadId, paths: string[], message?: string) {
+  const body = { threadId, paths, message: message ?? null };
+  if (isDesktopTauri()) {
+    return await invokeTauri<AttachResult>('attach_files', body);
+  }
+  return await postJson<AttachResult>('/api/codex/turn/attach-files', body);
+}
+
+export type InputItemPayload = { type: 'text'; text: string } | { type: 'localImage'; path: string };
+
+/** Sends text and local image items, in order, as one turn. Bad images fail the send. */
+export async function sendUserInputWithItems(threadId: ThreadId, items: InputItemPayload[]) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<TurnStartResult>('send_user_input_with_items', { threadId, items });
+  }
+  return await postJson<TurnStartResult>('/api/codex/turn/send-items', { threadId, items });
+}
+
+export interface QueuedMessage {
+  id: string;
+  text: string;
+}
+
+export interface SendResult {
+  /** Set when the message waits behind the running turn. */
+  queued: QueuedMessage | null;
+  turn: TurnStartResult | null;
+}
+
+/** Sends `message`, or queues it while a turn runs when `queue_while_busy` is on. */
+export async function sendMessage(threadId: ThreadId, message: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SendResult>('send_message', { threadId, message });
+  }
+  return await postJson<SendResult>('/api/codex/turn/send', { threadId, message });
+}
+
+export async function getQueuedMessages(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<QueuedMessage[]>('get_queued_messages', { threadId });
+  }
+  return await postJson<QueuedMessage[]>('/api/codex/turn/queue', { threadId });
+}
+
+/** Drops the queued messages of a thread and returns how many there were. */
+export async function clearQueue(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<number>('clear_queue', { threadId });
+  }
+  return await postJson<number>('/api/codex/turn/queue/clear', { threadId });
+}
+
+/** Session settings to change from the next turn on; omitted fields are kept. */
+export interface SessionConfigUpdate {
+  model?: string;
+  approvalPolicy?: AskForApproval;
+  sandboxMode?: 'read-only' | 'workspace-write';
+}
+
+/**
+ * Changes the model, approval policy or sandbox mode of a session. They are
+ * sent with the next turn and stick from then on; returns the changes still
+ * waiting for that turn.
+ */
+export async function updateSessionConfig(threadId: ThreadId, config: SessionConfigUpdate) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<Record<string, unknown>>('update_session_config', { threadId, config });
+  }
+  return await postJson<Record<string, unknown>>('/api/codex/session/config/update', { threadId, config });
+}
+
 export async function listThreads(params: ThreadListParams) {
   if (isDesktopTauri()) {
     return await invokeTauri<ThreadListResponse>('list_threads', { params });
@@ -177,6 +353,22 @@ export async function startReview(params: ReviewStartParams) {
   return await postJson<ReviewStartResponse>('/api/codex/review/start', params);
 }
 
+/** Reviews the thread's uncommitted changes inline. */
+export async function requestReview(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ReviewStartResponse>('request_review', { threadId });
+  }
+  return await postJson<ReviewStartResponse>('/api/codex/review/request', { threadId });
+}
+
+/** Starts a plan-mode turn: codex proposes a plan for `goal` before editing. */
+export async function startPlan(threadId: ThreadId, goal: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<TurnStartResult>('start_plan', { threadId, goal });
+  }
+  return await postJson<TurnStartResult>('/api/codex/plan/start', { threadId, goal });
+}
+
 export async function getAccountRateLimits() {
   if (isDesktopTauri()) {
     return await invokeTauri<GetAccountRateLimitsResponse>('account_rate_limits');
@@ -184,6 +376,20 @@ export async function getAccountRateLimits() {
   return await getJson<GetAccountRateLimitsResponse>('/api/codex/account/rate-limits');
 }
 
+export interface PendingQuestion {
+  request_id: RequestId;
+  thread_id: string;
+  question_ids: string[];
+}
+
+/** Answers the oldest clarifying question the thread is waiting on with free text. */
+export async function answerQuestion(threadId: ThreadId, text: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<PendingQuestion>('answer_question', { threadId, text });
+  }
+  return await postJson<PendingQuestion>('/api/codex/question/answer', { threadId, text });
+}
+
 export async function respondToRequestUserInput(requestId: RequestId, response: unknown) {
   if (isDesktopTauri()) {
     return await invokeTauri('respond_to_request_user_input', { requestId, response });
@@ -207,6 +413,93 @@ export async function respondToCommandExecutionApproval(
   });
 }
 
+export interface ApprovalResult {
+  request_id: RequestId;
+  kind: 'commandExecution' | 'fileChange';
+  ok: boolean;
+  error: string | null;
+}
+
+/** Decision sent to every pending approval; `allowForSession` stops similar prompts for the session. */
+export type ApprovalDecision = 'allow' | 'deny' | 'allowForSession';
+
+/** Sends the same decision to every approval the thread is waiting on. */
+export async function respondToAllPending(threadId: ThreadId, decision: ApprovalDecision) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ApprovalResult[]>('respond_to_all_pending', { threadId, decision });
+  }
+  return await postJson<ApprovalResult[]>('/api/codex/approval/respond-all', {
+    thread_id: threadId,
+    decision,
+  });
+}
+
+/** @deprecated Use `respondToAllPending`. */
+export async function approveAllPending(threadId: ThreadId, approved: boolean) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ApprovalResult[]>('approve_all_pending', { threadId, approved });
+  }
+  return await postJson<ApprovalResult[]>('/api/codex/approval/approve-all', {
+    thread_id: threadId,
+    approved,
+  });
+}
+
+export interface PatchFile {
+  path: string;
+  kind: 'add' | 'delete' | 'update';
+  move_path: string | null;
+  diff: string;
+  added: number;
+  removed: number;
+}
+
+export interface PatchPreview {
+  approval_id: RequestId;
+  thread_id: string;
+  item_id: string;
+  files: PatchFile[];
+}
+
+export interface ApprovalRecord {
+  request_id: RequestId;
+  kind: 'commandExecution' | 'fileChange';
+  thread_id: string | null;
+  /** The command, or codex's reason for a file change. */
+  summary: string | null;
+  decision: string;
+  source: 'user' | 'bulk' | 'autoDeny' | 'previewBatch';
+  /** Seconds since the epoch. */
+  decided_at: number;
+}
+
+/** Every approval decision made in the thread, oldest first. */
+export async function getApprovalHistory(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ApprovalRecord[]>('get_approval_history', { threadId });
+  }
+  return await postJson<ApprovalRecord[]>('/api/codex/approval/history', { threadId });
+}
+
+/** Accepts the batch of patches held for review in preview mode. */
+export async function applyPreviewedPatches(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ApprovalResult[]>('apply_previewed_patches', { threadId });
+  }
+  return await postJson<ApprovalResult[]>('/api/codex/approval/apply-previewed', { threadId });
+}
+
+/** Full proposed diff of a pending file-change approval. */
+export async function getPendingPatch(threadId: ThreadId, approvalId: RequestId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<PatchPreview>('get_pending_patch', { threadId, approvalId });
+  }
+  return await postJson<PatchPreview>('/api/codex/approval/pending-patch', {
+    thread_id: threadId,
+    approval_id: approvalId,
+  });
+}
+
 export async function respondToFileChangeApproval(
   requestId: RequestId,
   decision: FileChangeApprovalDecision
@@ -241,6 +534,124 @@ export async function listOtherModels() {
   return await getJson<FrontendProviderModels[]>('/api/codex/model/list-other');
 }
 
+export interface ModelInfo {
+  provider_id: string;
+  model_id: string;
+  label: string;
+}
+
+/** Models configured in codex's `config.toml`, or a built-in list without one. */
+export async function listAvailableModels() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ModelInfo[]>('list_available_models');
+  }
+  return await getJson<ModelInfo[]>('/api/codex/model/available');
+}
+
+export interface OssProviderStatus {
+  endpoint: string;
+  reachable: boolean;
+  models: string[];
+  error: string | null;
+}
+
+export async function checkOssProvider(endpoint?: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<OssProviderStatus>('check_oss_provider', { endpoint: endpoint ?? null });
+  }
+  return await postJson<OssProviderStatus>('/api/codex/oss/check', { endpoint: endpoint ?? null });
+}
+
+export interface DiscoveryCandidate {
+  path: string;
+  source: string;
+  exists: boolean;
+  version: string | null;
+}
+
+export interface DiscoveryReport {
+  candidates: DiscoveryCandidate[];
+  selected: string | null;
+  proxy: string | null;
+  no_proxy: string | null;
+  /** Set when several codex installs report different versions. */
+  version_conflict: string | null;
+}
+
+/** The application log, or the per-session log of `threadId` (needs `CODEXIA_LOG_PATH`). */
+export async function getLogPath(threadId?: ThreadId) {
+  const body = { threadId: threadId ?? null };
+  if (isDesktopTauri()) {
+    return await invokeTauri<string>('get_log_path', body);
+  }
+  return await postJson<string>('/api/log/path', body);
+}
+
+/** Emits the end of the log as `codexia-log-line` events, then follows it; resolves to the path streamed. */
+export async function streamLogFile(threadId?: ThreadId) {
+  const body = { threadId: threadId ?? null };
+  if (isDesktopTauri()) {
+    return await invokeTauri<string>('stream_log_file', body);
+  }
+  return await postJson<string>('/api/log/stream', body);
+}
+
+export async function stopLogStream(path: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<boolean>('stop_log_stream', { path });
+  }
+  return await postJson<boolean>('/api/log/stream/stop', { path });
+}
+
+/** Every location checked for the codex binary, in search order. */
+export async function codexDiscoveryDiagnostics() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<DiscoveryReport>('codex_discovery_diagnostics');
+  }
+  return await getJson<DiscoveryReport>('/api/codex/discovery');
+}
+
+export interface BootstrapStatus {
+  installed: boolean;
+  path: string | null;
+  version: string | null;
+  logged_in: boolean;
+  home_exists: boolean;
+}
+
+export interface CodexVersion {
+  /** `codex -V` output, e.g. `codex-cli 0.50.0` */
+  raw: string;
+  /** null, like minor and patch, when raw has no version number */
+  major: number | null;
+  minor: number | null;
+  patch: number | null;
+}
+
+export async function checkCodexVersionInfo() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CodexVersion>('check_codex_version_info');
+  }
+  return await getJson<CodexVersion>('/api/codex/version');
+}
+
+/** Raw `codex [subcommand] --help` output of the installed codex. */
+export async function getCodexHelp(subcommand?: string) {
+  const body = { subcommand: subcommand ?? null };
+  if (isDesktopTauri()) {
+    return await invokeTauri<string>('get_codex_help', body);
+  }
+  return await postJson<string>('/api/codex/help', body);
+}
+
+/** One-call onboarding check; never rejects on the backend side. */
+export async function bootstrapCheck() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<BootstrapStatus>('bootstrap_check');
+  }
+  return await getJson<BootstrapStatus>('/api/codex/bootstrap');
+}
+
 export async function loadEnvKeys() {
   if (isDesktopTauri()) {
     return await invokeTauri<EnvStatusItem[]>('load_env_keys');
@@ -275,3 +686,479 @@ export async function threadGoalClear(params: ThreadGoalClearParams) {
   }
   return await postJson<ThreadGoalClearResponse>('/api/codex/thread/goal/clear', params);
 }
+
+export interface ThreadPing {
+  alive: boolean;
+  busy: boolean;
+  last_event_age_ms: number | null;
+}
+
+export async function pingSession(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ThreadPing>('ping_session', { threadId });
+  }
+  return await postJson<ThreadPing>('/api/codex/session/ping', { threadId });
+}
+
+export interface SessionStatus {
+  active: boolean;
+  /** Process id of the app-server running the session */
+  pid: number | null;
+  uptime_secs: number | null;
+}
+
+export async function sessionStatus(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SessionStatus>('session_status', { threadId });
+  }
+  return await postJson<SessionStatus>('/api/codex/session/status', { threadId });
+}
+
+export interface CloseResult {
+  thread_id: string;
+  closed: boolean;
+  error: string | null;
+}
+
+/** Interrupts and unsubscribes every open thread, waiting for rollouts to flush. */
+export async function closeAllSessions() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CloseResult[]>('close_all_sessions');
+  }
+  return await postJson<CloseResult[]>('/api/codex/session/close-all', {});
+}
+
+export interface ShutdownSummary {
+  closed: number;
+  failed: CloseResult[];
+  outcome: 'graceful' | 'terminated' | 'killed' | 'notRunning';
+}
+
+/** Closes every thread and stops the codex process, as on exit; new sessions need an app restart. */
+export async function shutdownAllSessions() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ShutdownSummary>('shutdown_all_sessions');
+  }
+  return await postJson<ShutdownSummary>('/api/codex/session/shutdown-all', {});
+}
+
+export interface PartialMessage {
+  item_id: string;
+  turn_id: string | null;
+  text: string;
+  streaming: boolean;
+}
+
+/** The agent message currently being streamed in a thread, or the last completed one. */
+export async function getPartialMessage(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<PartialMessage | null>('get_partial_message', { threadId });
+  }
+  return await postJson<PartialMessage | null>('/api/codex/session/partial-message', { threadId });
+}
+
+/** Recent notifications of a thread, oldest first, for backfilling a late-attached window. */
+export async function getRecentEvents(threadId: ThreadId, limit?: number) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ServerNotification[]>('get_recent_events', {
+      threadId,
+      limit: limit ?? null,
+    });
+  }
+  return await postJson<ServerNotification[]>('/api/codex/session/recent-events', {
+    threadId,
+    limit: limit ?? null,
+  });
+}
+
+export interface ThreadConfig {
+  thread_id: string;
+  settings: Record<string, unknown> | null;
+  config_overrides: Record<string, unknown> | null;
+  developer_instructions: string | null;
+}
+
+export async function getSessionConfig(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ThreadConfig>('get_session_config', { threadId });
+  }
+  return await postJson<ThreadConfig>('/api/codex/session/config', { threadId });
+}
+
+export interface LaunchArgs {
+  program: string;
+  args: string[];
+  env: [string, string][];
+  /** Shell line reproducing the launch, secrets redacted. */
+  command_line: string;
+}
+
+/** The app-server command line serving the thread, for bug reports. */
+export async function getSessionLaunchArgs(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<LaunchArgs>('get_session_launch_args', { threadId });
+  }
+  return await postJson<LaunchArgs>('/api/codex/session/launch-args', { threadId });
+}
+
+/**
+ * A running thread keeps its working directory, so this fails with `workingDirFixed` unless
+ * `path` already is it. Resume the thread with `threadResume({ threadId, cwd: path })` instead.
+ */
+export async function setSessionWorkingDirectory(threadId: ThreadId, path: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<void>('set_session_working_directory', { threadId, path });
+  }
+  return await postJson<void>('/api/codex/session/working-directory', { threadId, path });
+}
+
+/** Reverts the last patch codex applied in the thread; returns the restored paths. */
+export async function undoLastPatch(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<string[]>('undo_last_patch', { threadId });
+  }
+  return await postJson<string[]>('/api/codex/session/undo-patch', { threadId });
+}
+
+export interface MigrationReport {
+  file_path: string;
+  format: 'current' | 'legacy' | 'unknown';
+  dry_run: boolean;
+  changed: boolean;
+  lines_total: number;
+  lines_converted: number;
+  lines_dropped: number;
+  backup_path: string | null;
+}
+
+export async function migrateSessionFile(filePath: string, dryRun = false) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<MigrationReport>('migrate_session_file', { filePath, dryRun });
+  }
+  return await postJson<MigrationReport>('/api/codex/session/migrate', { filePath, dryRun });
+}
+
+export interface ExportReport {
+  dest: string;
+  session_id: string;
+  turns: number;
+  lines_skipped: number;
+}
+
+export interface ImportReport {
+  rollout_path: string;
+  session_id: string;
+  turns: number;
+}
+
+/**
+ * Writes a rollout to `dest` in Codexia's versioned, codex-independent JSON format.
+ * Over the web API, `dest` is a file inside `~/.codexia/exports`.
+ */
+export async function exportSessionJson(filePath: string, dest: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ExportReport>('export_session_json', { filePath, dest });
+  }
+  return await postJson<ExportReport>('/api/codex/session/export-json', { filePath, dest });
+}
+
+/** A rollout as a Markdown transcript; saving it is left to the caller. */
+export async function exportSessionMarkdown(filePath: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<string>('export_session_markdown', { filePath });
+  }
+  return await postJson<string>('/api/codex/session/export-markdown', { filePath });
+}
+
+/** Recreates an exported session as a new codex rollout; over the web API, from `~/.codexia/exports`. */
+export async function importSessionJson(filePath: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ImportReport>('import_session_json', { filePath });
+  }
+  return await postJson<ImportReport>('/api/codex/session/import-json', { filePath });
+}
+
+/** Codexia-side settings for the codex app-server (`~/.codexia/codex.json`). */
+export interface CodexConfig {
+  approval_auto_deny_secs: number | null;
+  extra_discovery_paths: string[];
+  proxy: string | null;
+  no_proxy: string | null;
+  reasoning_capabilities: ReasoningCapabilities;
+  oss_provider: OssProviderConfig | null;
+  auto_retry_rate_limit: boolean;
+  /** Added to every new thread's developer instructions; at most 16000 characters. */
+  system_prompt: string | null;
+  /** Collect proposed patches for review as one batch instead of approving each. */
+  preview_mode: boolean;
+  /** Longest codex stdout line kept, in bytes; defaults to 8 MB. */
+  max_stdout_line_bytes: number | null;
+  /** Seconds of silence in a busy thread before a `codex-heartbeat-<threadId>` event. */
+  heartbeat_secs: number | null;
+  /** Monorepo root threads run in; their working directory becomes the focus. */
+  repo_root: string | null;
+  /** Also save approval decisions in the session's `.meta.json`. */
+  persist_approval_history: boolean;
+  /** Rollout directory; defaults to `sessions` in the codex home. */
+  sessions_dir: string | null;
+  /** Emit `codex-exec-progress-<threadId>` events parsed from command output. */
+  exec_progress: boolean;
+  /** Seconds codex gets to exit on shutdown before it is stopped; defaults to 3. */
+  shutdown_timeout_secs: number | null;
+  /** How codex is wrapped when spawned; `pty` needs `script` on PATH. Unset means `auto`. */
+  spawn_strategy: 'auto' | 'pty' | 'stdbuf' | 'plain' | null;
+  /** Default reasoning effort of new threads. */
+  reasoning_effort: 'minimal' | 'low' | 'medium' | 'high' | null;
+  /** Default reasoning summary of new threads. */
+  reasoning_summary: 'auto' | 'concise' | 'detailed' | 'none' | null;
+  /** Provider of new threads that name none, e.g. `azure` or a `model_providers` key. */
+  model_provider: string | null;
+  /** Extra `codex app-server` flags such as `-c key=value`; bare words and `--` are rejected. */
+  custom_args: string[];
+  /** Extra environment variables for codex; values of names with KEY/TOKEN/SECRET are redacted in logs. */
+  env: Record<string, string> | null;
+  /** Hold messages sent during a running turn and send them when it completes. */
+  queue_while_busy: boolean;
+}
+
+
+/** OpenAI-compatible endpoint registered as codex model provider `provider_id`. */
+export interface OssProviderConfig {
+  provider_id: string;
+  base_url: string;
+  api_key?: string | null;
+}
+
+/** Model (trailing `*` matches by prefix) and provider names mapped to reasoning support. */
+export interface ReasoningCapabilities {
+  models: Record<string, boolean>;
+  providers: Record<string, boolean>;
+}
+
+export async function getReasoningCapabilities() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ReasoningCapabilities>('get_reasoning_capabilities');
+  }
+  return await getJson<ReasoningCapabilities>('/api/codex/settings/reasoning');
+}
+
+export async function getCodexConfig() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CodexConfig>('get_codex_config');
+  }
+  return await getJson<CodexConfig>('/api/codex/settings');
+}
+
+export async function setCodexConfig(config: CodexConfig) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CodexConfig>('set_codex_config', { config });
+  }
+  return await postJson<CodexConfig>('/api/codex/settings', config);
+}
+
+export interface ConfigIssue {
+  /** `thread/start` param (`cwd`, `model`, ...) or settings field (`proxy`, ...). */
+  field: string;
+  severity: 'error' | 'warning';
+  message: string;
+}
+
+/** Static checks of session start params and settings (defaults to the current ones). */
+export async function validateConfig(params: ThreadStartParams, config?: CodexConfig) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ConfigIssue[]>('validate_config', { params, config: config ?? null });
+  }
+  return await postJson<ConfigIssue[]>('/api/codex/settings/validate', { params, config: config ?? null });
+}
+
+/** The command line codex would be launched with under `config` (defaults to the current settings), secrets redacted. */
+export async function buildCodexCommand(config?: CodexConfig) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<LaunchArgs>('build_codex_command', { config: config ?? null });
+  }
+  return await postJson<LaunchArgs>('/api/codex/settings/build-command', { config: config ?? null });
+}
+
+/** Stored `thread/start` defaults applied to any field left unset. */
+export type DefaultThreadConfig = Partial<
+  Pick<
+    ThreadStartParams,
+    | 'model'
+    | 'modelProvider'
+    | 'approvalPolicy'
+    | 'sandbox'
+    | 'serviceTier'
+    | 'personality'
+    | 'baseInstructions'
+    | 'developerInstructions'
+    | 'config'
+  >
+>;
+
+export async function getDefaultConfig() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<DefaultThreadConfig>('get_default_config');
+  }
+  return await getJson<DefaultThreadConfig>('/api/codex/default-config');
+}
+
+export async function setDefaultConfig(config: DefaultThreadConfig) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<DefaultThreadConfig>('set_default_config', { config });
+  }
+  return await postJson<DefaultThreadConfig>('/api/codex/default-config', config);
+}
+
+/** Approval policies codex accepts, for building a picker. */
+export async function listApprovalPolicies() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<string[]>('list_approval_policies');
+  }
+  return await getJson<string[]>('/api/codex/approval-policies');
+}
+
+/** Appends a plain JSONL transcript of the thread to `dest` as it happens; over the web API, inside `~/.codexia/exports`. */
+export async function teeSessionToFile(threadId: ThreadId, dest: string, includeStderr = false) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<void>('tee_session_to_file', { threadId, dest, includeStderr });
+  }
+  await postNoContent('/api/codex/session/tee', { threadId, dest, includeStderr });
+}
+
+export async function unteeSession(threadId: ThreadId) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<boolean>('untee_session', { threadId });
+  }
+  return await postJson<boolean>('/api/codex/session/untee', { threadId });
+}
+
+export interface SessionMatch {
+  file_path: string;
+  title: string | null;
+  tags: string[];
+  score: number;
+}
+
+/** Fuzzy-matches session titles and tags; cheap enough for as-you-type use. */
+export async function fuzzyFindSessions(query: string, limit?: number) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SessionMatch[]>('fuzzy_find_sessions', { query, limit: limit ?? null });
+  }
+  return await postJson<SessionMatch[]>('/api/codex/session/fuzzy-find', { query, limit: limit ?? null });
+}
+
+export interface SearchHit {
+  file_path: string;
+  /** 1-based line of the rollout holding the message */
+  line_number: number;
+  role: 'user' | 'assistant';
+  snippet: string;
+}
+
+/** Full-text search of user and assistant messages, newest session first. */
+export async function searchSessions(query: string, limit?: number) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SearchHit[]>('search_sessions', { query, limit: limit ?? null });
+  }
+  return await postJson<SearchHit[]>('/api/codex/session/search', { query, limit: limit ?? null });
+}
+
+export const SESSION_COLORS = ['red', 'orange', 'yellow', 'green', 'blue', 'purple', 'gray'] as const;
+export type SessionColor = (typeof SESSION_COLORS)[number];
+
+export interface SessionEntry {
+  file_path: string;
+  session_id: string | null;
+  title: string | null;
+  tags: string[];
+  favorite: boolean;
+  color: SessionColor | null;
+  updated_at: number | null;
+  /** Seconds since the epoch. */
+  started_at: number | null;
+  /** Clean close time, or the last event's time if the session did not close cleanly. */
+  ended_at: number | null;
+}
+
+/** Rollouts on disk with sidecar metadata, favorites first. */
+export async function loadSessionsFromDisk() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SessionEntry[]>('load_sessions_from_disk');
+  }
+  return await getJson<SessionEntry[]>('/api/codex/session/list-disk');
+}
+
+export interface SessionSummary {
+  path: string;
+  session_id: string | null;
+  title: string | null;
+  /** User and assistant messages. */
+  message_count: number;
+  /** Seconds since the epoch. */
+  last_modified: number | null;
+  /** Model of the latest turn. */
+  model: string | null;
+}
+
+/** Cheap per-rollout metadata for sorting and previews, most recently modified first. */
+export async function listSessionSummaries() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SessionSummary[]>('list_session_summaries');
+  }
+  return await getJson<SessionSummary[]>('/api/codex/session/summaries');
+}
+
+export async function toggleSessionFavorite(filePath: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<boolean>('toggle_session_favorite', { filePath });
+  }
+  return await postJson<boolean>('/api/codex/session/favorite', { filePath });
+}
+
+/** Sets the session's color label; `null` clears it. */
+export async function setSessionColor(filePath: string, color: SessionColor | null) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SessionColor | null>('set_session_color', { filePath, color });
+  }
+  return await postJson<SessionColor | null>('/api/codex/session/color', { filePath, color });
+}
+
+/** Sets (or with `null`, clears) a session's custom title; resolves to the title now shown. */
+export async function setSessionTitle(sessionId: string, title: string | null) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<string | null>('set_session_title', { sessionId, title });
+  }
+  return await postJson<string | null>('/api/codex/session/title', { sessionId, title });
+}
+
+/**
+ * Moves a rollout and its metadata to `destDir` (relative to the sessions
+ * directory, or absolute inside it); returns the new path.
+ */
+export async function moveSession(filePath: string, destDir: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<string>('move_session', { filePath, destDir });
+  }
+  return await postJson<string>('/api/codex/session/move', { filePath, destDir });
+}
+
+export interface DeleteResult {
+  path: string;
+  success: boolean;
+  error: string | null;
+}
+
+/** Deletes rollouts with their metadata; each file reports its own outcome. */
+export async function deleteSessionFiles(filePaths: string[]) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<DeleteResult[]>('delete_session_files', { filePaths });
+  }
+  return await postJson<DeleteResult[]>('/api/codex/session/delete-files', { filePaths });
+}
+
+export async function listSessionsByColor(color: SessionColor) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SessionEntry[]>('list_sessions_by_color', { color });
+  }
+  return await postJson<SessionEntry[]>('/api/codex/session/list-by-color', { color });
+}
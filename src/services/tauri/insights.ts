@@ -105,3 +105,33 @@ export async function getInsightRankings(filters: InsightFilters): Promise<Ranki
     agent: filters.agent ?? null,
   });
 }
+
+export interface TokenTotals {
+  input_tokens: number;
+  cached_input_tokens: number;
+  output_tokens: number;
+  reasoning_output_tokens: number;
+  total_tokens: number;
+}
+
+export interface DailyUsage extends TokenTotals {
+  date: string;
+  model: string;
+  sessions: number;
+}
+
+export interface UsageStats {
+  from: string | null;
+  to: string | null;
+  sessions: number;
+  totals: TokenTotals;
+  series: DailyUsage[];
+}
+
+/** `from` / `to` are inclusive `YYYY-MM-DD` bounds. */
+export async function getUsageStats(from?: string, to?: string): Promise<UsageStats> {
+  if (isDesktopTauri()) {
+    return invokeTauri<UsageStats>('get_usage_stats', { from: from ?? null, to: to ?? null });
+  }
+  return postJson<UsageStats>('/api/codex/usage/stats', { from: from ?? null, to: to ?? null });
+}
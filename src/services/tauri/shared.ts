@@ -90,14 +90,19 @@ export type TerminalStartResponse = {
   shell: string;
 };
 
-async function extractErrorMessage(response: Response) {
+async function extractError(response: Response) {
   try {
-    const payload = (await response.clone().json()) as { error?: string };
+    const payload = (await response.clone().json()) as { error?: string; code?: string };
     if (payload?.error) {
-      return payload.error;
+      return { message: payload.error, code: payload.code };
     }
   } catch {}
-  return `Request failed: ${response.status}`;
+  return { message: `Request failed: ${response.status}`, code: undefined };
+}
+
+/** Keeps the backend's error `code` on the rejected Error, when there is one. */
+function requestError(message: string, code?: string) {
+  return code ? Object.assign(new Error(message), { code }) : new Error(message);
 }
 
 export async function invokeTauri<T>(
@@ -121,7 +126,7 @@ export async function getJsonWithOptions<T>(
   });
 
   if (!response.ok) {
-    const message = await extractErrorMessage(response);
+    const { message, code } = await extractError(response);
     if (!options?.suppressToast) {
       toast({
         title: 'Request failed',
@@ -129,7 +134,7 @@ export async function getJsonWithOptions<T>(
         variant: 'destructive',
       });
     }
-    return Promise.reject(new Error(message));
+    return Promise.reject(requestError(message, code));
   }
 
   return (await response.json()) as T;
@@ -151,7 +156,7 @@ export async function postJsonWithOptions<T>(
   });
 
   if (!response.ok) {
-    const message = await extractErrorMessage(response);
+    const { message, code } = await extractError(response);
     if (!options?.suppressToast) {
       toast({
         title: 'Request failed',
@@ -159,7 +164,7 @@ export async function postJsonWithOptions<T>(
         variant: 'destructive',
       });
     }
-    return Promise.reject(new Error(message));
+    return Promise.reject(requestError(message, code));
   }
 
   return (await response.json()) as T;
@@ -181,7 +186,7 @@ export async function postNoContentWithOptions(
   });
 
   if (!response.ok) {
-    const message = await extractErrorMessage(response);
+    const { message, code } = await extractError(response);
     if (!options?.suppressToast) {
       toast({
         title: 'Request failed',
@@ -189,7 +194,7 @@ export async function postNoContentWithOptions(
         variant: 'destructive',
       });
     }
-    return Promise.reject(new Error(message));
+    return Promise.reject(requestError(message, code));
   }
 }
 
@@ -1,3 +1,40 @@
+/** Typed error returned by codex commands; branch on `code`, display `message`. */
+export type CodexiaError = {
+  code:
+    | 'notInstalled'
+    | 'incompatibleVersion'
+    | 'sessionNotFound'
+    | 'spawnFailed'
+    | 'workingDirMissing'
+    | 'workingDirFixed'
+    | 'notConnected'
+    | 'other';
+  message: string;
+  sessionId?: string;
+  path?: string;
+  version?: string;
+};
+
+function isCodexiaError(error: unknown): error is CodexiaError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as CodexiaError).code === 'string' &&
+    typeof (error as CodexiaError).message === 'string'
+  );
+}
+
+/** `code` of a Tauri command error, or of a web request error carrying one. */
+export function getErrorCode(error: unknown): CodexiaError['code'] | null {
+  if (isCodexiaError(error)) {
+    return error.code;
+  }
+  if (error instanceof Error && typeof (error as Error & { code?: unknown }).code === 'string') {
+    return (error as Error & { code: CodexiaError['code'] }).code;
+  }
+  return null;
+}
+
 export function getErrorMessage(error: unknown): string {
   if (typeof error === 'string') {
     return error;
@@ -7,6 +44,10 @@ export function getErrorMessage(error: unknown): string {
     return error.message;
   }
 
+  if (isCodexiaError(error)) {
+    return error.message;
+  }
+
   try {
     return JSON.stringify(error);
   } catch (jsonError) {
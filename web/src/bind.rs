@@ -0,0 +1,79 @@
+//! Which interface the web server listens on.
+//!
+//! Localhost is the default so the UI is only reachable through a tunnel or
+//! SSH unless the user opts into LAN access.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use serde::Serialize;
+
+pub const DEFAULT_BIND_HOST: &str = "127.0.0.1";
+
+/// Resolves `localhost`, `lan` (all interfaces) or a literal IP address.
+pub fn resolve_bind_host(host: &str) -> Result<IpAddr, String> {
+    match host.trim().to_ascii_lowercase().as_str() {
+        "" | "localhost" => Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        "lan" => Ok(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        other => other.parse::<IpAddr>().map_err(|_| {
+            format!(
+                "Invalid bind address '{}': expected localhost, lan or an IP address",
+                host
+            )
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BindStatus {
+    pub bind_address: SocketAddr,
+    /// Set when the server is reachable beyond this machine, since the web
+    /// UI has no authentication.
+    pub warning: Option<String>,
+}
+
+impl BindStatus {
+    /// `saved_host` is the host saved for later launches; the warning names
+    /// it when it is not loopback either, since it sticks until changed.
+    pub fn new(bind_address: SocketAddr, saved_host: Option<&str>) -> Self {
+        let saved_remote = saved_host
+            .filter(|host| resolve_bind_host(host).is_ok_and(|ip| !ip.is_loopback()));
+        let warning = (!bind_address.ip().is_loopback()).then(|| {
+            let mut warning = format!(
+                "Web UI is listening on {} without authentication; anyone who can reach this address can control codex",
+                bind_address
+            );
+            if let Some(host) = saved_remote {
+                warning.push_str(&format!(
+                    ". The saved host '{}' makes later launches listen there too; start with --host localhost to reset it",
+                    host
+                ));
+            }
+            warning
+        });
+        Self {
+            bind_address,
+            warning,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_bind_hosts_and_warns_off_loopback() {
+        assert_eq!(resolve_bind_host("localhost").unwrap(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(resolve_bind_host("LAN").unwrap(), IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        assert_eq!(resolve_bind_host("192.168.1.20").unwrap().to_string(), "192.168.1.20");
+        assert!(resolve_bind_host("example.com").is_err());
+
+        let local = BindStatus::new(SocketAddr::new(resolve_bind_host("::1").unwrap(), 7420), Some("lan"));
+        assert!(local.warning.is_none());
+        let lan = BindStatus::new(SocketAddr::new(resolve_bind_host("lan").unwrap(), 7420), Some("localhost"));
+        assert!(!lan.warning.unwrap().contains("saved host"));
+        let saved_lan = BindStatus::new(SocketAddr::new(resolve_bind_host("lan").unwrap(), 7420), Some("lan"));
+        assert!(saved_lan.warning.unwrap().contains("saved host 'lan'"));
+    }
+}
@@ -1,18 +1,19 @@
 use super::to_error_response;
 use super::types::{
-    CommandExecutionApprovalParams, FileChangeApprovalParams,
+    ApproveAllPendingParams, RespondAllPendingParams, AttachFilesParams, SendInputItemsParams, SendMessageParams, SessionConfigParams, BuildCommandParams, LogPathParams, StopLogStreamParams, CancelStartParams, ResumeSessionParams, CodexHelpParams, HandoffParams, StartThreadBody, CommandExecutionApprovalParams, FileChangeApprovalParams,
     UnifiedMcpAddParams, UnifiedMcpReadParams, UnifiedMcpRemoveParams, UnifiedMcpToggleParams,
-    UserInputResponseParams,
+    AnswerQuestionParams, ColorParams, DeleteSessionFilesParams, ExportSessionParams, FuzzyFindSessionsParams, InterruptAndSendParams, MigrateSessionParams, MoveSessionParams, OssProviderParams, PendingPatchParams, RecentEventsParams, SearchSessionsParams, SessionColorParams, SessionTitleParams, SessionFileParams, SessionWorkingDirectoryParams, SendWithModelParams, StartPlanParams, TeeSessionParams, ThreadIdParams, UsageStatsParams, ValidateConfigParams, UserInputResponseParams,
 };
 use axum::{Json, extract::State as AxumState, http::StatusCode};
 use codex_app_server_protocol::{
     GetAccountParams, LoginAccountParams, ModelListParams, ThreadUnarchiveParams,
     ReviewStartParams, SkillsConfigWriteParams, SkillsListParams, ThreadArchiveParams,
     ThreadForkParams, ThreadListParams, ThreadResumeParams, ThreadRollbackParams,
-    ThreadStartParams, TurnInterruptParams, TurnStartParams,
+    TurnInterruptParams, TurnStartParams,
 };
 use serde_json::{Value, json};
 use crate::types::{ErrorResponse, WebServerState};
+use codexia_codex::error::CodexiaError;
 
 use codexia_codex::AppState;
 use codexia_cc::mcp_unified as mcp;
@@ -20,19 +21,50 @@ use codexia_cc::mcp_unified as mcp;
 fn require_codex(state: &WebServerState) -> Result<&AppState, ErrorResponse> {
     state.codex_state.as_deref().ok_or_else(|| ErrorResponse {
         error: "codex backend is not available (codex binary not found in PATH)".to_string(),
+        code: Some(CodexiaError::NotConnected.code()),
     })
 }
 
 pub(crate) async fn api_start_thread(
     AxumState(state): AxumState<WebServerState>,
-    Json(params): Json<ThreadStartParams>,
+    Json(body): Json<StartThreadBody>,
 ) -> Result<Json<Value>, ErrorResponse> {
-    let params_value = serde_json::to_value(params).map_err(to_error_response)?;
-    let result = require_codex(&state)?
-        .codex
-        .send_request("thread/start", params_value)
-        .await
-        .map_err(to_error_response)?;
+    let params_value = serde_json::to_value(body.params).map_err(to_error_response)?;
+    let result = codexia_codex::thread::start_thread_cancellable(
+        &require_codex(&state)?.codex,
+        params_value,
+        body.start_id,
+    )
+    .await
+    .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_handoff_session(
+    AxumState(state): AxumState<WebServerState>,
+    Json(body): Json<HandoffParams>,
+) -> Result<Json<codexia_codex::thread::Handoff>, ErrorResponse> {
+    let params_value = serde_json::to_value(body.params).map_err(to_error_response)?;
+    let codex = &require_codex(&state)?.codex;
+    let handoff =
+        codexia_codex::thread::handoff_thread(codex, &body.source_thread_id, params_value).await?;
+    Ok(Json(handoff))
+}
+
+pub(crate) async fn api_cancel_session_start(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CancelStartParams>,
+) -> Result<Json<bool>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codexia_codex::thread::cancel_thread_start(codex, &params.start_id)))
+}
+
+pub(crate) async fn api_resume_codex_session(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ResumeSessionParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::thread::resume_session(codex, &params.session_id).await?;
     Ok(Json(result))
 }
 
@@ -41,9 +73,7 @@ pub(crate) async fn api_resume_thread(
     Json(params): Json<ThreadResumeParams>,
 ) -> Result<Json<Value>, ErrorResponse> {
     let params_value = serde_json::to_value(params).map_err(to_error_response)?;
-    let result = require_codex(&state)?
-        .codex
-        .send_request("thread/resume", params_value)
+    let result = codexia_codex::thread::resume_thread(&require_codex(&state)?.codex, params_value)
         .await
         .map_err(to_error_response)?;
     Ok(Json(result))
@@ -54,9 +84,7 @@ pub(crate) async fn api_fork_thread(
     Json(params): Json<ThreadForkParams>,
 ) -> Result<Json<Value>, ErrorResponse> {
     let params_value = serde_json::to_value(params).map_err(to_error_response)?;
-    let result = require_codex(&state)?
-        .codex
-        .send_request("thread/fork", params_value)
+    let result = codexia_codex::thread::fork_thread(&require_codex(&state)?.codex, params_value)
         .await
         .map_err(to_error_response)?;
     Ok(Json(result))
@@ -118,10 +146,10 @@ pub(crate) async fn api_turn_start(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<TurnStartParams>,
 ) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
     let params_value = serde_json::to_value(params).map_err(to_error_response)?;
-    let result = require_codex(&state)?
-        .codex
-        .send_request("turn/start", params_value)
+    let result = codexia_codex::turn::start_turn(codex, params_value)
         .await
         .map_err(to_error_response)?;
     Ok(Json(result))
@@ -131,15 +159,114 @@ pub(crate) async fn api_turn_interrupt(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<TurnInterruptParams>,
 ) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
     let params_value = serde_json::to_value(params).map_err(to_error_response)?;
-    let result = require_codex(&state)?
-        .codex
-        .send_request("turn/interrupt", params_value)
+    let result = codex
+        .send_priority_request("turn/interrupt", params_value)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_interrupt_session(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<()>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
+    codexia_codex::turn::interrupt_session(codex, &params.thread_id)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(()))
+}
+
+pub(crate) async fn api_attach_files(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<AttachFilesParams>,
+) -> Result<Json<codexia_codex::attachments::AttachResult>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::attachments::attach_files(
+        codex,
+        &params.thread_id,
+        params.paths,
+        params.message,
+    )
+    .await?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_send_user_input_with_items(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<SendInputItemsParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::attachments::send_input_items(codex, &params.thread_id, params.items).await?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_send_message(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<SendMessageParams>,
+) -> Result<Json<codexia_codex::message_queue::SendResult>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::message_queue::send_message(codex, &params.thread_id, &params.message).await?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_get_queued_messages(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<Vec<codexia_codex::message_queue::QueuedMessage>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codex.messages.queued(&params.thread_id)))
+}
+
+pub(crate) async fn api_clear_queue(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<usize>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codex.messages.clear(&params.thread_id)))
+}
+
+pub(crate) async fn api_update_session_config(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<SessionConfigParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codexia_codex::turn::update_session_config(codex, &params.thread_id, params.config).await?))
+}
+
+pub(crate) async fn api_interrupt_and_send(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<InterruptAndSendParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
+    let result = codexia_codex::turn::interrupt_and_send(codex, &params.thread_id, &params.message)
         .await
         .map_err(to_error_response)?;
     Ok(Json(result))
 }
 
+pub(crate) async fn api_send_message_with_model(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<SendWithModelParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
+    let result = codexia_codex::turn::send_message_with_model(
+        codex,
+        &params.thread_id,
+        &params.message,
+        &params.model,
+    )
+    .await
+    .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
 pub(crate) async fn api_model_list(
     AxumState(state): AxumState<WebServerState>,
 ) -> Result<Json<Value>, ErrorResponse> {
@@ -238,9 +365,8 @@ pub(crate) async fn api_respond_command_execution_approval(
     )
     .map_err(to_error_response)?;
 
-    require_codex(&state)?
-        .codex
-        .send_response(params.request_id, result_value)
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::approvals::respond_to_approval(codex, params.request_id, result_value)
         .await
         .map_err(to_error_response)?;
 
@@ -251,22 +377,77 @@ pub(crate) async fn api_respond_file_change_approval(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<FileChangeApprovalParams>,
 ) -> Result<StatusCode, ErrorResponse> {
-    let result_value = serde_json::to_value(
-        codex_app_server_protocol::FileChangeRequestApprovalResponse {
-            decision: params.decision,
-        },
-    )
-    .map_err(to_error_response)?;
-
-    require_codex(&state)?
-        .codex
-        .send_response(params.request_id, result_value)
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::approvals::respond_to_file_change_approval(codex, params.request_id, params.decision)
         .await
         .map_err(to_error_response)?;
 
     Ok(StatusCode::OK)
 }
 
+pub(crate) async fn api_answer_question(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<AnswerQuestionParams>,
+) -> Result<Json<codexia_codex::questions::PendingQuestion>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let answered = codexia_codex::questions::answer_question(codex, &params.thread_id, &params.text).await?;
+    Ok(Json(answered))
+}
+
+pub(crate) async fn api_respond_to_all_pending(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<RespondAllPendingParams>,
+) -> Result<Json<Vec<codexia_codex::approvals::ApprovalResult>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
+    let results =
+        codexia_codex::approvals::respond_to_all_pending(codex, &params.thread_id, params.decision)
+            .await;
+    Ok(Json(results))
+}
+
+/// Deprecated: bool form of [`api_respond_to_all_pending`].
+pub(crate) async fn api_approve_all_pending(
+    state: AxumState<WebServerState>,
+    Json(params): Json<ApproveAllPendingParams>,
+) -> Result<Json<Vec<codexia_codex::approvals::ApprovalResult>>, ErrorResponse> {
+    let params = RespondAllPendingParams {
+        thread_id: params.thread_id,
+        decision: params.approved.into(),
+    };
+    api_respond_to_all_pending(state, Json(params)).await
+}
+
+pub(crate) async fn api_get_approval_history(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<Vec<codexia_codex::approvals::ApprovalRecord>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let history = codexia_codex::approvals::get_approval_history(codex, &params.thread_id)?;
+    Ok(Json(history))
+}
+
+pub(crate) async fn api_apply_previewed_patches(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<Vec<codexia_codex::approvals::ApprovalResult>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
+    let results = codexia_codex::patches::apply_previewed_patches(codex, &params.thread_id).await;
+    Ok(Json(results))
+}
+
+pub(crate) async fn api_get_pending_patch(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<PendingPatchParams>,
+) -> Result<Json<codexia_codex::patches::PatchPreview>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let preview =
+        codexia_codex::patches::get_pending_patch(codex, &params.thread_id, &params.approval_id)
+            .map_err(to_error_response)?;
+    Ok(Json(preview))
+}
+
 pub(crate) async fn api_respond_user_input(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<UserInputResponseParams>,
@@ -349,3 +530,398 @@ pub(crate) async fn api_unified_read_mcp_config(
         .map_err(to_error_response)?;
     Ok(Json(result))
 }
+
+pub(crate) async fn api_get_usage_stats(
+    Json(params): Json<UsageStatsParams>,
+) -> Result<Json<codexia_codex::usage::UsageStats>, ErrorResponse> {
+    let result = codexia_codex::usage::get_usage_stats(params.from, params.to)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_codex_discovery_diagnostics() -> Json<codexia_codex::discovery::DiscoveryReport> {
+    Json(codexia_codex::discovery::codex_discovery_diagnostics().await)
+}
+
+pub(crate) async fn api_get_codex_help(
+    Json(params): Json<CodexHelpParams>,
+) -> Result<Json<String>, ErrorResponse> {
+    let help = codexia_codex::discovery::get_codex_help(params.subcommand)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(help))
+}
+
+pub(crate) async fn api_bootstrap_check() -> Json<codexia_codex::discovery::BootstrapStatus> {
+    Json(codexia_codex::discovery::bootstrap_check().await)
+}
+
+pub(crate) async fn api_check_codex_version_info(
+) -> Result<Json<codexia_codex::discovery::CodexVersion>, ErrorResponse> {
+    let version = codexia_codex::discovery::check_codex_version_info()
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(version))
+}
+
+pub(crate) async fn api_check_oss_provider(
+    Json(params): Json<OssProviderParams>,
+) -> Result<Json<codexia_codex::connectivity::OssProviderStatus>, ErrorResponse> {
+    Ok(Json(codexia_codex::connectivity::check_oss_provider(params.endpoint).await))
+}
+
+pub(crate) async fn api_migrate_session_file(
+    Json(params): Json<MigrateSessionParams>,
+) -> Result<Json<codexia_codex::migrate::MigrationReport>, ErrorResponse> {
+    let result = codexia_codex::migrate::migrate_session_file(params.file_path, params.dry_run)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+/// A rollout in the sessions directory, as a path string.
+fn web_rollout(file_path: &str) -> Result<String, ErrorResponse> {
+    let rollout = codexia_codex::sessions::session_rollout(file_path).map_err(to_error_response)?;
+    Ok(rollout.to_string_lossy().to_string())
+}
+
+/// A file in the exports directory; the web API cannot name other paths.
+fn web_export_path(path: &str) -> Result<std::path::PathBuf, ErrorResponse> {
+    use codexia_codex::session_export::{confine_to, exports_dir};
+    confine_to(&exports_dir(), path).map_err(to_error_response)
+}
+
+pub(crate) async fn api_export_session_json(
+    Json(params): Json<ExportSessionParams>,
+) -> Result<Json<codexia_codex::session_export::ExportReport>, ErrorResponse> {
+    let file_path = web_rollout(&params.file_path)?;
+    let dest = web_export_path(&params.dest)?.to_string_lossy().to_string();
+    let result = codexia_codex::session_export::export_session_json(file_path, dest)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_export_session_markdown(
+    Json(params): Json<SessionFileParams>,
+) -> Result<Json<String>, ErrorResponse> {
+    let file_path = web_rollout(&params.file_path)?;
+    let markdown = codexia_codex::session_export::export_session_markdown(file_path)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(markdown))
+}
+
+pub(crate) async fn api_import_session_json(
+    Json(params): Json<SessionFileParams>,
+) -> Result<Json<codexia_codex::session_export::ImportReport>, ErrorResponse> {
+    let file_path = web_export_path(&params.file_path)?.to_string_lossy().to_string();
+    let result = codexia_codex::session_export::import_session_json(file_path)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_get_codex_config(
+    AxumState(state): AxumState<WebServerState>,
+) -> Result<Json<codexia_codex::settings::CodexConfig>, ErrorResponse> {
+    let codex = state.codex_state.as_deref().map(|s| s.codex.as_ref());
+    Ok(Json(codexia_codex::settings::current_codex_config(codex)))
+}
+
+pub(crate) async fn api_get_reasoning_capabilities(
+    AxumState(state): AxumState<WebServerState>,
+) -> Result<Json<codexia_codex::reasoning::ReasoningCapabilities>, ErrorResponse> {
+    let codex = state.codex_state.as_deref().map(|s| s.codex.as_ref());
+    let config = codexia_codex::settings::current_codex_config(codex);
+    Ok(Json(codexia_codex::reasoning::reasoning_capabilities(&config)))
+}
+
+pub(crate) async fn api_set_codex_config(
+    AxumState(state): AxumState<WebServerState>,
+    Json(config): Json<codexia_codex::settings::CodexConfig>,
+) -> Result<Json<codexia_codex::settings::CodexConfig>, ErrorResponse> {
+    let codex = state.codex_state.as_deref().map(|s| s.codex.as_ref());
+    let result = codexia_codex::settings::update_codex_config(codex, config)
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_validate_config(
+    AxumState(state): AxumState<WebServerState>,
+    Json(body): Json<ValidateConfigParams>,
+) -> Json<Vec<codexia_codex::validation::ConfigIssue>> {
+    let config = body.config.unwrap_or_else(|| {
+        let codex = state.codex_state.as_deref().map(|s| s.codex.as_ref());
+        codexia_codex::settings::current_codex_config(codex)
+    });
+    Json(codexia_codex::validation::validate_config(&body.params, &config).await)
+}
+
+pub(crate) async fn api_build_codex_command(
+    AxumState(state): AxumState<WebServerState>,
+    Json(body): Json<BuildCommandParams>,
+) -> Result<Json<codexia_codex::launch_args::LaunchArgs>, ErrorResponse> {
+    let config = body.config.unwrap_or_else(|| {
+        let codex = state.codex_state.as_deref().map(|s| s.codex.as_ref());
+        codexia_codex::settings::current_codex_config(codex)
+    });
+    Ok(Json(codexia_codex::app_server::build_codex_command(&config).await?))
+}
+
+pub(crate) async fn api_get_default_config() -> Json<serde_json::Map<String, Value>> {
+    Json(codexia_codex::defaults::get_default_config())
+}
+
+pub(crate) async fn api_list_approval_policies() -> Json<Vec<&'static str>> {
+    Json(codexia_codex::defaults::list_approval_policies())
+}
+
+pub(crate) async fn api_set_default_config(
+    Json(config): Json<Value>,
+) -> Result<Json<serde_json::Map<String, Value>>, ErrorResponse> {
+    let result = codexia_codex::defaults::set_default_config(config).map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_request_review(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::turn::request_review(codex, &params.thread_id)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_start_plan(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<StartPlanParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::turn::start_plan(codex, &params.thread_id, &params.goal)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_fuzzy_find_sessions(
+    Json(params): Json<FuzzyFindSessionsParams>,
+) -> Result<Json<Vec<codexia_codex::session_meta::SessionMatch>>, ErrorResponse> {
+    let result = codexia_codex::session_meta::fuzzy_find_sessions(params.query, params.limit)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_search_sessions(
+    Json(params): Json<SearchSessionsParams>,
+) -> Result<Json<Vec<codexia_codex::session_meta::SearchHit>>, ErrorResponse> {
+    let result = codexia_codex::session_meta::search_sessions(params.query, params.limit)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_load_sessions_from_disk(
+) -> Result<Json<Vec<codexia_codex::session_meta::SessionEntry>>, ErrorResponse> {
+    let result = codexia_codex::session_meta::load_sessions_from_disk()
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_list_session_summaries(
+) -> Result<Json<Vec<codexia_codex::session_meta::SessionSummary>>, ErrorResponse> {
+    let result = codexia_codex::session_meta::list_session_summaries()
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_toggle_session_favorite(
+    Json(params): Json<SessionFileParams>,
+) -> Result<Json<bool>, ErrorResponse> {
+    let favorite = codexia_codex::session_meta::toggle_favorite(&params.file_path)
+        .map_err(to_error_response)?;
+    Ok(Json(favorite))
+}
+
+pub(crate) async fn api_set_session_color(
+    Json(params): Json<SessionColorParams>,
+) -> Result<Json<Option<String>>, ErrorResponse> {
+    let color = codexia_codex::session_meta::set_session_color(&params.file_path, params.color)
+        .map_err(to_error_response)?;
+    Ok(Json(color))
+}
+
+pub(crate) async fn api_set_session_title(
+    Json(params): Json<SessionTitleParams>,
+) -> Result<Json<Option<String>>, ErrorResponse> {
+    let title = codexia_codex::session_meta::set_session_title(&params.session_id, params.title).await?;
+    Ok(Json(title))
+}
+
+pub(crate) async fn api_move_session(
+    Json(params): Json<MoveSessionParams>,
+) -> Result<Json<String>, ErrorResponse> {
+    let path = codexia_codex::sessions::move_session(&params.file_path, &params.dest_dir)
+        .map_err(to_error_response)?;
+    Ok(Json(path))
+}
+
+pub(crate) async fn api_delete_session_files(
+    Json(params): Json<DeleteSessionFilesParams>,
+) -> Result<Json<Vec<codexia_codex::sessions::DeleteResult>>, ErrorResponse> {
+    let results = codexia_codex::sessions::delete_session_files(&params.file_paths)
+        .map_err(to_error_response)?;
+    Ok(Json(results))
+}
+
+pub(crate) async fn api_list_sessions_by_color(
+    Json(params): Json<ColorParams>,
+) -> Result<Json<Vec<codexia_codex::session_meta::SessionEntry>>, ErrorResponse> {
+    let result = codexia_codex::session_meta::list_sessions_by_color(params.color)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_tee_session_to_file(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<TeeSessionParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
+    let dest = web_export_path(&params.dest)?;
+    codex
+        .tees
+        .start(&params.thread_id, &dest, params.include_stderr)
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_untee_session(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<bool>, ErrorResponse> {
+    let stopped = require_codex(&state)?.codex.tees.stop(&params.thread_id).is_some();
+    Ok(Json(stopped))
+}
+
+pub(crate) async fn api_ping_session(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<codexia_codex::thread::ThreadPing>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codexia_codex::thread::ping_thread(codex, &params.thread_id).await))
+}
+
+pub(crate) async fn api_session_status(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<codexia_codex::thread::SessionStatus>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codexia_codex::thread::session_status(codex, &params.thread_id).await))
+}
+
+pub(crate) async fn api_get_recent_events(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<RecentEventsParams>,
+) -> Result<Json<Vec<Value>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let events = codexia_codex::thread::get_recent_events(codex, &params.thread_id, params.limit)?;
+    Ok(Json(events))
+}
+
+pub(crate) async fn api_get_partial_message(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<Option<codexia_codex::threads::PartialMessage>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let partial = codexia_codex::thread::get_partial_message(codex, &params.thread_id)?;
+    Ok(Json(partial))
+}
+
+pub(crate) async fn api_close_all_sessions(
+    AxumState(state): AxumState<WebServerState>,
+) -> Result<Json<Vec<codexia_codex::thread::CloseResult>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codexia_codex::thread::close_all_threads(codex).await))
+}
+
+pub(crate) async fn api_shutdown_all_sessions(
+    AxumState(state): AxumState<WebServerState>,
+) -> Result<Json<codexia_codex::thread::ShutdownSummary>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    Ok(Json(codexia_codex::thread::shutdown_all_sessions(codex).await))
+}
+
+pub(crate) async fn api_get_session_config(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<codexia_codex::thread::ThreadConfig>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::thread::get_thread_config(codex, &params.thread_id)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_get_session_launch_args(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<codexia_codex::launch_args::LaunchArgs>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    let result = codexia_codex::thread::get_session_launch_args(codex, &params.thread_id)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_set_session_working_directory(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<SessionWorkingDirectoryParams>,
+) -> Result<Json<()>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::set_session_working_directory(codex, &params.thread_id, &params.path)?;
+    Ok(Json(()))
+}
+
+pub(crate) async fn api_undo_last_patch(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<ThreadIdParams>,
+) -> Result<Json<Vec<String>>, ErrorResponse> {
+    let codex = &require_codex(&state)?.codex;
+    codexia_codex::thread::require_thread(codex, &params.thread_id)?;
+    let reverted = codexia_codex::patches::undo_last_patch(codex, &params.thread_id)
+        .map_err(to_error_response)?;
+    Ok(Json(reverted))
+}
+
+/// The web server only logs to stderr, so only per-session logs can be
+/// shown.
+fn log_path(state: &WebServerState, thread_id: Option<&str>) -> Result<std::path::PathBuf, ErrorResponse> {
+    let codex = &require_codex(state)?.codex;
+    codexia_codex::log_tail::resolve_log_path(&codex.session_logs, thread_id, None).map_err(to_error_response)
+}
+
+pub(crate) async fn api_get_log_path(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<LogPathParams>,
+) -> Result<Json<std::path::PathBuf>, ErrorResponse> {
+    Ok(Json(log_path(&state, params.thread_id.as_deref())?))
+}
+
+pub(crate) async fn api_stream_log_file(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<LogPathParams>,
+) -> Result<Json<std::path::PathBuf>, ErrorResponse> {
+    let path = log_path(&state, params.thread_id.as_deref())?;
+    let event_sink = std::sync::Arc::new(codexia_shared::event_sink::WebSocketEventSink::new(state.event_tx.clone()));
+    codexia_codex::log_tail::stream_log_file(event_sink, &path).map_err(to_error_response)?;
+    Ok(Json(path))
+}
+
+pub(crate) async fn api_stop_log_stream(Json(params): Json<StopLogStreamParams>) -> Json<bool> {
+    Json(codexia_codex::log_tail::stop_log_stream(&params.path))
+}
+
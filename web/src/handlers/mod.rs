@@ -58,15 +58,22 @@ pub(super) async fn api_allow_sleep(
     Ok(StatusCode::OK)
 }
 
-pub(super) async fn health_check() -> impl IntoResponse {
+pub(super) async fn health_check(AxumState(state): AxumState<WebServerState>) -> impl IntoResponse {
     Json(json!({
-        "status": "ok"
+        "status": "ok",
+        "bindAddress": state.bind_status.bind_address,
+        "warning": state.bind_status.warning,
     }))
 }
 
+pub(super) async fn api_get_saved_remote_ui_config() -> Json<Option<crate::remote_config::RemoteUiConfig>> {
+    Json(crate::remote_config::load_remote_ui_config())
+}
+
 fn to_error_response(err: impl ToString) -> ErrorResponse {
     ErrorResponse {
         error: err.to_string(),
+        code: None,
     }
 }
 
@@ -79,14 +86,22 @@ pub struct SetEnvPayload {
 pub(super) async fn api_model_list_other() -> Result<Json<Value>, ErrorResponse> {
     match load_and_fetch_models().await {
         Ok(models) => Ok(Json(json!(models))),
-        Err(e) => Err(ErrorResponse { error: e }),
+        Err(e) => Err(ErrorResponse { error: e, code: None }),
+    }
+}
+
+pub(super) async fn api_list_available_models(
+) -> Result<Json<Vec<codexia_codex::config::models::ModelInfo>>, ErrorResponse> {
+    match codexia_codex::config::models::list_available_models() {
+        Ok(models) => Ok(Json(models)),
+        Err(e) => Err(ErrorResponse { error: e, code: None }),
     }
 }
 
 pub(super) async fn api_load_env_keys() -> Result<Json<Value>, ErrorResponse> {
     match load_env_keys().await {
         Ok(items) => Ok(Json(json!(items))),
-        Err(e) => Err(ErrorResponse { error: e }),
+        Err(e) => Err(ErrorResponse { error: e, code: None }),
     }
 }
 
@@ -96,6 +111,7 @@ pub(super) async fn api_set_env(
 ) -> Result<StatusCode, ErrorResponse> {
     // Delegate to the same implementation used by the Tauri command
     set_env(payload.key, payload.value)
-        .map_err(|e| ErrorResponse { error: e })?;
+        .await
+        .map_err(|e| ErrorResponse { error: e, code: None })?;
     Ok(StatusCode::OK)
 }
@@ -1,5 +1,5 @@
 use codex_app_server_protocol::{
-    CommandExecutionApprovalDecision, FileChangeApprovalDecision, RequestId,
+    CommandExecutionApprovalDecision, FileChangeApprovalDecision, RequestId, ThreadStartParams,
     ToolRequestUserInputResponse,
 };
 use serde::Deserialize;
@@ -15,6 +15,55 @@ pub(crate) struct CommandExecutionApprovalParams {
     pub(crate) decision: CommandExecutionApprovalDecision,
 }
 
+#[derive(Deserialize)]
+pub(crate) struct StartThreadBody {
+    #[serde(default, alias = "startId")]
+    pub(crate) start_id: Option<String>,
+    #[serde(flatten)]
+    pub(crate) params: ThreadStartParams,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct HandoffParams {
+    #[serde(alias = "sourceThreadId")]
+    pub(crate) source_thread_id: String,
+    pub(crate) params: ThreadStartParams,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ResumeSessionParams {
+    #[serde(alias = "sessionId")]
+    pub(crate) session_id: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CancelStartParams {
+    #[serde(alias = "startId")]
+    pub(crate) start_id: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ApproveAllPendingParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) approved: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RespondAllPendingParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) decision: codexia_codex::approvals::ApprovalDecision,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PendingPatchParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    #[serde(alias = "approvalId")]
+    pub(crate) approval_id: RequestId,
+}
+
 #[derive(Deserialize)]
 pub(crate) struct FileChangeApprovalParams {
     pub(crate) request_id: RequestId,
@@ -354,3 +403,207 @@ pub(crate) struct InsightFiltersParams {
     #[serde(default)]
     pub(crate) agent: Option<String>,
 }
+
+#[derive(Deserialize)]
+pub(crate) struct SessionFileParams {
+    #[serde(alias = "filePath")]
+    pub(crate) file_path: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SessionTitleParams {
+    #[serde(alias = "sessionId")]
+    pub(crate) session_id: String,
+    #[serde(default)]
+    pub(crate) title: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SessionColorParams {
+    #[serde(alias = "filePath")]
+    pub(crate) file_path: String,
+    #[serde(default)]
+    pub(crate) color: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ColorParams {
+    pub(crate) color: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MigrateSessionParams {
+    #[serde(alias = "filePath")]
+    pub(crate) file_path: String,
+    #[serde(default, alias = "dryRun")]
+    pub(crate) dry_run: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MoveSessionParams {
+    #[serde(alias = "filePath")]
+    pub(crate) file_path: String,
+    #[serde(alias = "destDir")]
+    pub(crate) dest_dir: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct DeleteSessionFilesParams {
+    #[serde(alias = "filePaths")]
+    pub(crate) file_paths: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ExportSessionParams {
+    #[serde(alias = "filePath")]
+    pub(crate) file_path: String,
+    pub(crate) dest: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StartPlanParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) goal: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TeeSessionParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) dest: String,
+    #[serde(default, alias = "includeStderr")]
+    pub(crate) include_stderr: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FuzzyFindSessionsParams {
+    pub(crate) query: String,
+    #[serde(default)]
+    pub(crate) limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SearchSessionsParams {
+    pub(crate) query: String,
+    #[serde(default)]
+    pub(crate) limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct OssProviderParams {
+    #[serde(default)]
+    pub(crate) endpoint: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UsageStatsParams {
+    #[serde(default)]
+    pub(crate) from: Option<String>,
+    #[serde(default)]
+    pub(crate) to: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RecentEventsParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    #[serde(default)]
+    pub(crate) limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CodexHelpParams {
+    #[serde(default)]
+    pub(crate) subcommand: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AttachFilesParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) paths: Vec<String>,
+    #[serde(default)]
+    pub(crate) message: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SendInputItemsParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) items: Vec<codexia_codex::attachments::InputItemPayload>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LogPathParams {
+    #[serde(default, alias = "threadId")]
+    pub(crate) thread_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct StopLogStreamParams {
+    pub(crate) path: std::path::PathBuf,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SendMessageParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) message: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SessionConfigParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) config: codexia_codex::turn::SessionConfigUpdate,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InterruptAndSendParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) message: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SendWithModelParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) message: String,
+    pub(crate) model: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ThreadIdParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SessionWorkingDirectoryParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) path: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BuildCommandParams {
+    #[serde(default)]
+    pub(crate) config: Option<codexia_codex::settings::CodexConfig>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ValidateConfigParams {
+    #[serde(default)]
+    pub(crate) params: Value,
+    #[serde(default)]
+    pub(crate) config: Option<codexia_codex::settings::CodexConfig>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AnswerQuestionParams {
+    #[serde(alias = "threadId")]
+    pub(crate) thread_id: String,
+    pub(crate) text: String,
+}
@@ -1,5 +1,7 @@
 pub mod watcher;
+pub mod bind;
 mod handlers;
+pub mod remote_config;
 pub mod router;
 mod server;
 pub mod terminal;
@@ -19,7 +21,13 @@ pub fn start_server(host: &str, port: u16) {
     let host = host.to_string();
 
     // Open the browser after a short delay to let the server bind first.
-    let open_url = format!("http://{}:{}", if host == "0.0.0.0" { "127.0.0.1" } else { &host }, port);
+    let open_host = match bind::resolve_bind_host(&host) {
+        Ok(ip) if ip.is_unspecified() || ip.is_loopback() => "127.0.0.1".to_string(),
+        Ok(std::net::IpAddr::V6(ip)) => format!("[{}]", ip),
+        Ok(ip) => ip.to_string(),
+        Err(_) => host.clone(),
+    };
+    let open_url = format!("http://{}:{}", open_host, port);
     std::thread::spawn(move || {
         std::thread::sleep(Duration::from_millis(500));
         if let Err(e) = open::that(&open_url) {
@@ -1,10 +1,20 @@
+use codexia_web::bind::DEFAULT_BIND_HOST;
+use codexia_web::remote_config::load_remote_ui_config;
+
 const DEFAULT_WEB_PORT: u16 = 7420;
 
+/// Flags win over environment variables, which win over the address the UI
+/// was last served on.
 fn parse_web_options() -> (String, u16) {
-    let host = "127.0.0.1".to_string();
+    let saved = load_remote_ui_config();
+    let mut host = std::env::var("CODEXIA_WEB_HOST")
+        .ok()
+        .or_else(|| saved.as_ref().map(|config| config.host.clone()))
+        .unwrap_or_else(|| DEFAULT_BIND_HOST.to_string());
     let mut port: u16 = std::env::var("VITE_WEB_PORT")
         .ok()
         .and_then(|v| v.parse().ok())
+        .or_else(|| saved.as_ref().map(|config| config.port))
         .unwrap_or(DEFAULT_WEB_PORT);
 
     let mut args = std::env::args().skip(1).peekable();
@@ -17,6 +27,16 @@ fn parse_web_options() -> (String, u16) {
                     }
                 }
             }
+            "--host" | "--bind" => {
+                if let Some(v) = args.next() {
+                    host = v;
+                }
+            }
+            _ if arg.starts_with("--host=") || arg.starts_with("--bind=") => {
+                if let Some(v) = arg.split_once('=').map(|(_, v)| v) {
+                    host = v.to_string();
+                }
+            }
             _ if arg.starts_with("--web-port=") => {
                 if let Some(v) = arg.split('=').nth(1) {
                     if let Ok(p) = v.parse::<u16>() {
@@ -0,0 +1,125 @@
+//! The host and port the web UI was last served on, kept in
+//! `~/.codexia/remote_ui.json` so a restart without flags comes back on
+//! the same address.
+//!
+//! Command-line flags and environment variables still win; the saved
+//! values only replace the built-in defaults. A host reachable beyond this
+//! machine is only saved when [`REMEMBER_HOST_ENV`] is set, so a one-off
+//! `--host lan` does not expose the unauthenticated UI on every later
+//! launch.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteUiConfig {
+    /// `localhost`, `lan` or an IP address, as accepted by
+    /// [`crate::bind::resolve_bind_host`].
+    pub host: String,
+    pub port: u16,
+}
+
+/// Set to `1` to save a non-loopback host for later launches.
+pub const REMEMBER_HOST_ENV: &str = "CODEXIA_WEB_REMEMBER_HOST";
+
+pub fn remember_host_opted_in() -> bool {
+    std::env::var(REMEMBER_HOST_ENV).is_ok_and(|value| matches!(value.trim(), "1" | "true"))
+}
+
+/// What to save after serving on `host`: the host itself when it is
+/// loopback or `remember_host` is set, otherwise the previously saved host
+/// (or localhost), with the port either way.
+pub fn config_to_save(
+    host: &str,
+    loopback: bool,
+    port: u16,
+    saved: Option<&RemoteUiConfig>,
+    remember_host: bool,
+) -> RemoteUiConfig {
+    let host = if loopback || remember_host {
+        host.to_string()
+    } else {
+        saved
+            .map(|config| config.host.clone())
+            .unwrap_or_else(|| crate::bind::DEFAULT_BIND_HOST.to_string())
+    };
+    RemoteUiConfig { host, port }
+}
+
+pub fn remote_ui_config_path() -> PathBuf {
+    codexia_codex::utils::codexia_dir().join("remote_ui.json")
+}
+
+fn read_config(path: &Path) -> Option<RemoteUiConfig> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|err| log::warn!("[web] ignoring malformed {}: {}", path.display(), err))
+        .ok()
+}
+
+/// Written readable by the owner only, as it tells where the
+/// unauthenticated UI can be reached.
+fn write_config(path: &Path, config: &RemoteUiConfig) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// The saved config, `None` when nothing was saved yet.
+pub fn load_remote_ui_config() -> Option<RemoteUiConfig> {
+    read_config(&remote_ui_config_path())
+}
+
+pub fn save_remote_ui_config(config: &RemoteUiConfig) -> Result<(), String> {
+    write_config(&remote_ui_config_path(), config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_with_owner_only_permissions() {
+        let dir = std::env::temp_dir().join(format!("codexia-remote-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("remote_ui.json");
+        assert_eq!(read_config(&path), None);
+
+        let config = RemoteUiConfig {
+            host: "lan".to_string(),
+            port: 7421,
+        };
+        write_config(&path, &config).unwrap();
+        assert_eq!(read_config(&path), Some(config));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(read_config(&path), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_one_off_lan_hosts_out_of_the_saved_config() {
+        let saved = RemoteUiConfig {
+            host: "localhost".to_string(),
+            port: 7420,
+        };
+        assert_eq!(config_to_save("lan", false, 7421, Some(&saved), false).host, "localhost");
+        assert_eq!(config_to_save("lan", false, 7421, None, false).host, crate::bind::DEFAULT_BIND_HOST);
+        assert_eq!(config_to_save("lan", false, 7421, Some(&saved), true).host, "lan");
+        assert_eq!(config_to_save("::1", true, 7421, None, false), RemoteUiConfig { host: "::1".to_string(), port: 7421 });
+    }
+}
@@ -36,22 +36,23 @@ use super::{
         api_read_dxt_setting,
         api_read_pdf_content, api_read_text_file_lines,
         api_read_xlsx_content, api_respond_command_execution_approval,
-        api_respond_file_change_approval, api_respond_user_input, api_resume_thread,
+        api_respond_file_change_approval, api_approve_all_pending, api_respond_to_all_pending, api_get_pending_patch, api_apply_previewed_patches, api_get_approval_history, api_respond_user_input, api_answer_question, api_resume_thread, api_resume_codex_session,
         api_rollback_thread, api_fork_thread,
         api_save_dxt_setting,
         api_search_files, api_search_files_by_name, api_skills_config_write, api_skills_list, api_start_review,
-        api_start_thread, api_watch_directory, api_unwatch_directory,
+        api_start_thread, api_cancel_session_start, api_handoff_session, api_watch_directory, api_unwatch_directory,
         api_skill_groups_read, api_skill_groups_write,
         api_skills_clone_repo, api_skills_delete_central, api_skills_install_marketplace,
         api_skills_link_to_agent, api_skills_list_central, api_skills_list_installed,
         api_skills_list_marketplace, api_skills_uninstall_installed,
         api_skillssh_install, api_skillssh_leaderboard, api_skillssh_search,
         api_terminal_resize, api_terminal_start, api_terminal_stop,
-        api_terminal_write, api_toggle_favorite, api_turn_interrupt, api_turn_start,
+        api_terminal_write, api_toggle_favorite, api_turn_interrupt, api_interrupt_session, api_interrupt_and_send, api_send_message_with_model, api_attach_files, api_send_user_input_with_items, api_send_message, api_get_queued_messages, api_clear_queue, api_update_session_config, api_turn_start,
         api_unified_add_mcp_server, api_unified_disable_mcp_server,
         api_unified_enable_mcp_server, api_unified_read_mcp_config,
         api_unified_remove_mcp_server, api_update_note, api_write_file,
-        api_get_settings_file, api_save_settings_file, health_check, api_model_list_other, api_load_env_keys, api_set_env,
+        api_get_settings_file, api_save_settings_file, health_check, api_get_usage_stats, api_check_oss_provider, api_codex_discovery_diagnostics, api_get_log_path, api_stream_log_file, api_stop_log_stream, api_bootstrap_check, api_check_codex_version_info, api_get_codex_help, api_migrate_session_file, api_export_session_json, api_export_session_markdown, api_import_session_json, api_tee_session_to_file, api_fuzzy_find_sessions, api_search_sessions, api_load_sessions_from_disk, api_list_session_summaries, api_toggle_session_favorite, api_set_session_color, api_set_session_title, api_list_sessions_by_color, api_move_session, api_delete_session_files, api_untee_session, api_get_codex_config, api_set_codex_config, api_validate_config, api_build_codex_command, api_get_reasoning_capabilities, api_get_default_config, api_set_default_config, api_list_approval_policies, api_request_review, api_start_plan, api_ping_session, api_session_status, api_get_recent_events, api_get_partial_message, api_close_all_sessions, api_shutdown_all_sessions, api_get_session_launch_args, api_set_session_working_directory, api_undo_last_patch,
+        api_get_session_config, api_model_list_other, api_list_available_models, api_load_env_keys, api_set_env, api_get_saved_remote_ui_config,
     },
     types::WebServerState,
     websocket::{sse_handler, ws_handler},
@@ -99,11 +100,15 @@ pub fn create_router(state: WebServerState) -> Router {
 
     Router::new()
         .route("/health", get(health_check))
+        .route("/api/remote-ui/config", get(api_get_saved_remote_ui_config))
         .route("/ws", get(ws_handler))
         .route("/api/events", get(sse_handler))
         .route("/api/codex/thread/start", post(api_start_thread))
         .route("/api/codex/start-thread", post(api_start_thread))
+        .route("/api/codex/thread/cancel-start", post(api_cancel_session_start))
+        .route("/api/codex/thread/handoff", post(api_handoff_session))
         .route("/api/codex/thread/resume", post(api_resume_thread))
+        .route("/api/codex/session/resume", post(api_resume_codex_session))
         .route("/api/codex/thread/fork", post(api_fork_thread))
         .route("/api/codex/thread/rollback", post(api_rollback_thread))
         .route("/api/codex/thread/list", post(api_list_threads))
@@ -111,11 +116,21 @@ pub fn create_router(state: WebServerState) -> Router {
         .route("/api/codex/thread/unarchive", post(api_unarchive_thread))
         .route("/api/codex/turn/start", post(api_turn_start))
         .route("/api/codex/turn/interrupt", post(api_turn_interrupt))
+        .route("/api/codex/session/interrupt", post(api_interrupt_session))
+        .route("/api/codex/turn/interrupt-and-send", post(api_interrupt_and_send))
+        .route("/api/codex/turn/send-with-model", post(api_send_message_with_model))
+        .route("/api/codex/turn/attach-files", post(api_attach_files))
+        .route("/api/codex/turn/send-items", post(api_send_user_input_with_items))
+        .route("/api/codex/turn/send", post(api_send_message))
+        .route("/api/codex/turn/queue", post(api_get_queued_messages))
+        .route("/api/codex/turn/queue/clear", post(api_clear_queue))
+        .route("/api/codex/session/config/update", post(api_update_session_config))
         .route(
             "/api/codex/model/list",
             get(api_model_list).post(api_model_list_post),
         )
         .route("/api/codex/model/list-other", get(api_model_list_other))
+        .route("/api/codex/model/available", get(api_list_available_models))
         .route("/api/codex/load_env_keys", get(api_load_env_keys))
         .route("/api/codex/set_env", post(api_set_env))
         .route(
@@ -141,7 +156,65 @@ pub fn create_router(state: WebServerState) -> Router {
             "/api/codex/approval/user-input",
             post(api_respond_user_input),
         )
+        .route("/api/codex/question/answer", post(api_answer_question))
+        .route(
+            "/api/codex/approval/approve-all",
+            post(api_approve_all_pending),
+        )
+        .route("/api/codex/approval/respond-all", post(api_respond_to_all_pending))
+        .route("/api/codex/approval/pending-patch", post(api_get_pending_patch))
+        .route("/api/codex/approval/apply-previewed", post(api_apply_previewed_patches))
+        .route("/api/codex/approval/history", post(api_get_approval_history))
         .route("/api/codex/review/start", post(api_start_review))
+        .route("/api/codex/review/request", post(api_request_review))
+        .route("/api/codex/plan/start", post(api_start_plan))
+        .route("/api/codex/usage/stats", post(api_get_usage_stats))
+        .route("/api/codex/oss/check", post(api_check_oss_provider))
+        .route("/api/codex/discovery", get(api_codex_discovery_diagnostics))
+        .route("/api/log/path", post(api_get_log_path))
+        .route("/api/log/stream", post(api_stream_log_file))
+        .route("/api/log/stream/stop", post(api_stop_log_stream))
+        .route("/api/codex/bootstrap", get(api_bootstrap_check))
+        .route("/api/codex/version", get(api_check_codex_version_info))
+        .route("/api/codex/help", post(api_get_codex_help))
+        .route("/api/codex/session/migrate", post(api_migrate_session_file))
+        .route("/api/codex/session/export-json", post(api_export_session_json))
+        .route("/api/codex/session/export-markdown", post(api_export_session_markdown))
+        .route("/api/codex/session/import-json", post(api_import_session_json))
+        .route("/api/codex/session/tee", post(api_tee_session_to_file))
+        .route("/api/codex/session/untee", post(api_untee_session))
+        .route("/api/codex/session/fuzzy-find", post(api_fuzzy_find_sessions))
+        .route("/api/codex/session/search", post(api_search_sessions))
+        .route("/api/codex/session/list-disk", get(api_load_sessions_from_disk))
+        .route("/api/codex/session/summaries", get(api_list_session_summaries))
+        .route("/api/codex/session/favorite", post(api_toggle_session_favorite))
+        .route("/api/codex/session/color", post(api_set_session_color))
+        .route("/api/codex/session/title", post(api_set_session_title))
+        .route("/api/codex/session/list-by-color", post(api_list_sessions_by_color))
+        .route("/api/codex/session/move", post(api_move_session))
+        .route("/api/codex/session/delete-files", post(api_delete_session_files))
+        .route(
+            "/api/codex/settings",
+            get(api_get_codex_config).post(api_set_codex_config),
+        )
+        .route("/api/codex/settings/validate", post(api_validate_config))
+        .route("/api/codex/settings/build-command", post(api_build_codex_command))
+        .route("/api/codex/settings/reasoning", get(api_get_reasoning_capabilities))
+        .route(
+            "/api/codex/default-config",
+            get(api_get_default_config).post(api_set_default_config),
+        )
+        .route("/api/codex/approval-policies", get(api_list_approval_policies))
+        .route("/api/codex/session/ping", post(api_ping_session))
+        .route("/api/codex/session/status", post(api_session_status))
+        .route("/api/codex/session/recent-events", post(api_get_recent_events))
+        .route("/api/codex/session/partial-message", post(api_get_partial_message))
+        .route("/api/codex/session/close-all", post(api_close_all_sessions))
+        .route("/api/codex/session/shutdown-all", post(api_shutdown_all_sessions))
+        .route("/api/codex/session/config", post(api_get_session_config))
+        .route("/api/codex/session/launch-args", post(api_get_session_launch_args))
+        .route("/api/codex/session/working-directory", post(api_set_session_working_directory))
+        .route("/api/codex/session/undo-patch", post(api_undo_last_patch))
         .route("/api/filesystem/read-directory", post(api_read_directory))
         .route("/api/filesystem/home-directory", get(api_get_home_directory))
         .route("/api/filesystem/canonicalize-path", post(api_canonicalize_path))
@@ -5,11 +5,12 @@ use std::time::Instant;
 use serde_json::Value;
 use tokio::sync::broadcast;
 
-use super::{router::create_router, types::WebServerState};
+use super::{bind::{BindStatus, resolve_bind_host}, router::create_router, types::WebServerState};
 use codexia_cc::CCState;
 use codexia_codex::{AppState, CodexInitializationState, connect_codex, initialize_codex};
 use codexia_shared::event_sink::{EventSink, WebSocketEventSink};
 use codexia_shared::sleep::SleepState;
+use crate::remote_config::{config_to_save, load_remote_ui_config, remember_host_opted_in, save_remote_ui_config};
 use crate::watcher::WebWatchState;
 
 pub async fn start_web_server_with_events(
@@ -25,6 +26,8 @@ pub async fn start_web_server_with_events(
         log::info!("[web] startup cwd: <unavailable>");
     }
     log::info!("[web] requested port: {}", port);
+    let bind_ip = resolve_bind_host(host)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
 
     let automation_sink: Arc<dyn EventSink> = Arc::new(WebSocketEventSink::new(event_tx.clone()));
     codexia_cc::automation::initialize_automation_runtime(
@@ -37,6 +40,27 @@ pub async fn start_web_server_with_events(
 
     codexia_cc::scan::start_session_scanner();
 
+    let listener = tokio::net::TcpListener::bind((bind_ip, port)).await?;
+    let local_addr = listener.local_addr()?;
+    // A non-loopback host is only remembered on request, so a one-off LAN
+    // launch does not become the default.
+    let remote_config = config_to_save(
+        host,
+        bind_ip.is_loopback(),
+        local_addr.port(),
+        load_remote_ui_config().as_ref(),
+        remember_host_opted_in(),
+    );
+    if let Err(err) = save_remote_ui_config(&remote_config) {
+        log::warn!("[web] failed to save the remote UI address: {}", err);
+    }
+    let bind_status = BindStatus::new(local_addr, Some(&remote_config.host));
+    log::info!("Web server listening on http://{}", bind_status.bind_address);
+    if let Some(warning) = &bind_status.warning {
+        log::warn!("[web] {}", warning);
+    }
+
+    let shutdown_codex = codex_state.clone();
     let state = WebServerState {
         codex_state,
         cc_state,
@@ -44,18 +68,57 @@ pub async fn start_web_server_with_events(
         terminal_state: Arc::new(super::terminal::WebTerminalState::default()),
         fs_watch_state: Arc::new(WebWatchState::default()),
         event_tx,
+        bind_status: Arc::new(bind_status),
     };
 
     let app = create_router(state);
-    let listener = tokio::net::TcpListener::bind(format!("{}:{}", host, port)).await?;
 
-    log::info!("Web server listening on http://{}:{}", host, port);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
 
-    axum::serve(listener, app).await?;
+    if let Some(codex_state) = shutdown_codex {
+        let report = codex_state.codex.shutdown().await;
+        if report.outcome.is_forced() {
+            log::warn!("[web] codex did not exit on its own ({:?}); its last session may be incomplete", report.outcome);
+        }
+        log::info!("[web] shut down {} codex sessions", report.sessions);
+    }
 
     Ok(())
 }
 
+/// Resolves on Ctrl+C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            log::warn!("[web] failed to listen for Ctrl+C: {}", err);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(err) => {
+                log::warn!("[web] failed to listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    log::info!("[web] shutdown signal received");
+}
+
 pub async fn start_web_server(host: &str, port: u16) -> Result<(), Box<dyn std::error::Error>> {
     let boot_started_at = Instant::now();
     let (event_tx, _) = broadcast::channel(100);
@@ -12,7 +12,9 @@ use tokio::sync::broadcast;
 
 use codexia_cc::CCState;
 use codexia_codex::AppState;
+use codexia_codex::error::CodexiaError;
 use codexia_shared::sleep::SleepState;
+use crate::bind::BindStatus;
 use crate::watcher::WebWatchState;
 use crate::terminal::WebTerminalState;
 
@@ -24,6 +26,7 @@ pub struct WebServerState {
     pub(crate) terminal_state: Arc<WebTerminalState>,
     pub(crate) fs_watch_state: Arc<WebWatchState>,
     pub event_tx: broadcast::Sender<(String, Value)>,
+    pub(crate) bind_status: Arc<BindStatus>,
 }
 
 impl WebServerState {
@@ -34,8 +37,17 @@ impl WebServerState {
         terminal_state: Arc<WebTerminalState>,
         fs_watch_state: Arc<WebWatchState>,
         event_tx: broadcast::Sender<(String, Value)>,
+        bind_status: BindStatus,
     ) -> Self {
-        Self { codex_state, cc_state, sleep_state, terminal_state, fs_watch_state, event_tx }
+        Self {
+            codex_state,
+            cc_state,
+            sleep_state,
+            terminal_state,
+            fs_watch_state,
+            event_tx,
+            bind_status: Arc::new(bind_status),
+        }
     }
 }
 
@@ -48,6 +60,18 @@ impl FromRef<WebServerState> for broadcast::Sender<(String, Value)> {
 #[derive(Serialize)]
 pub(super) struct ErrorResponse {
     pub(super) error: String,
+    /// [`CodexiaError::code`] when the failure has a typed cause.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) code: Option<&'static str>,
+}
+
+impl From<CodexiaError> for ErrorResponse {
+    fn from(err: CodexiaError) -> Self {
+        Self {
+            error: err.to_string(),
+            code: Some(err.code()),
+        }
+    }
 }
 
 impl IntoResponse for ErrorResponse {